@@ -0,0 +1,62 @@
+//! Drives `spwm::step()` with arbitrary duty cycle and frequency update sequences.
+//!
+//! Every input byte pair is an operation: `0` sets the duty cycle, `1` sets the period length,
+//! `2` toggles the ratio lock, `3` schedules a delayed trigger and anything else runs a number
+//! of ticks. Period starts are emulated the way `SpwmChannel::process_tick()` handles them.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use spwm::{EngineState, TickEvent, step};
+
+fuzz_target!(|ops: &[u8]| {
+    let mut state = EngineState {
+        period_ticks: 100,
+        ..EngineState::default()
+    };
+    let mut duty_cycle = 0;
+    let mut high_ticks = 0u32;
+    let mut stable = false;
+
+    for op in ops.chunks_exact(2) {
+        let (kind, value) = (op[0], op[1]);
+
+        match kind {
+            0 => duty_cycle = u32::from(value % 101),
+            1 => state.period_ticks = u32::from(value) * 4,
+            2 => state.locked = !state.locked,
+            3 => state.trigger_countdown = u32::from(value) + 1,
+            _ => {
+                for _ in 0..value {
+                    let due = !state.locked && state.counter + 1 >= state.period_ticks.max(2);
+                    let (next, event) = step(state);
+
+                    assert!(due <= (event == TickEvent::PeriodEnd));
+                    assert!(event != TickEvent::Off || state.waveform_on);
+                    assert!(state.locked || next.counter < state.period_ticks.max(2));
+
+                    if state.waveform_on && event != TickEvent::Start {
+                        high_ticks += 1;
+                    }
+
+                    state = next;
+
+                    if matches!(event, TickEvent::Start | TickEvent::PeriodEnd) {
+                        if event == TickEvent::PeriodEnd && stable && !state.locked {
+                            assert_eq!(high_ticks, state.on_ticks.min(state.period_ticks.max(2)));
+                        }
+
+                        state.on_ticks = state.period_ticks / 100 * duty_cycle;
+                        state.waveform_on = state.on_ticks != 0;
+                        high_ticks = 0;
+                        stable = true;
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        stable = false;
+    }
+});
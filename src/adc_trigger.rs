@@ -0,0 +1,109 @@
+//! ADC trigger outputs aligned to the period of another channel.
+//!
+//! Sampling a current or voltage while a switching edge rings gives noisy readings, so SMPS and
+//! motor controllers trigger their ADC at a quiet point of the PWM period, typically the middle
+//! of the on- or off-time. An ADC trigger channel is ratio-locked to the PWM channel and emits
+//! a short fixed pulse at that point of every period, re-derived from the on-time of the PWM
+//! channel at every period boundary so it follows duty cycle changes.
+
+use crate::{ChannelId, Spwm, SpwmError};
+
+/// Point within the period of the PWM channel at which the ADC trigger pulse starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdcTriggerPoint {
+    /// Fixed number of ticks after the period start
+    Ticks(u32),
+    /// Middle of the on-time
+    MidOn,
+    /// Middle of the off-time
+    MidOff,
+}
+
+impl AdcTriggerPoint {
+    /// Returns the ticks from the period start to the trigger pulse for a period of
+    /// `period_ticks` with `on_ticks` on-time, kept within the period.
+    pub(crate) fn delay_ticks(self, on_ticks: u32, period_ticks: u32) -> u32 {
+        let on_ticks = on_ticks.min(period_ticks);
+        let delay_ticks = match self {
+            AdcTriggerPoint::Ticks(ticks) => ticks,
+            AdcTriggerPoint::MidOn => on_ticks / 2,
+            AdcTriggerPoint::MidOff => {
+                on_ticks.saturating_add(period_ticks.saturating_sub(on_ticks) / 2)
+            }
+        };
+
+        delay_ticks.min(period_ticks.saturating_sub(1))
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Turns a channel into an ADC trigger output aligned to the period of a PWM channel.
+    ///
+    /// The trigger channel is locked to the PWM channel with the same period and produces one
+    /// pulse of `pulse_ticks` per period, starting at `point`. Its frequency and duty cycle are
+    /// replaced and duty cycle updates are rejected; `unchain()` releases the lock.
+    ///
+    /// # Parameters
+    /// - `pwm_id`: The identifier of the PWM channel
+    /// - `trigger_id`: The identifier of the ADC trigger channel
+    /// - `point`: Point within the PWM period at which the pulse starts
+    /// - `pulse_ticks`: Width of the trigger pulse in ticks
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if either channel is not registered or both are the same
+    /// - `SpwmError::InvalidPulseWidth` if the pulse is 0 ticks or, like a `Ticks` point, not
+    ///   shorter than the PWM period
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{AdcTriggerPoint, Spwm};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<2>::new(1_000_000);
+    /// let mut ids = [0; 2];
+    ///
+    /// for id in &mut ids {
+    ///     let channel = spwm.create_channel()
+    ///         .freq_hz(10_000)
+    ///         .duty_cycle(30)
+    ///         .on_off_callback(|_| {})
+    ///         .period_callback(|| {})
+    ///         .build()?;
+    ///     *id = spwm.register_channel(channel)?;
+    /// }
+    ///
+    /// // 1 µs ADC trigger in the middle of the off-time
+    /// spwm.align_adc_trigger(ids[0], ids[1], AdcTriggerPoint::MidOff, 1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn align_adc_trigger(
+        &mut self,
+        pwm_id: ChannelId,
+        trigger_id: ChannelId,
+        point: AdcTriggerPoint,
+        pulse_ticks: u32,
+    ) -> Result<(), SpwmError> {
+        if pwm_id == trigger_id {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        let period_ticks = self
+            .get_channel(pwm_id)
+            .ok_or(SpwmError::InvalidChannel)?
+            .effective_period_ticks();
+        let trigger = self
+            .get_channel(trigger_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if pulse_ticks == 0
+            || pulse_ticks >= period_ticks
+            || matches!(point, AdcTriggerPoint::Ticks(ticks) if ticks >= period_ticks)
+        {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        trigger.set_trigger_output(period_ticks, pulse_ticks);
+        self.lock_with_phase(pwm_id, trigger_id, 1, (0, 1))?;
+        self.align_locked(trigger_id, point)
+    }
+}
@@ -0,0 +1,116 @@
+//! Software alarms.
+//!
+//! `irq_handler()` already runs at the known hardware timer frequency, so it also fires one-shot
+//! and periodic software alarms. Small firmware can schedule timeouts and housekeeping tasks
+//! without a dedicated timer queue. The number of alarm slots is the `A` parameter of
+//! `Spwm<N, A>`, which defaults to none. Alarms are `TickScheduler` compare events on the
+//! manager tick counter.
+
+use crate::scheduler::MAX_COMPARE_TICKS;
+use crate::{Spwm, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Callback invoked from `irq_handler()` when an alarm expires.
+pub type AlarmCallback = fn();
+
+/// Unique identifier for a set alarm.
+pub type AlarmId = usize;
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Sets a one-shot alarm expiring after `ticks` hardware timer ticks.
+    ///
+    /// The callback is invoked once from the first `irq_handler()` call at or after the
+    /// deadline, after the channels were processed, so it must be short. Alarms do not expire
+    /// while the manager is suspended.
+    ///
+    /// # Parameters
+    /// - `ticks`: Number of ticks until the alarm expires
+    /// - `callback`: Callback invoked when the alarm expires
+    ///
+    /// # Returns
+    /// The identifier of the alarm.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidAlarm` if `ticks` is not less than 2^31
+    /// - `SpwmError::NoAlarmSlotAvailable` if all `A` alarm slots are in use
+    pub fn set_alarm(&mut self, ticks: u32, callback: AlarmCallback) -> Result<AlarmId, SpwmError> {
+        self.insert_alarm(ticks, 0, callback)
+    }
+
+    /// Sets a periodic alarm expiring every `period_ticks` hardware timer ticks.
+    ///
+    /// The first expiration is `period_ticks` after this call. If several periods elapsed
+    /// within one `irq_handler()` call (e.g. with a tick divider), the callback is invoked once.
+    ///
+    /// # Parameters
+    /// - `period_ticks`: Number of ticks between expirations
+    /// - `callback`: Callback invoked when the alarm expires
+    ///
+    /// # Returns
+    /// The identifier of the alarm.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidAlarm` if `period_ticks` is 0 or not less than 2^31
+    /// - `SpwmError::NoAlarmSlotAvailable` if all `A` alarm slots are in use
+    pub fn set_periodic_alarm(
+        &mut self,
+        period_ticks: u32,
+        callback: AlarmCallback,
+    ) -> Result<AlarmId, SpwmError> {
+        if period_ticks == 0 {
+            return Err(SpwmError::InvalidAlarm);
+        }
+
+        self.insert_alarm(period_ticks, period_ticks, callback)
+    }
+
+    /// Cancels a pending alarm.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the alarm
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidAlarm` if the alarm is not pending (never set, already
+    /// expired or cancelled).
+    pub fn cancel_alarm(&mut self, id: AlarmId) -> Result<(), SpwmError> {
+        self.alarms.remove_compare(id)
+    }
+
+    /// Returns whether an alarm is pending, i.e. set and neither expired nor cancelled.
+    ///
+    /// Periodic alarms stay pending until cancelled.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the alarm
+    pub fn is_alarm_pending(&self, id: AlarmId) -> bool {
+        self.alarms.is_pending(id)
+    }
+
+    /// Stores an alarm into a free slot.
+    fn insert_alarm(
+        &mut self,
+        ticks: u32,
+        period: u32,
+        callback: AlarmCallback,
+    ) -> Result<AlarmId, SpwmError> {
+        if ticks >= MAX_COMPARE_TICKS {
+            return Err(SpwmError::InvalidAlarm);
+        }
+
+        let id = self
+            .alarms
+            .add_compare(self.ticks.low().wrapping_add(ticks), period, callback)?;
+        // The new deadline may precede the next scheduled event
+        self.idle_ticks.store(0, Ordering::Relaxed);
+
+        Ok(id)
+    }
+
+    /// Invokes the callbacks of the alarms expired at `tick`.
+    ///
+    /// # Returns
+    /// Ticks after `tick` before the next alarm expires, or `u32::MAX` if no alarm is pending.
+    pub(crate) fn process_alarms(&self, tick: u32) -> u32 {
+        self.alarms.process(tick)
+    }
+}
@@ -0,0 +1,296 @@
+//! Bit-stream output mode for SPWM channels.
+//!
+//! With Manchester and biphase mark codes every channel period carries one half-bit of a
+//! line-coded byte buffer: the period is either fully on or fully off, so the channel frequency
+//! has to be twice the bit rate. With the pulse-width code every period carries a whole bit whose
+//! high time selects its value, as used by single-wire protocols of addressable LEDs.
+//!
+//! Bytes are sent MSB first. Once the buffer is sent the channel returns to its configured duty
+//! cycle and the transmit complete callback is invoked.
+
+use crate::{SpwmChannel, SpwmError};
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
+
+/// Line code used to encode bits as output transitions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum LineCode {
+    /// Manchester code (IEEE 802.3): `0` is sent as high-low, `1` as low-high
+    Manchester = 0,
+    /// Biphase mark code: the level toggles at every bit start and additionally at mid-bit for `1`
+    BiphaseMark = 1,
+    /// Pulse-width code: one period per bit with the high time configured by `PulseTiming`
+    PulseWidth = 2,
+}
+
+/// Bit timing of the pulse-width line code.
+///
+/// All durations are in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PulseTiming {
+    /// Duration of one bit (high and low time)
+    pub bit_ns: u32,
+    /// High time of a `0` bit
+    pub t0h_ns: u32,
+    /// High time of a `1` bit
+    pub t1h_ns: u32,
+    /// Maximum allowed deviation of any quantized duration
+    pub tolerance_ns: u32,
+}
+
+/// Bit-stream transmission state of a channel.
+///
+/// # Fields
+/// - `data`: Buffer being transmitted; only written while no transmission is active
+/// - `code`: Line code of the active transmission
+/// - `position`: Index of the next half-bit to send
+/// - `level`: Last emitted level
+/// - `active`: Whether a transmission is in progress
+/// - `t0h_ticks`: High time of a `0` bit for the pulse-width code (0 if not configured)
+/// - `t1h_ticks`: High time of a `1` bit for the pulse-width code
+#[derive(Default, Debug)]
+pub(crate) struct StreamState {
+    data: Cell<&'static [u8]>,
+    code: AtomicU8,
+    position: AtomicU32,
+    level: AtomicBool,
+    active: AtomicBool,
+    t0h_ticks: AtomicU32,
+    t1h_ticks: AtomicU32,
+}
+
+/// Converts a duration into the nearest number of hardware timer ticks.
+///
+/// # Errors
+/// - `SpwmError::TimingToleranceExceeded` if the quantized duration deviates from `ns` by more
+///   than `tolerance_ns`
+/// - `SpwmError::InvalidHardwareFrequency` if `hardware_freq_hz` is 0
+fn ns_to_ticks(ns: u32, tolerance_ns: u32, hardware_freq_hz: u32) -> Result<u32, SpwmError> {
+    const NS_PER_SEC: u64 = 1_000_000_000;
+
+    let hardware_freq_hz = u64::from(hardware_freq_hz);
+    let ticks = u64::from(ns)
+        .saturating_mul(hardware_freq_hz)
+        .saturating_add(NS_PER_SEC.div_euclid(2))
+        .div_euclid(NS_PER_SEC);
+    let quantized_ns = ticks
+        .saturating_mul(NS_PER_SEC)
+        .checked_div(hardware_freq_hz)
+        .ok_or(SpwmError::InvalidHardwareFrequency)?;
+
+    if quantized_ns.abs_diff(u64::from(ns)) > u64::from(tolerance_ns) {
+        return Err(SpwmError::TimingToleranceExceeded);
+    }
+
+    u32::try_from(ticks).map_err(|_| SpwmError::TimingToleranceExceeded)
+}
+
+impl SpwmChannel {
+    /// Sets the channel frequency for bit-stream transmission at `bit_rate` bits per second.
+    ///
+    /// # Parameters
+    /// - `bit_rate`: Desired bit rate in bits per second
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the bit rate is 0 or twice the bit rate is too
+    /// high for the hardware timer frequency.
+    pub fn set_bit_rate(&self, bit_rate: u32, hardware_freq_hz: u32) -> Result<(), SpwmError> {
+        let freq_hz = bit_rate.checked_mul(2).ok_or(SpwmError::InvalidFrequency)?;
+
+        self.update_frequency(freq_hz, hardware_freq_hz)
+    }
+
+    /// Configures the channel period and bit high times for the pulse-width line code.
+    ///
+    /// The channel period is set to the bit duration directly, bypassing the usual frequency
+    /// ratio requirement, since only the two high times have to be resolved.
+    ///
+    /// # Parameters
+    /// - `timing`: Bit timing in nanoseconds
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidHardwareFrequency` if the hardware frequency is 0
+    /// - `SpwmError::TimingToleranceExceeded` if the tick rate cannot meet the timing tolerance
+    /// - `SpwmError::InvalidPulseWidth` if the high times are not distinct, non-zero and shorter
+    ///   than the bit duration after quantization
+    /// - `SpwmError::Busy` if a transmission is in progress
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{PulseTiming, Spwm, SpwmError};
+    /// # let spwm = Spwm::<1>::new(100_000);
+    /// # let channel = spwm.create_channel()
+    /// #     .freq_hz(1_000)
+    /// #     .duty_cycle(0)
+    /// #     .on_off_callback(|_| {})
+    /// #     .period_callback(|| {})
+    /// #     .build()
+    /// #     .unwrap();
+    /// let timing = PulseTiming {
+    ///     bit_ns: 1_250,
+    ///     t0h_ns: 400,
+    ///     t1h_ns: 800,
+    ///     tolerance_ns: 150,
+    /// };
+    ///
+    /// // 20 MHz tick: 50 ns resolution
+    /// assert!(channel.set_pulse_timing(&timing, 20_000_000).is_ok());
+    /// // 1 MHz tick cannot resolve the high times
+    /// assert_eq!(
+    ///     channel.set_pulse_timing(&timing, 1_000_000),
+    ///     Err(SpwmError::TimingToleranceExceeded)
+    /// );
+    /// ```
+    pub fn set_pulse_timing(
+        &self,
+        timing: &PulseTiming,
+        hardware_freq_hz: u32,
+    ) -> Result<(), SpwmError> {
+        if hardware_freq_hz == 0 {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        if self.is_transmitting() {
+            return Err(SpwmError::Busy);
+        }
+
+        let bit_ticks = ns_to_ticks(timing.bit_ns, timing.tolerance_ns, hardware_freq_hz)?;
+        let t0h_ticks = ns_to_ticks(timing.t0h_ns, timing.tolerance_ns, hardware_freq_hz)?;
+        let t1h_ticks = ns_to_ticks(timing.t1h_ns, timing.tolerance_ns, hardware_freq_hz)?;
+
+        if t0h_ticks == 0 || t0h_ticks == t1h_ticks || t0h_ticks.max(t1h_ticks) >= bit_ticks {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        self.set_period_ticks_keep_duty(bit_ticks);
+        self.stream.t0h_ticks.store(t0h_ticks, Ordering::Relaxed);
+        self.stream.t1h_ticks.store(t1h_ticks, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Starts transmitting `data` as a line-coded bit stream.
+    ///
+    /// The first half-bit is sent at the next period boundary of the enabled channel.
+    ///
+    /// # Parameters
+    /// - `data`: Bytes to send, MSB first
+    /// - `code`: Line code to use
+    ///
+    /// # Errors
+    /// Returns `SpwmError::Busy` if a transmission is already in progress, or
+    /// `SpwmError::InvalidPulseWidth` if the pulse-width code is used before
+    /// `set_pulse_timing()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{LineCode, Spwm};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let spwm = Spwm::<1>::new(100_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(0)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .transmit_complete_callback(|| {})
+    ///     .build()?;
+    ///
+    /// channel.set_bit_rate(500, 100_000)?;
+    /// channel.enable()?;
+    /// channel.transmit(b"hi", LineCode::Manchester)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transmit(&self, data: &'static [u8], code: LineCode) -> Result<(), SpwmError> {
+        let stream = &self.stream;
+
+        if stream.active.load(Ordering::Acquire) {
+            return Err(SpwmError::Busy);
+        }
+
+        if code == LineCode::PulseWidth && stream.t0h_ticks.load(Ordering::Relaxed) == 0 {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        stream.data.set(data);
+        stream.code.store(code as u8, Ordering::Relaxed);
+        stream.position.store(0, Ordering::Relaxed);
+        stream
+            .level
+            .store(self.waveform_on.load(Ordering::Relaxed), Ordering::Relaxed);
+        stream.active.store(true, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Returns `true` if a bit-stream transmission is in progress.
+    pub fn is_transmitting(&self) -> bool {
+        self.stream.active.load(Ordering::Acquire)
+    }
+
+    /// Produces the on-time ticks for the next period of an active transmission.
+    ///
+    /// Finishes the transmission and invokes the transmit complete callback once the whole
+    /// buffer has been sent.
+    ///
+    /// # Returns
+    /// The on-time ticks of the next period, or `None` if no transmission is active.
+    pub(crate) fn next_stream_ticks(&self) -> Option<u32> {
+        let stream = &self.stream;
+
+        if !stream.active.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let code = stream.code.load(Ordering::Relaxed);
+        let position = stream.position.load(Ordering::Relaxed) as usize;
+        let bit_index = if code == LineCode::PulseWidth as u8 {
+            position
+        } else {
+            position / 2
+        };
+        let Some(byte) = stream.data.get().get(bit_index / 8) else {
+            stream.active.store(false, Ordering::Release);
+
+            if let Some(callback) = self.transmit_complete_callback.get() {
+                callback();
+            }
+
+            return None;
+        };
+        let bit = byte & (0x80 >> (bit_index % 8)) != 0;
+
+        stream.position.fetch_add(1, Ordering::Relaxed);
+
+        if code == LineCode::PulseWidth as u8 {
+            let high_ticks = if bit {
+                &stream.t1h_ticks
+            } else {
+                &stream.t0h_ticks
+            };
+
+            return Some(high_ticks.load(Ordering::Relaxed));
+        }
+
+        let first_half = position.is_multiple_of(2);
+        let previous = stream.level.load(Ordering::Relaxed);
+        let level = if code == LineCode::Manchester as u8 {
+            first_half != bit
+        } else if first_half {
+            !previous
+        } else {
+            previous != bit
+        };
+
+        stream.level.store(level, Ordering::Relaxed);
+
+        Some(if level {
+            self.period_ticks.load(Ordering::Relaxed)
+        } else {
+            0
+        })
+    }
+}
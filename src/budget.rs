@@ -0,0 +1,73 @@
+//! Interrupt handler budget check.
+//!
+//! Callbacks run inside `irq_handler()`, so callbacks of several channels falling on the same
+//! tick delay the handler. Once the handler runs longer than the interval between two calls,
+//! ticks are coalesced by the interrupt controller and the waveforms jitter. The check adds up
+//! the worst case of every channel firing all its per-period callbacks on the same tick and
+//! compares it with the tick interval at configuration time.
+
+use crate::{Spwm, SpwmChannel, SpwmError};
+
+impl SpwmChannel {
+    /// Returns the worst-case number of callbacks the channel invokes in one period.
+    pub(crate) fn callbacks_per_period(&self) -> u32 {
+        let window_edges = if self.measurement_window_callback.get().is_some() {
+            2
+        } else {
+            0
+        };
+        let counted = [
+            self.period_callback.get().is_some(),
+            self.period_ticks_callback.get().is_some(),
+            self.prepare_callback.get().is_some(),
+            self.update_applied_callback.get().is_some(),
+            self.level_source.get().is_some(),
+            self.transmit_complete_callback.get().is_some(),
+            self.burst_complete_callback.get().is_some(),
+            self.double_pulse_complete_callback.get().is_some(),
+        ];
+        let single_callbacks = counted.iter().filter(|&&set| set).count();
+
+        // on and off edges
+        u32::try_from(single_callbacks)
+            .unwrap_or(u32::MAX)
+            .saturating_add(window_edges)
+            .saturating_add(2)
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Checks that the callbacks can not overrun the interval between two `irq_handler()`
+    /// calls.
+    ///
+    /// The estimate assumes the worst case: every registered channel invokes all callbacks it
+    /// may invoke in a period (edges, period, prepare, measurement window and completion
+    /// callbacks) on the same tick, together with every pending alarm. The handler overhead
+    /// itself is not included, so the check is meant to catch misconfigurations rather than to
+    /// replace a measurement on the target.
+    ///
+    /// # Parameters
+    /// - `max_callback_ticks`: Worst-case duration of a single callback in hardware timer ticks
+    ///
+    /// # Returns
+    /// The worst-case number of callbacks invoked within one `irq_handler()` call.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidConfiguration` if the worst-case callbacks take longer than the
+    /// interval between two `irq_handler()` calls (the tick divider).
+    pub fn validate_budget(&self, max_callback_ticks: u32) -> Result<u32, SpwmError> {
+        let alarms = (0..A).filter(|&id| self.alarms.is_pending(id)).count();
+        let callbacks = self.channels().map(SpwmChannel::callbacks_per_period).fold(
+            u32::try_from(alarms).unwrap_or(u32::MAX),
+            u32::saturating_add,
+        );
+
+        if u64::from(callbacks).saturating_mul(u64::from(max_callback_ticks))
+            > u64::from(self.tick_divider)
+        {
+            return Err(SpwmError::InvalidConfiguration);
+        }
+
+        Ok(callbacks)
+    }
+}
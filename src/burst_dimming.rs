@@ -0,0 +1,66 @@
+//! Burst dimming of LEDs at low brightness.
+//!
+//! Very short pulses shift the color temperature of LEDs and lose efficiency in the driver, so
+//! below a threshold duty cycle a channel with burst dimming keeps the pulse at the threshold
+//! duty cycle and skips whole periods instead. The periods are spread with an accumulator like
+//! mains burst-fire, so the average brightness matches the configured duty cycle. The switch
+//! between the two modes happens at the period boundary and is transparent to the duty cycle
+//! setters.
+
+use crate::channel::MAX_DUTY_CYCLE;
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Sets the duty cycle below which the channel skips periods instead of shortening them.
+    ///
+    /// # Parameters
+    /// - `threshold`: Threshold duty cycle percentage (0 disables burst dimming)
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the threshold is greater than 100.
+    pub fn set_burst_dimming(&self, threshold: u8) -> Result<(), SpwmError> {
+        if threshold > MAX_DUTY_CYCLE {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        self.burst_dim_threshold.store(threshold, Ordering::SeqCst);
+        self.burst_dim_accumulator.store(0, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Returns whether the configured duty cycle is below the burst dimming threshold, so the
+    /// channel skips periods.
+    pub fn is_burst_dimming(&self) -> bool {
+        let duty_cycle = self.duty_cycle();
+
+        duty_cycle != 0 && duty_cycle < self.burst_dim_threshold.load(Ordering::Relaxed)
+    }
+
+    /// Returns the on-time of the starting period while the channel burst dims: the threshold
+    /// on-time in the share of periods matching the duty cycle and 0 in the others.
+    pub(crate) fn burst_dimmed_ticks(&self) -> Option<u32> {
+        if !self.is_burst_dimming() {
+            return None;
+        }
+
+        let threshold = self.burst_dim_threshold.load(Ordering::Relaxed);
+        let accumulator = self
+            .burst_dim_accumulator
+            .load(Ordering::Relaxed)
+            .saturating_add(u32::from(self.duty_cycle()));
+
+        if let Some(remainder) = accumulator.checked_sub(u32::from(threshold)) {
+            self.burst_dim_accumulator
+                .store(remainder, Ordering::Relaxed);
+
+            Some(self.value_to_ticks(threshold))
+        } else {
+            self.burst_dim_accumulator
+                .store(accumulator, Ordering::Relaxed);
+
+            Some(0)
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Calibration of the duty cycle to the actual output.
+//!
+//! Drivers, LED forward voltages and RC-filter DACs do not respond linearly to the duty cycle.
+//! A channel with a calibration table interprets every requested duty cycle as an output value
+//! and maps it to the duty cycle delivering it by linear interpolation between the table
+//! points. The interpolated on-time is computed in hundredths of a percent, so a flat section
+//! of the response does not collapse several output values onto one 1% duty cycle step.
+
+use crate::channel::MAX_DUTY_CYCLE;
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Hundredths of a percent per percent.
+const LEVEL_PER_PERCENT: u32 = 100;
+
+/// Full scale of an interpolated level in hundredths of a percent.
+const LEVEL_SCALE: u64 = 10_000;
+
+/// Returns the duty cycle in hundredths of a percent delivering `value` according to `table`.
+///
+/// Values outside the table are clamped to its first and last point.
+fn interpolate(table: &[(u8, u8)], value: u8) -> u32 {
+    let (Some(&(first_value, first_duty)), Some(&(last_value, last_duty))) =
+        (table.first(), table.last())
+    else {
+        return u32::from(value).saturating_mul(LEVEL_PER_PERCENT);
+    };
+
+    if value <= first_value {
+        return u32::from(first_duty).saturating_mul(LEVEL_PER_PERCENT);
+    }
+
+    if value >= last_value {
+        return u32::from(last_duty).saturating_mul(LEVEL_PER_PERCENT);
+    }
+
+    table
+        .windows(2)
+        .find_map(|segment| match *segment {
+            [(value_0, duty_0), (value_1, duty_1)] if value <= value_1 => {
+                let start = i64::from(duty_0).saturating_mul(i64::from(LEVEL_PER_PERCENT));
+                let rise = i64::from(duty_1)
+                    .saturating_sub(i64::from(duty_0))
+                    .saturating_mul(i64::from(LEVEL_PER_PERCENT))
+                    .saturating_mul(i64::from(value.saturating_sub(value_0)));
+                let run = i64::from(value_1.saturating_sub(value_0));
+
+                rise.checked_div(run)
+                    .map(|offset| start.saturating_add(offset))
+                    .and_then(|level| u32::try_from(level).ok())
+            }
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+impl SpwmChannel {
+    /// Sets the calibration table of the channel after validating it.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the table has fewer than 2 points, a value or
+    /// duty cycle is greater than 100, or the values are not strictly increasing.
+    pub(crate) fn set_calibration(&self, table: &'static [(u8, u8)]) -> Result<(), SpwmError> {
+        let valid = table.len() >= 2
+            && table
+                .iter()
+                .all(|&(value, duty)| value <= MAX_DUTY_CYCLE && duty <= MAX_DUTY_CYCLE)
+            && table.windows(2).all(|segment| match *segment {
+                [(value_0, _), (value_1, _)] => value_0 < value_1,
+                _ => false,
+            });
+
+        if !valid {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        self.calibration
+            .set(table)
+            .map_err(|_| SpwmError::CallbackSetError)
+    }
+
+    /// Returns the calibrated on-time of the output value `value` for the current period, or
+    /// `None` if the channel has no calibration table.
+    pub(crate) fn calibrated_ticks(&self, value: u8) -> Option<u32> {
+        let level = interpolate(self.calibration.get()?, value);
+
+        u64::from(self.period_ticks.load(Ordering::Relaxed))
+            .saturating_mul(u64::from(level))
+            .checked_div(LEVEL_SCALE)
+            .and_then(|ticks| u32::try_from(ticks).ok())
+    }
+
+    /// Returns the duty cycle in hundredths of a percent the configured output value is mapped
+    /// to, or `None` if the channel has no calibration table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spwm::Spwm;
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// // the driver only responds above 20% duty cycle
+    /// static TABLE: [(u8, u8); 2] = [(0, 20), (100, 100)];
+    ///
+    /// let spwm = Spwm::<1>::new(1_000_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(50)
+    ///     .calibration(&TABLE)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    ///
+    /// assert_eq!(channel.calibrated_duty(), Some(6_000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn calibrated_duty(&self) -> Option<u32> {
+        let table = self.calibration.get()?;
+
+        Some(interpolate(table, self.duty_cycle()))
+    }
+}
@@ -0,0 +1,407 @@
+//! Channel chaining support for SPWM.
+//!
+//! A chained (slave) channel does not run freely: it starts a single period whenever its
+//! master channel completes one (or every `k`-th) period, and then waits for the next trigger.
+//!
+//! A ratio-locked channel runs continuously with a period of exactly `ratio` master periods, and
+//! its period boundaries are driven by the master, so the two outputs never drift apart. A locked
+//! channel may additionally be shifted by a fixed fraction of its period, which is how
+//! interleaved (multi-phase) channel groups are built.
+
+use crate::adc_trigger::AdcTriggerPoint;
+use crate::{ChannelId, Spwm, SpwmChannel, SpwmError};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Defines when a chained channel is triggered by its master channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChainMode {
+    /// Trigger the chained channel each time the master completes a period
+    EveryPeriod,
+    /// Trigger the chained channel each time the master completes the given number of periods
+    EveryNthPeriod(u32),
+}
+
+/// Link between a chained channel and its master.
+///
+/// # Fields
+/// - `master`: Identifier of the master channel
+/// - `divider`: Number of master periods per trigger
+/// - `count`: Master periods completed since the last trigger
+/// - `locked`: Whether the slave is ratio-locked instead of chained
+/// - `phase`: Phase shift of a locked slave as a `(numerator, denominator)` fraction of its period
+/// - `align`: Point within the master period replacing `phase` for an ADC trigger slave
+pub(crate) struct ChainLink {
+    master: ChannelId,
+    divider: u32,
+    count: AtomicU32,
+    locked: bool,
+    phase: (u32, u32),
+    align: Option<AdcTriggerPoint>,
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Chains a slave channel to a master channel.
+    ///
+    /// The slave channel runs one period each time the master channel completes the number of
+    /// periods defined by `mode`, and stays off in between. This allows derived timing
+    /// relationships, e.g. a sampling strobe on every 4th PWM cycle. If the slave is processed
+    /// after the master (lower priority), it starts on the same tick the master period ends;
+    /// otherwise it starts on the next tick.
+    ///
+    /// # Parameters
+    /// - `master_id`: The identifier of the master channel
+    /// - `slave_id`: The identifier of the channel to be triggered
+    /// - `mode`: Defines how many master periods trigger a slave period
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if either channel is not registered or both are the same
+    /// - `SpwmError::InvalidChainMode` if the period divider is 0
+    /// - `SpwmError::InvalidMode` if the master is chained to the slave, directly or through
+    ///   other channels, so the link would close a cycle
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{ChainMode, Spwm};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<2>::new(100_000);
+    /// let pwm = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(50)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    /// let strobe = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(5)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    /// let pwm_id = spwm.register_channel(pwm)?;
+    /// let strobe_id = spwm.register_channel(strobe)?;
+    ///
+    /// spwm.chain(pwm_id, strobe_id, ChainMode::EveryNthPeriod(4))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn chain(
+        &mut self,
+        master_id: ChannelId,
+        slave_id: ChannelId,
+        mode: ChainMode,
+    ) -> Result<(), SpwmError> {
+        let divider = match mode {
+            ChainMode::EveryPeriod => 1,
+            ChainMode::EveryNthPeriod(divider) => divider,
+        };
+
+        if divider == 0 {
+            return Err(SpwmError::InvalidChainMode);
+        }
+
+        if master_id == slave_id || self.get_channel(master_id).is_none() {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        if self.is_chained_to(master_id, slave_id) {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        self.unchain(slave_id)?;
+
+        let slot = self
+            .channel_slots
+            .get_mut(slave_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let slave = slot.channel.as_ref().ok_or(SpwmError::InvalidChannel)?;
+
+        slave.set_chained(true);
+        slot.chain = Some(ChainLink {
+            master: master_id,
+            divider,
+            count: AtomicU32::new(0),
+            locked: false,
+            phase: (0, 1),
+            align: None,
+        });
+
+        Ok(())
+    }
+
+    /// Locks the frequency of a slave channel to an exact integer divisor of a master channel.
+    ///
+    /// The slave period becomes exactly `ratio` master periods (regardless of the frequency it
+    /// was built with) and its period boundaries are driven by the master, so harmonically
+    /// related outputs never drift apart due to rounding. The slave keeps following the master
+    /// when the master frequency changes at runtime, preserving its duty cycle.
+    ///
+    /// # Parameters
+    /// - `master_id`: The identifier of the master channel
+    /// - `slave_id`: The identifier of the locked channel
+    /// - `ratio`: Number of master periods in one slave period
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if either channel is not registered or both are the same
+    /// - `SpwmError::InvalidChainMode` if `ratio` is 0
+    /// - `SpwmError::InvalidFrequency` if the resulting slave period does not fit into `u32` ticks
+    pub fn lock_ratio(
+        &mut self,
+        master_id: ChannelId,
+        slave_id: ChannelId,
+        ratio: u32,
+    ) -> Result<(), SpwmError> {
+        self.lock_with_phase(master_id, slave_id, ratio, (0, 1))
+    }
+
+    /// Interleaves a group of channels at the frequency of the first one.
+    ///
+    /// Every other listed channel is locked to the first channel with the same frequency and a
+    /// phase offset of `360° * k / n`, where `k` is its position in `channel_ids` and `n` is the
+    /// number of channels. The offsets are maintained across runtime frequency changes of the
+    /// first channel, as used for multi-phase buck converters and LED backlight strings.
+    ///
+    /// # Parameters
+    /// - `channel_ids`: Identifiers of the channels to interleave; the first one is the reference
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the list is empty, contains duplicates or
+    /// unregistered channels.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::Spwm;
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<3>::new(100_000);
+    /// let mut ids = [0; 3];
+    ///
+    /// for id in &mut ids {
+    ///     let channel = spwm.create_channel()
+    ///         .freq_hz(1_000)
+    ///         .duty_cycle(30)
+    ///         .on_off_callback(|_| {})
+    ///         .period_callback(|| {})
+    ///         .build()?;
+    ///     *id = spwm.register_channel(channel)?;
+    /// }
+    ///
+    /// // phases 0°, 120° and 240°
+    /// spwm.interleave(&ids)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn interleave(&mut self, channel_ids: &[ChannelId]) -> Result<(), SpwmError> {
+        let (&reference_id, others) = channel_ids.split_first().ok_or(SpwmError::InvalidChannel)?;
+
+        for (i, &id) in channel_ids.iter().enumerate() {
+            if self.get_channel(id).is_none()
+                || channel_ids.iter().take(i).any(|&other| other == id)
+            {
+                return Err(SpwmError::InvalidChannel);
+            }
+        }
+
+        let n = u32::try_from(channel_ids.len()).map_err(|_| SpwmError::InvalidChannel)?;
+
+        self.unchain(reference_id)?;
+
+        for (k, &id) in (1..).zip(others) {
+            self.lock_with_phase(reference_id, id, 1, (k, n))?;
+        }
+
+        Ok(())
+    }
+
+    /// Configures two channels as a quadrature output pair.
+    ///
+    /// Both channels are set to 50% duty cycle and the second channel is locked to the first one
+    /// with the same frequency and a quarter-period (90°) phase shift. The shift is maintained
+    /// across runtime frequency changes of the first channel, so the pair can drive devices
+    /// expecting quadrature clocks or simulate an incremental encoder.
+    ///
+    /// # Parameters
+    /// - `a_id`: The identifier of the leading (A) channel
+    /// - `b_id`: The identifier of the lagging (B) channel
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if either channel is not registered or both are the
+    /// same.
+    pub fn quadrature(&mut self, a_id: ChannelId, b_id: ChannelId) -> Result<(), SpwmError> {
+        if a_id == b_id {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        for id in [a_id, b_id] {
+            self.get_channel(id)
+                .ok_or(SpwmError::InvalidChannel)?
+                .update_duty_cycle(50)?;
+        }
+
+        self.unchain(a_id)?;
+        self.lock_with_phase(a_id, b_id, 1, (1, 4))
+    }
+
+    /// Locks a slave channel to `ratio` master periods, shifted by `phase` of its period.
+    pub(crate) fn lock_with_phase(
+        &mut self,
+        master_id: ChannelId,
+        slave_id: ChannelId,
+        ratio: u32,
+        phase: (u32, u32),
+    ) -> Result<(), SpwmError> {
+        if ratio == 0 || phase.1 == 0 {
+            return Err(SpwmError::InvalidChainMode);
+        }
+
+        if master_id == slave_id {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        let master_period_ticks = self
+            .get_channel(master_id)
+            .ok_or(SpwmError::InvalidChannel)?
+            .period_ticks
+            .load(Ordering::Relaxed);
+        let period_ticks = master_period_ticks
+            .checked_mul(ratio)
+            .ok_or(SpwmError::InvalidFrequency)?;
+
+        self.unchain(slave_id)?;
+
+        let slot = self
+            .channel_slots
+            .get_mut(slave_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let slave = slot.channel.as_ref().ok_or(SpwmError::InvalidChannel)?;
+
+        slave.set_period_ticks_keep_duty(period_ticks);
+        slave.set_locked(true);
+        slot.chain = Some(ChainLink {
+            master: master_id,
+            divider: ratio,
+            count: AtomicU32::new(0),
+            locked: true,
+            phase,
+            align: None,
+        });
+
+        Ok(())
+    }
+
+    /// Aligns the period start of a locked slave to `point` of its master period.
+    pub(crate) fn align_locked(
+        &mut self,
+        slave_id: ChannelId,
+        point: AdcTriggerPoint,
+    ) -> Result<(), SpwmError> {
+        let link = self
+            .channel_slots
+            .get_mut(slave_id)
+            .and_then(|slot| slot.chain.as_mut())
+            .filter(|link| link.locked)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        link.align = Some(point);
+
+        Ok(())
+    }
+
+    /// Removes the chaining or ratio lock of a slave channel, so it runs freely again.
+    ///
+    /// # Parameters
+    /// - `slave_id`: The identifier of the chained channel
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered.
+    pub fn unchain(&mut self, slave_id: ChannelId) -> Result<(), SpwmError> {
+        let slot = self
+            .channel_slots
+            .get_mut(slave_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let channel = slot.channel.as_ref().ok_or(SpwmError::InvalidChannel)?;
+
+        if let Some(link) = slot.chain.take() {
+            if link.locked {
+                channel.set_locked(false);
+            } else {
+                channel.set_chained(false);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether `channel_id` is driven by `master_id`, following the master links of
+    /// chained and locked channels.
+    fn is_chained_to(&self, channel_id: ChannelId, master_id: ChannelId) -> bool {
+        let mut current = channel_id;
+
+        // a walk longer than the number of slots is already caught in a cycle
+        for _ in 0..N {
+            let Some(link) = self
+                .channel_slots
+                .get(current)
+                .and_then(|slot| slot.chain.as_ref())
+            else {
+                return false;
+            };
+
+            if link.master == master_id {
+                return true;
+            }
+
+            current = link.master;
+        }
+
+        false
+    }
+
+    /// Counts a completed period of `master_id` and triggers the channels chained to it.
+    pub(crate) fn trigger_chained(&self, master_id: ChannelId) {
+        for slot in self.slots() {
+            if let (Some(channel), Some(link)) = (&slot.channel, &slot.chain)
+                && link.master == master_id
+            {
+                let count = link.count.load(Ordering::Relaxed).saturating_add(1);
+
+                if count >= link.divider {
+                    link.count.store(0, Ordering::Relaxed);
+
+                    if link.locked {
+                        self.trigger_locked(channel, link);
+                    } else {
+                        channel.trigger();
+                    }
+                } else {
+                    link.count.store(count, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Re-derives the period of a locked slave from its master and schedules its next period
+    /// boundary according to the configured phase shift.
+    fn trigger_locked(&self, channel: &SpwmChannel, link: &ChainLink) {
+        let Some(master) = self.get_channel(link.master) else {
+            return;
+        };
+        let master_period_ticks = master.period_ticks.load(Ordering::Relaxed);
+        let Some(period_ticks) = master_period_ticks.checked_mul(link.divider) else {
+            return;
+        };
+        let delay_ticks = if let Some(point) = link.align {
+            point.delay_ticks(master.on_ticks.load(Ordering::Relaxed), period_ticks)
+        } else {
+            let (numerator, denominator) = link.phase;
+            let Some(delay_ticks) = u64::from(period_ticks)
+                .saturating_mul(u64::from(numerator))
+                .checked_div(u64::from(denominator))
+            else {
+                return;
+            };
+
+            u32::try_from(delay_ticks).unwrap_or(u32::MAX)
+        };
+
+        channel.set_period_ticks_keep_duty(period_ticks);
+        channel.trigger_delayed(delay_ticks);
+    }
+}
@@ -3,17 +3,98 @@
 //! This module provides the `SpwmChannel` struct and a type-safe builder pattern
 //! for creating and configuring individual PWM channels.
 
-use crate::{OnOffCallback, PeriodCallback, SpwmError, SpwmState};
+use crate::bitstream::StreamState;
+#[cfg(feature = "duty-lut")]
+use crate::duty_lut::DutyLut;
+use crate::engine::{self, EngineState, TickEvent};
+use crate::irq_errors::IRQ_ERROR_UNCONFIGURED;
+use crate::protection::{ProtectionProfile, ProtectionState, ProtectionViolationCallback};
+use crate::reciprocal::HardwareReciprocal;
+use crate::reschedule;
+use crate::signal::SignalWaveform;
+#[cfg(feature = "stats")]
+use crate::stats::ChannelStats;
+use crate::tick_count::{TickCount, is_valid_hardware_frequency};
+use crate::trigger;
+use crate::update_policy::UpdatePolicy;
+use crate::{
+    BurstCompleteCallback, DoublePulseCompleteCallback, EnableCallback, LevelSourceCallback,
+    MeasurementWindowCallback, OnOffCallback, PeriodCallback, PeriodTicksCallback, PrepareCallback,
+    SpwmError, SpwmState, TransmitCompleteCallback, UpdateAppliedCallback, assert_flicker_safe,
+};
 use core::cell::OnceCell;
 use core::marker::PhantomData;
-use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU8, AtomicU32, Ordering};
 
 /// Maximum allowed duty cycle percentage.
-const MAX_DUTY_CYCLE: u8 = 100;
+pub(crate) const MAX_DUTY_CYCLE: u8 = 100;
+
+/// Schedule flag: the channel timing changed outside of `Spwm::irq_handler()`.
+pub(crate) const SCHEDULE_CHANGED: u8 = 1 << 0;
+
+/// Schedule flag: the channel was enabled and starts counting from the next tick.
+pub(crate) const SCHEDULE_RESTARTED: u8 = 1 << 1;
+
+/// Minimum period length in ticks; shorter periods leave no room for both output states.
+pub(crate) const MIN_PERIOD_TICKS: u32 = 2;
 
 /// Minimum ratio between hardware timer frequency and channel frequency.
 /// The hardware timer must run at least 100x faster than the PWM channel frequency.
-const FREQUENCY_DIFFERENCE_REQUIRED: u32 = 100;
+pub(crate) const FREQUENCY_DIFFERENCE_REQUIRED: u32 = 100;
+
+/// `2^32 / 100` rounded up, the fixed-point step of one duty cycle percent.
+const DUTY_STEP: u32 = 42_949_673;
+
+/// Duty cycle or frequency update applied at a period boundary.
+///
+/// # Fields
+/// - `old_on_ticks`: On-time of the period that just ended in ticks
+/// - `new_on_ticks`: On-time of the period that starts in ticks
+/// - `period_ticks`: Length of the period that starts in ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AppliedUpdate {
+    pub old_on_ticks: u32,
+    pub new_on_ticks: u32,
+    pub period_ticks: u32,
+}
+
+/// Output waveform produced by a channel for its configured duty cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputWaveform {
+    /// The output never switches on (0 on-time ticks, e.g. 0% duty cycle)
+    ConstantOff,
+    /// The output never switches off (on-time covers the whole period, e.g. 100% duty cycle)
+    ConstantOn,
+    /// The output switches on at every period start and off after the on-time ticks
+    Modulated,
+}
+
+/// Order of the period callbacks and the latching of pending updates at a period boundary.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PeriodCallbackTiming {
+    /// The period callbacks run first, so an update requested inside them applies to the
+    /// period that starts on the same tick
+    #[default]
+    BeforeUpdate,
+    /// Pending updates are latched first, so the period callbacks observe the on-time of the
+    /// starting period and an update requested inside them applies one period later
+    AfterUpdate,
+}
+
+/// Report of the effective timing of a channel as returned by `SpwmChannel::validate()`.
+///
+/// # Fields
+/// - `period_ticks`: Effective period length in ticks (at least `MIN_PERIOD_TICKS`)
+/// - `on_ticks`: Configured on-time ticks of each period
+/// - `waveform`: Output waveform resulting from the on-time and period ticks
+/// - `period_clamped`: Whether the configured period was shorter than the minimum of 2 ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelValidation {
+    pub period_ticks: u32,
+    pub on_ticks: u32,
+    pub waveform: OutputWaveform,
+    pub period_clamped: bool,
+}
 
 /// Builder state indicating frequency needs to be set.
 pub struct SpwmChannelFreqHzBuildState {}
@@ -36,10 +117,177 @@ pub struct SpwmChannel {
     pub(crate) on_ticks: AtomicU32,
     /// Pending `on_ticks` value to be applied at next period start
     pub(crate) update_on_ticks: AtomicU32,
+    /// Whether `update_on_ticks` holds an update not applied yet
+    pub(crate) update_pending: AtomicBool,
+    /// Configured duty cycle percentage (the pending value if not yet applied)
+    pub(crate) duty_cycle: AtomicU8,
+    /// One-shot `on_ticks` value for the next period (0 if no pulse is requested)
+    pub(crate) pulse_ticks: AtomicU32,
+    /// Precomputed on-time ticks for every duty cycle percentage
+    #[cfg(feature = "duty-lut")]
+    pub(crate) duty_lut: DutyLut,
     /// Current tick counter within the period
     pub(crate) counter: AtomicU32,
+    /// Pending `SCHEDULE_*` flags that make `Spwm::irq_handler()` re-evaluate the next event
+    pub(crate) schedule: AtomicU8,
+    /// Reschedule generation index of the manager the channel is registered with
+    pub(crate) reschedule_index: AtomicU8,
+    /// Processing priority within `Spwm::irq_handler()` (higher goes first)
+    pub(crate) priority: AtomicU8,
+    /// Load-shedding priority for `Spwm::shed_load()` (lower is shed first)
+    pub(crate) shed_priority: AtomicU8,
+    /// Whether this channel only runs a period when triggered by a master channel
+    pub(crate) chained: AtomicBool,
+    /// Whether this channel's period boundaries are driven by a master channel
+    pub(crate) locked: AtomicBool,
+    /// Whether a chained channel is idle and waiting for a trigger
+    pub(crate) waiting: AtomicBool,
+    /// Whether a trigger is pending for a chained channel
+    pub(crate) triggered: AtomicBool,
+    /// Ticks until a locked channel ends its period, plus one (0 if no trigger is pending)
+    pub(crate) trigger_countdown: AtomicU32,
+    /// Ticks between `enable()` and the start of the first period
+    pub(crate) start_delay_ticks: AtomicU32,
+    /// Ticks until the first period starts after `enable()` (0 if already started)
+    pub(crate) start_countdown: AtomicU32,
+    /// Ticks the next period is delayed by to shift the phase (0 if no shift is pending)
+    pub(crate) phase_delay: AtomicU32,
+    /// `MAINS_*` mode of a channel whose periods are started by the mains zero cross
+    pub(crate) mains_mode: AtomicU8,
+    /// Width of the leading-edge gate pulse in ticks (0 to conduct until the period end)
+    pub(crate) gate_pulse_ticks: AtomicU32,
+    /// Conducting half-cycles per burst-fire pattern
+    pub(crate) burst_on_half_cycles: AtomicU32,
+    /// Length of the burst-fire pattern in half-cycles
+    pub(crate) burst_half_cycles: AtomicU32,
+    /// Accumulator spreading the conducting half-cycles over the burst-fire pattern
+    pub(crate) burst_accumulator: AtomicU32,
+    /// Peak duty cycle of the peak-and-hold profile
+    pub(crate) peak_duty: AtomicU8,
+    /// Pull-in time of the peak-and-hold profile in ticks (0 without a profile)
+    pub(crate) pull_in_ticks: AtomicU32,
+    /// Pull-in ticks left after the current period
+    pub(crate) peak_remaining_ticks: AtomicU32,
+    /// Whether the current period runs at the peak duty cycle
+    pub(crate) in_peak: AtomicBool,
+    /// Carrier periods per burst (0 if the channel does not run in burst mode)
+    pub(crate) burst_periods: AtomicU32,
+    /// Quiet window after every burst in ticks
+    pub(crate) quiet_ticks: AtomicU32,
+    /// Carrier periods completed in the current burst
+    pub(crate) burst_period_count: AtomicU32,
+    /// Callback invoked when a burst completes
+    pub(crate) burst_complete_callback: OnceCell<BurstCompleteCallback>,
+    /// Fixed pulse of a trigger output in ticks (0 if the duty cycle sets the on-time)
+    pub(crate) trigger_pulse_ticks: AtomicU32,
+    /// Ticks from the period start to the start of the on-time in the current period
+    pub(crate) pulse_offset: AtomicU32,
+    /// Pending `pulse_offset` value to be applied at next period start
+    pub(crate) update_pulse_offset: AtomicU32,
+    /// First pulse, gap and second pulse of a double-pulse pattern in ticks
+    pub(crate) double_pulse_ticks: [AtomicU32; 3],
+    /// Stage of the running double-pulse pattern (0 if none is running)
+    pub(crate) double_pulse_stage: AtomicU8,
+    /// Ticks remaining in the current double-pulse stage
+    pub(crate) double_pulse_countdown: AtomicU32,
+    /// Callback invoked when a double-pulse pattern completes
+    pub(crate) double_pulse_complete_callback: OnceCell<DoublePulseCompleteCallback>,
+    /// Whether the current period was cut short by a current-limit trip
+    pub(crate) tripped: AtomicBool,
+    /// Ticks after each on edge during which current-limit trips are ignored
+    pub(crate) blanking_ticks: AtomicU32,
+    /// Waveform of a signal generator channel (0 if the channel is not a signal generator)
+    pub(crate) signal_waveform: AtomicU8,
+    /// Phase of the generated waveform (a full turn is 2^32)
+    pub(crate) signal_phase: AtomicU32,
+    /// Waveform phase advance per tick
+    pub(crate) signal_phase_step: AtomicU32,
+    /// Peak amplitude of the generated waveform in percent
+    pub(crate) signal_amplitude: AtomicU8,
+    /// Offset of the generated waveform in percent
+    pub(crate) signal_offset: AtomicU8,
+    /// State of the noise generator (0 before the first draw)
+    pub(crate) noise_state: AtomicU32,
+    /// Packed 8-bit rows of the pink noise generator
+    pub(crate) noise_rows: AtomicU32,
+    /// Noise levels drawn, selecting the pink noise row to refresh
+    pub(crate) noise_count: AtomicU32,
+    /// Level of a noise waveform in the current signal period, scaled to ±10000
+    pub(crate) noise_sample: AtomicI32,
+    /// Cached reciprocal of the hardware timer frequency for period length conversions
+    pub(crate) hardware_reciprocal: HardwareReciprocal,
+    /// Frequency reached by the frequency ramp in Hz
+    pub(crate) ramp_freq_hz: AtomicU32,
+    /// Target frequency of the frequency ramp in Hz (0 if the frequency does not ramp)
+    pub(crate) ramp_target_hz: AtomicU32,
+    /// Maximum frequency change per period of the frequency ramp in Hz (0 for no limit)
+    pub(crate) ramp_step_hz: AtomicU32,
+    /// Hardware timer frequency the ramp periods are computed for in Hz
+    pub(crate) ramp_hardware_freq_hz: AtomicU32,
+    /// Last output state reported through the on/off callback
+    pub(crate) output_on: AtomicBool,
+    /// Output state of the generated waveform, which differs from `output_on` while paused or
+    /// suspended
+    pub(crate) waveform_on: AtomicBool,
+    /// Whether the output is held off while the waveform keeps running
+    pub(crate) paused: AtomicBool,
+    /// Whether the output is held off by `Spwm::suspend()`
+    pub(crate) suspended: AtomicBool,
+    /// Bit-stream transmission state
+    pub(crate) stream: StreamState,
+    /// Callback invoked when a bit-stream transmission completes
+    pub(crate) transmit_complete_callback: OnceCell<TransmitCompleteCallback>,
+    /// Callback providing the output level of the next period
+    pub(crate) level_source: OnceCell<LevelSourceCallback>,
+    /// Callback invoked when a pending update is applied at the period boundary
+    pub(crate) update_applied_callback: OnceCell<UpdateAppliedCallback>,
+    /// Callback invoked at the end of each period with the ticks since `enable()`
+    pub(crate) period_ticks_callback: OnceCell<PeriodTicksCallback>,
+    /// Whether the period callbacks run after pending updates were latched
+    pub(crate) period_callback_after_update: AtomicBool,
+    /// Callback invoked `prepare_lead_ticks` ticks before the end of each period
+    pub(crate) prepare_callback: OnceCell<PrepareCallback>,
+    /// Ticks between the prepare callback and the end of the period
+    pub(crate) prepare_lead_ticks: AtomicU32,
+    /// Output value to duty cycle points the configured duty cycle is mapped through
+    pub(crate) calibration: OnceCell<&'static [(u8, u8)]>,
+    /// Duty cycle below which periods are skipped instead of shortened (0 if disabled)
+    pub(crate) burst_dim_threshold: AtomicU8,
+    /// Accumulator spreading the burst dimmed periods
+    pub(crate) burst_dim_accumulator: AtomicU32,
+    /// Whether frequencies in the known flicker ranges are rejected
+    pub(crate) flicker_safe: AtomicBool,
+    /// `UpdatePolicy` of on-time updates
+    pub(crate) update_policy: AtomicU8,
+    /// On-time ticks staged until the channel is enabled
+    pub(crate) staged_on_ticks: AtomicU32,
+    /// Whether an on-time update is staged until the channel is enabled
+    pub(crate) staged_pending: AtomicBool,
+    /// Duty cycle in effect before the pending update, restored by `cancel_pending()`
+    pub(crate) latched_duty_cycle: AtomicU8,
+    /// On-time ticks in effect before the pending update, restored by `cancel_pending()`
+    pub(crate) latched_on_ticks: AtomicU32,
+    /// `IRQ_ERROR_*` flags collected by `Spwm::take_errors()`
+    pub(crate) irq_errors: AtomicU32,
+    /// Callback invoked at the start (`true`) and stop (`false`) of the measurement window
+    pub(crate) measurement_window_callback: OnceCell<MeasurementWindowCallback>,
+    /// Ticks from the period start to the measurement window start
+    pub(crate) window_start_ticks: AtomicU32,
+    /// Ticks from the period start to the measurement window stop
+    pub(crate) window_stop_ticks: AtomicU32,
+    /// Ticks processed since `enable()`
+    pub(crate) enabled_ticks: TickCount,
+    /// Protection profile limiting the on-time
+    pub(crate) protection: ProtectionState,
+    /// Percentage of the duty cycle on-time removed by `Spwm::derate_all()`
+    pub(crate) derating: AtomicU8,
+    /// Accumulated enabled and on-time ticks
+    #[cfg(feature = "stats")]
+    pub(crate) stats: ChannelStats,
     /// Whether this channel is currently enabled
     pub(crate) enabled: AtomicBool,
+    /// Callback invoked when the channel is enabled or disabled
+    pub(crate) enable_callback: OnceCell<EnableCallback>,
     /// Callback invoked on state changes
     pub(crate) on_off_callback: OnceCell<OnOffCallback>,
     /// Callback invoked at period completion
@@ -47,11 +295,6 @@ pub struct SpwmChannel {
 }
 
 impl SpwmChannel {
-    /// Increments and returns the current tick counter.
-    pub(crate) fn counter_tick(&self) -> u32 {
-        self.counter.fetch_add(1, Ordering::SeqCst)
-    }
-
     /// Resets the tick counter to zero (called at period boundaries).
     pub(crate) fn counter_reset(&self) {
         self.counter.store(0, Ordering::SeqCst);
@@ -59,17 +302,236 @@ impl SpwmChannel {
 
     /// Sets the total number of ticks in one PWM period.
     pub(crate) fn set_period_ticks(&self, period_ticks: u32) {
+        #[cfg(feature = "duty-lut")]
+        self.duty_lut.fill(period_ticks);
+
         self.period_ticks.store(period_ticks, Ordering::SeqCst);
+        self.reschedule(SCHEDULE_CHANGED);
     }
 
-    /// Updates the on-time ticks, applying immediately if disabled or at next period if enabled.
-    pub(crate) fn update_on_ticks(&self, on_ticks: u32) {
-        if self.enabled.load(Ordering::Relaxed) {
-            self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+    /// Returns the period length in ticks, raised to `MIN_PERIOD_TICKS`.
+    pub(crate) fn effective_period_ticks(&self) -> u32 {
+        self.period_ticks
+            .load(Ordering::Relaxed)
+            .max(MIN_PERIOD_TICKS)
+    }
+
+    /// Requests `Spwm::irq_handler()` to re-evaluate the next event of this channel.
+    pub(crate) fn reschedule(&self, flags: u8) {
+        self.schedule.fetch_or(flags, Ordering::SeqCst);
+        reschedule::bump(self.reschedule_index.load(Ordering::Relaxed));
+    }
+
+    /// Returns whether the channel has a period to generate.
+    ///
+    /// Channels created by the builder always have one. A default-constructed channel has a
+    /// period of 0 ticks; while enabled, it is skipped by `irq_handler()` and reported through
+    /// `IRQ_ERROR_UNCONFIGURED` instead of generating a meaningless waveform.
+    pub fn is_configured(&self) -> bool {
+        self.period_ticks.load(Ordering::Relaxed) != 0
+    }
+
+    /// Advances the channel by `ticks` ticks that were skipped by `Spwm::irq_handler()`.
+    ///
+    /// The skipped ticks are known to contain no event, so only the tick counter and a pending
+    /// delayed trigger are moved forward. A channel enabled during the skipped ticks starts
+    /// counting from the current interrupt instead and is advanced by `restart_ticks`, the
+    /// ticks of the current interrupt before the processed one.
+    pub(crate) fn catch_up(&self, ticks: u32, restart_ticks: u32) {
+        let flags = self.schedule.swap(0, Ordering::SeqCst);
+        let ticks = if flags & SCHEDULE_RESTARTED != 0 {
+            restart_ticks
+        } else {
+            ticks
+        };
+
+        if ticks == 0 || !self.enabled.load(Ordering::Relaxed) || !self.is_configured() {
+            return;
+        }
+
+        self.enabled_ticks.add(ticks);
+        #[cfg(feature = "stats")]
+        self.account_ticks(ticks);
+
+        if self.catch_up_double_pulse(ticks) || self.waiting.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let start_countdown = self.start_countdown.load(Ordering::Relaxed);
+
+        if start_countdown != 0 {
+            self.start_countdown.store(
+                start_countdown.saturating_sub(ticks).max(1),
+                Ordering::SeqCst,
+            );
+
+            return;
+        }
+
+        self.counter.store(
+            self.counter.load(Ordering::Relaxed).saturating_add(ticks),
+            Ordering::SeqCst,
+        );
+
+        let countdown = self.trigger_countdown.load(Ordering::Relaxed);
+
+        if countdown != 0 {
+            self.trigger_countdown
+                .store(countdown.saturating_sub(ticks).max(1), Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the number of upcoming ticks on which `process_tick()` would only advance the
+    /// tick counter.
+    ///
+    /// The next event is either the Off edge, the prepare callback, the end of the period, a
+    /// pending trigger or the end of the start delay.
+    /// `u32::MAX` means the channel has no scheduled event at all.
+    pub(crate) fn idle_ticks(&self) -> u32 {
+        if !self.enabled.load(Ordering::Relaxed) || !self.is_configured() {
+            return u32::MAX;
+        }
+
+        if let Some(idle_ticks) = self.double_pulse_idle_ticks() {
+            return idle_ticks;
+        }
+
+        if self.waiting.load(Ordering::Relaxed) {
+            return if self.triggered.load(Ordering::Relaxed) {
+                0
+            } else {
+                u32::MAX
+            };
+        }
+
+        if let Some(start_countdown) = self.start_countdown.load(Ordering::Relaxed).checked_sub(1) {
+            return start_countdown;
+        }
+
+        let current_ticks = self.counter.load(Ordering::Relaxed);
+        let ticks_until = |elapsed_ticks: u32| {
+            elapsed_ticks
+                .checked_sub(1)
+                .and_then(|last_ticks| last_ticks.checked_sub(current_ticks))
+        };
+        let on_ticks = self.on_ticks.load(Ordering::Relaxed);
+        let pulse_offset = self.pulse_offset.load(Ordering::Relaxed);
+        let mut idle_ticks = if self.waveform_on.load(Ordering::Relaxed) {
+            ticks_until(pulse_offset.saturating_add(on_ticks)).unwrap_or(0)
+        } else if on_ticks != 0 {
+            ticks_until(pulse_offset).unwrap_or(u32::MAX)
+        } else {
+            u32::MAX
+        };
+
+        if let Some(blanking_end) = self.blanking_end() {
+            idle_ticks = idle_ticks.min(ticks_until(blanking_end).unwrap_or(u32::MAX));
+        }
+
+        if self.locked.load(Ordering::Relaxed) {
+            if let Some(countdown) = self
+                .trigger_countdown
+                .load(Ordering::Relaxed)
+                .checked_sub(1)
+            {
+                idle_ticks = idle_ticks.min(countdown);
+            }
         } else {
-            self.on_ticks.store(on_ticks, Ordering::SeqCst);
-            self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+            idle_ticks = idle_ticks.min(ticks_until(self.effective_period_ticks()).unwrap_or(0));
+
+            if self.prepare_callback.get().is_some()
+                && let Some(prepare_ticks) = ticks_until(self.prepare_tick())
+            {
+                idle_ticks = idle_ticks.min(prepare_ticks);
+            }
+        }
+
+        if let Some(window_ticks) = self.window_idle_ticks(ticks_until) {
+            idle_ticks = idle_ticks.min(window_ticks);
+        }
+
+        idle_ticks
+    }
+
+    /// Updates the on-time ticks, applying them as the update policy of the channel requires.
+    pub(crate) fn update_on_ticks(&self, on_ticks: u32) {
+        self.latch_with_policy(self.limit_duty(on_ticks));
+    }
+
+    /// Converts a duty cycle percentage into on-time ticks for the current period.
+    #[cfg(not(feature = "duty-lut"))]
+    pub(crate) fn duty_to_ticks(&self, duty_cycle: u8) -> u32 {
+        duty_ticks(self.period_ticks.load(Ordering::Relaxed), duty_cycle)
+    }
+
+    /// Looks up the on-time ticks for a duty cycle percentage in the precomputed table.
+    #[cfg(feature = "duty-lut")]
+    pub(crate) fn duty_to_ticks(&self, duty_cycle: u8) -> u32 {
+        self.duty_lut.get(duty_cycle)
+    }
+
+    /// Scales duty cycle on-time ticks by the cap set with `Spwm::derate_all()`.
+    pub(crate) fn derated(&self, on_ticks: u32) -> u32 {
+        let derating = self.derating.load(Ordering::Relaxed);
+
+        if derating == 0 {
+            return on_ticks;
         }
+
+        let percent = MAX_DUTY_CYCLE.saturating_sub(derating);
+
+        u64::from(on_ticks)
+            .saturating_mul(u64::from(percent))
+            .checked_div(u64::from(MAX_DUTY_CYCLE))
+            .and_then(|ticks| u32::try_from(ticks).ok())
+            .unwrap_or(on_ticks)
+    }
+
+    /// Converts a duty cycle percentage into on-time ticks through the calibration table, if
+    /// the channel has one.
+    pub(crate) fn value_to_ticks(&self, duty_cycle: u8) -> u32 {
+        self.calibrated_ticks(duty_cycle)
+            .unwrap_or_else(|| self.duty_to_ticks(duty_cycle))
+    }
+
+    /// Brings the on-time ticks in line with the configured duty cycle.
+    ///
+    /// The duty cycle is re-checked after the ticks are stored, so a concurrent update that
+    /// slips in between the two steps is never overwritten by a stale value.
+    pub(crate) fn sync_on_ticks(&self) {
+        let trigger_pulse_ticks = self.trigger_pulse_ticks.load(Ordering::SeqCst);
+
+        if trigger_pulse_ticks != 0 {
+            self.update_on_ticks(trigger_pulse_ticks);
+
+            return;
+        }
+
+        loop {
+            let duty_cycle = self.duty_cycle.load(Ordering::SeqCst);
+            self.update_on_ticks(self.value_to_ticks(duty_cycle));
+
+            if self.duty_cycle.load(Ordering::SeqCst) == duty_cycle {
+                break;
+            }
+        }
+    }
+
+    /// Applies an already validated duty cycle at the next period boundary.
+    ///
+    /// Trigger outputs and signal generators are left unchanged.
+    ///
+    /// # Returns
+    /// Whether the duty cycle differed from the configured one and was applied.
+    #[inline]
+    pub(crate) fn apply_duty_cycle(&self, duty_cycle: u8) -> bool {
+        if self.check_duty_writable().is_err() || self.swap_duty_cycle(duty_cycle) == duty_cycle {
+            return false;
+        }
+
+        self.sync_on_ticks();
+
+        true
     }
 
     /// Sets the on-time ticks directly (used internally by IRQ handler).
@@ -93,42 +555,862 @@ impl SpwmChannel {
         self.period_callback.set(period_callback)
     }
 
-    /// Updates the PWM frequency for this channel.
+    /// Advances the channel by one hardware timer tick and invokes due callbacks.
+    ///
+    /// # Returns
+    /// `true` if the channel completed a PWM period on this tick.
+    pub(crate) fn process_tick(&self) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        if !self.is_configured() {
+            self.flag_error(IRQ_ERROR_UNCONFIGURED);
+
+            return false;
+        }
+
+        self.enabled_ticks.add(1);
+        #[cfg(feature = "stats")]
+        self.account_ticks(1);
+
+        if self.step_double_pulse() {
+            return false;
+        }
+
+        let waiting = self.waiting.load(Ordering::Relaxed);
+
+        // A mains-synchronized channel restarts on a zero cross even within a period
+        if (waiting || self.is_mains_synced()) && self.triggered.swap(false, Ordering::SeqCst) {
+            self.waiting.store(false, Ordering::SeqCst);
+            self.start_countdown.store(0, Ordering::SeqCst);
+            self.counter_reset();
+            self.latch_on_ticks();
+            self.start_half_cycle();
+
+            return false;
+        }
+
+        if waiting {
+            return false;
+        }
+
+        let state = self.engine_state();
+        let (next, event) = engine::step(state);
+
+        self.store_engine_state(&state, &next);
+
+        let period_end = match event {
+            TickEvent::Idle => {
+                self.prepare_period(&next);
+
+                false
+            }
+            TickEvent::Start => {
+                self.start_period();
+
+                false
+            }
+            TickEvent::PeriodEnd => {
+                self.step_frequency_ramp();
+
+                if self.period_callback_after_update.load(Ordering::Relaxed) {
+                    self.latch_on_ticks();
+                    self.invoke_period_callbacks();
+                } else {
+                    self.invoke_period_callbacks();
+                    self.latch_on_ticks();
+                }
+
+                if self.chained.load(Ordering::Relaxed) {
+                    self.waiting.store(true, Ordering::SeqCst);
+
+                    self.set_output(&SpwmState::Off);
+                } else {
+                    let delay_ticks = self
+                        .phase_delay
+                        .swap(0, Ordering::SeqCst)
+                        .saturating_add(self.end_carrier_period());
+
+                    if delay_ticks == 0 {
+                        self.start_period();
+                    } else {
+                        self.start_countdown.store(delay_ticks, Ordering::SeqCst);
+
+                        self.set_output(&SpwmState::Off);
+                    }
+                }
+
+                true
+            }
+            TickEvent::Off => {
+                self.set_output(&SpwmState::Off);
+                self.prepare_period(&next);
+
+                false
+            }
+            TickEvent::On => {
+                if !self.tripped.load(Ordering::SeqCst) {
+                    self.set_output(&SpwmState::On);
+                }
+
+                self.prepare_period(&next);
+
+                false
+            }
+        };
+
+        self.signal_measurement_window(next.counter);
+
+        period_end
+    }
+
+    /// Invokes the period callbacks at the end of a period.
+    fn invoke_period_callbacks(&self) {
+        if let Some(callback) = self.period_callback.get() {
+            callback();
+        }
+
+        if let Some(callback) = self.period_ticks_callback.get() {
+            callback(self.enabled_ticks.get());
+        }
+    }
+
+    /// Returns the value of the tick counter on which the prepare callback is invoked.
+    fn prepare_tick(&self) -> u32 {
+        self.effective_period_ticks()
+            .saturating_sub(self.prepare_lead_ticks.load(Ordering::Relaxed))
+            .max(1)
+    }
+
+    /// Returns the counter values on which the channel has events within its period.
+    ///
+    /// Covers the period end, the on and off edges (including pending updates), the prepare
+    /// callback, the measurement window and the end of the blanking window; unused events are
+    /// reported as 0.
+    pub(crate) fn event_ticks(&self) -> [u32; 9] {
+        let ticks_if = |used: bool, ticks: u32| if used { ticks } else { 0 };
+        let pulse_offset = self.pulse_offset.load(Ordering::Relaxed);
+        let (window_start_ticks, window_stop_ticks) = self.measurement_window();
+        let window_used = self.measurement_window_callback.get().is_some();
+
+        [
+            self.period_ticks.load(Ordering::Relaxed),
+            pulse_offset.saturating_add(self.on_ticks.load(Ordering::Relaxed)),
+            pulse_offset,
+            self.update_pulse_offset
+                .load(Ordering::Relaxed)
+                .saturating_add(self.update_on_ticks.load(Ordering::Relaxed)),
+            self.update_pulse_offset.load(Ordering::Relaxed),
+            ticks_if(self.prepare_callback.get().is_some(), self.prepare_tick()),
+            ticks_if(window_used, window_start_ticks),
+            ticks_if(window_used, window_stop_ticks),
+            pulse_offset.saturating_add(self.blanking_ticks.load(Ordering::Relaxed)),
+        ]
+    }
+
+    /// Invokes the prepare callback if the period of a free-running channel ends in
+    /// `prepare_lead_ticks` ticks.
+    fn prepare_period(&self, state: &EngineState) {
+        let Some(callback) = self.prepare_callback.get() else {
+            return;
+        };
+
+        if state.start_countdown == 0 && !state.locked && state.counter == self.prepare_tick() {
+            callback();
+        }
+    }
+
+    /// Captures the timing state consumed by `engine::step()`.
+    fn engine_state(&self) -> EngineState {
+        EngineState {
+            counter: self.counter.load(Ordering::Relaxed),
+            period_ticks: self.period_ticks.load(Ordering::Relaxed),
+            on_ticks: self.on_ticks.load(Ordering::Relaxed),
+            on_offset: self.pulse_offset.load(Ordering::Relaxed),
+            waveform_on: self.waveform_on.load(Ordering::Relaxed),
+            locked: self.locked.load(Ordering::Relaxed),
+            trigger_countdown: self.trigger_countdown.load(Ordering::Relaxed),
+            start_countdown: self.start_countdown.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stores the counters advanced by `engine::step()`.
+    ///
+    /// Only changed values are written back, so configuration updated concurrently from thread
+    /// context (period, on-time, lock) is never overwritten. The output state is applied by the
+    /// caller through `set_output()`.
+    fn store_engine_state(&self, state: &EngineState, next: &EngineState) {
+        if next.counter != state.counter {
+            self.counter.store(next.counter, Ordering::SeqCst);
+        }
+
+        if next.trigger_countdown != state.trigger_countdown {
+            self.trigger_countdown
+                .store(next.trigger_countdown, Ordering::SeqCst);
+        }
+
+        if next.start_countdown != state.start_countdown {
+            self.start_countdown
+                .store(next.start_countdown, Ordering::SeqCst);
+        }
+    }
+
+    /// Applies the next bit-stream or level source level, a pending one-shot pulse or a duty cycle update at the
+    /// period boundary. An applied duty cycle update is reported through the update applied callback.
+    fn latch_on_ticks(&self) {
+        self.pulse_offset.store(
+            self.update_pulse_offset.load(Ordering::SeqCst),
+            Ordering::SeqCst,
+        );
+
+        let pulse_ticks = self.pulse_ticks.swap(0, Ordering::SeqCst);
+        let mut duty_update = false;
+        let mut applied = false;
+        let update_ticks = if let Some(stream_ticks) = self.next_stream_ticks() {
+            stream_ticks
+        } else if let Some(level) = self.level_source.get().and_then(|source| source()) {
+            if level {
+                self.period_ticks.load(Ordering::Relaxed)
+            } else {
+                0
+            }
+        } else if pulse_ticks != 0 {
+            pulse_ticks
+        } else if let Some(signal_ticks) = self.signal_on_ticks() {
+            signal_ticks
+        } else {
+            duty_update = true;
+            applied = self.update_pending.swap(false, Ordering::SeqCst);
+            self.derated(
+                self.peak_on_ticks()
+                    .or_else(|| self.burst_dimmed_ticks())
+                    .unwrap_or_else(|| self.update_on_ticks.load(Ordering::Relaxed)),
+            )
+        };
+        let on_ticks = self.on_ticks.load(Ordering::Relaxed);
+        let update_ticks = if !duty_update {
+            self.limit_duty(update_ticks)
+        } else if applied {
+            self.limit_step(on_ticks, update_ticks)
+        } else {
+            update_ticks
+        };
+        let update_ticks = self.limit_continuous_on(on_ticks, update_ticks);
+
+        if update_ticks != on_ticks {
+            self.set_on_ticks(update_ticks);
+        }
+
+        if applied && let Some(callback) = self.update_applied_callback.get() {
+            callback(&AppliedUpdate {
+                old_on_ticks: on_ticks,
+                new_on_ticks: update_ticks,
+                period_ticks: self.period_ticks.load(Ordering::Relaxed),
+            });
+        }
+    }
+
+    /// Switches the output to the initial state of a new period.
+    ///
+    /// A period with zero on-time or an on-time offset switches the output off if the previous
+    /// period kept it on.
+    pub(crate) fn start_period(&self) {
+        self.tripped.store(false, Ordering::SeqCst);
+
+        if self.on_ticks.load(Ordering::Relaxed) != 0
+            && self.pulse_offset.load(Ordering::Relaxed) == 0
+        {
+            self.set_output(&SpwmState::On);
+        } else {
+            self.set_output(&SpwmState::Off);
+        }
+    }
+
+    /// Records the output state and invokes the on/off callback if the state changed.
+    ///
+    /// Constant-on and constant-off channels therefore invoke the callback only when the output
+    /// actually toggles, which keeps the IRQ short and avoids redundant writes to slow outputs
+    /// such as I/O expander pins. While the channel is paused or suspended only the waveform state
+    /// is recorded.
+    pub(crate) fn set_output(&self, state: &SpwmState) {
+        let on = matches!(state, SpwmState::On);
+
+        self.waveform_on.store(on, Ordering::SeqCst);
+
+        if !self.paused.load(Ordering::SeqCst) && !self.suspended.load(Ordering::SeqCst) {
+            self.report_output(on);
+        }
+    }
+
+    /// Holds the output off for `Spwm::suspend()` or releases it to the waveform state.
+    pub(crate) fn set_suspended(&self, suspended: bool) {
+        self.suspended.store(suspended, Ordering::SeqCst);
+
+        if !self.paused.load(Ordering::SeqCst) {
+            self.report_output(!suspended && self.waveform_on.load(Ordering::SeqCst));
+        }
+    }
+
+    /// Invokes the on/off callback if `on` differs from the last reported output state.
+    fn report_output(&self, on: bool) {
+        let state = if on { SpwmState::On } else { SpwmState::Off };
+
+        if self.output_on.swap(on, Ordering::Relaxed) == on {
+            return;
+        }
+
+        if let Some(callback) = self.on_off_callback.get() {
+            callback(&state);
+        }
+    }
+
+    /// Marks the channel as chained to a master channel.
+    ///
+    /// A chained channel runs a single period per trigger and waits for the next one in between.
+    /// An enabled channel switches over at its next period boundary, while unchaining a waiting
+    /// channel starts a free-running period on the next tick.
+    pub(crate) fn set_chained(&self, chained: bool) {
+        self.chained.store(chained, Ordering::SeqCst);
+
+        if chained {
+            if !self.enabled.load(Ordering::Relaxed) {
+                self.waiting.store(true, Ordering::SeqCst);
+            }
+        } else if self.waiting.load(Ordering::Relaxed) {
+            if self.enabled.load(Ordering::Relaxed) {
+                self.triggered.store(true, Ordering::SeqCst);
+            } else {
+                self.waiting.store(false, Ordering::SeqCst);
+            }
+        }
+
+        self.reschedule(SCHEDULE_CHANGED);
+    }
+
+    /// Returns the position within the current period, given the ticks processed by
+    /// `Spwm::irq_handler()` that the channel did not catch up on yet.
+    ///
+    /// A channel that has not started its first period yet (start delay, waiting chained
+    /// channel) or was enabled after the last processed event is at position 0.
+    pub(crate) fn phase_ticks(&self, lag_ticks: u32) -> u32 {
+        if !self.enabled.load(Ordering::Relaxed)
+            || self.waiting.load(Ordering::Relaxed)
+            || self.start_countdown.load(Ordering::Relaxed) != 0
+            || self.schedule.load(Ordering::Relaxed) & SCHEDULE_RESTARTED != 0
+        {
+            return 0;
+        }
+
+        let phase_ticks = u64::from(self.counter.load(Ordering::Relaxed))
+            .saturating_add(u64::from(lag_ticks))
+            .checked_rem(u64::from(self.effective_period_ticks()))
+            .unwrap_or(0);
+
+        u32::try_from(phase_ticks).unwrap_or(0)
+    }
+
+    /// Requests the phase shift turning the current position `phase_ticks` into `offset`.
+    ///
+    /// The shift delays the period following the next boundary by up to one period, during
+    /// which the output stays off.
+    pub(crate) fn shift_phase(&self, phase_ticks: u32, offset: u32) {
+        let period_ticks = self.effective_period_ticks();
+        let offset = offset.checked_rem(period_ticks).unwrap_or(0);
+        let delay = phase_ticks
+            .wrapping_add(period_ticks)
+            .wrapping_sub(offset)
+            .checked_rem(period_ticks)
+            .unwrap_or(0);
+
+        self.phase_delay.store(delay, Ordering::SeqCst);
+    }
+
+    /// Marks the channel as ratio-locked to a master channel.
+    ///
+    /// A locked channel only ends its period when triggered, so its period boundaries follow the
+    /// master channel exactly instead of its own tick counter.
+    pub(crate) fn set_locked(&self, locked: bool) {
+        self.locked.store(locked, Ordering::SeqCst);
+        self.trigger_countdown.store(0, Ordering::SeqCst);
+        self.reschedule(SCHEDULE_CHANGED);
+    }
+
+    /// Rescales the channel timing to a new hardware timer frequency.
+    ///
+    /// The period, the on-time of the current period and the tick counter are passed through
+    /// `scale`, so the running period continues from the same position. The next period uses the
+    /// on-time of the configured duty cycle for the new period length.
+    pub(crate) fn rescale(&self, scale: impl Fn(u32) -> u32) {
+        self.set_on_ticks(scale(self.on_ticks.load(Ordering::Relaxed)));
+        self.counter.store(
+            scale(self.counter.load(Ordering::Relaxed)),
+            Ordering::SeqCst,
+        );
+        self.set_period_ticks(scale(self.period_ticks.load(Ordering::Relaxed)));
+        if self.is_trigger_output() {
+            self.trigger_pulse_ticks.store(
+                scale(self.trigger_pulse_ticks.load(Ordering::Relaxed)).max(1),
+                Ordering::SeqCst,
+            );
+        }
+        self.sync_on_ticks();
+    }
+
+    /// Sets the period length in ticks and recomputes the on-time ticks for the configured duty.
+    pub(crate) fn set_period_ticks_keep_duty(&self, period_ticks: u32) {
+        if self.period_ticks.load(Ordering::Relaxed) != period_ticks {
+            self.set_period_ticks(period_ticks);
+            self.sync_on_ticks();
+        }
+    }
+
+    /// Requests a chained channel to start its next period.
+    pub(crate) fn trigger(&self) {
+        self.triggered.store(true, Ordering::SeqCst);
+        self.reschedule(SCHEDULE_CHANGED);
+    }
+
+    /// Requests a locked channel to end its period after `delay_ticks` ticks.
+    pub(crate) fn trigger_delayed(&self, delay_ticks: u32) {
+        self.trigger_countdown
+            .store(delay_ticks.saturating_add(1), Ordering::SeqCst);
+    }
+
+    /// Updates the PWM frequency for this channel.
+    ///
+    /// # Parameters
+    /// - `freq_hz`: Desired PWM frequency in Hz
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// Cancels a frequency ramp started with `set_frequency_target()`.
+    ///
+    /// The period length `hardware_freq_hz / freq_hz` is computed from a reciprocal of the
+    /// hardware timer frequency cached in the channel, and the validation and duty cycle
+    /// conversion that follow use multiplications and shifts only, which matters on cores
+    /// without a hardware divider. Only the first update after a change of the hardware timer
+    /// frequency divides, to refresh the cached reciprocal.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the frequency is 0, too high relative to the
+    /// hardware timer frequency (must be at least 100x lower) or in a flicker range of a
+    /// flicker-safe channel.
+    pub fn update_frequency(&self, freq_hz: u32, hardware_freq_hz: u32) -> Result<(), SpwmError> {
+        input_frequency_validate(freq_hz, hardware_freq_hz)?;
+        self.flicker_validate(freq_hz)?;
+        self.cancel_frequency_ramp();
+        let ticks = self
+            .hardware_reciprocal
+            .divide(hardware_freq_hz, freq_hz)
+            .ok_or(SpwmError::InvalidFrequency)?;
+        self.set_period_ticks(ticks);
+
+        Ok(())
+    }
+
+    /// Lengthens or shortens the period by a number of ticks without recomputing it from a
+    /// frequency.
+    ///
+    /// Meant for control loops locking the channel frequency to an external reference (mains,
+    /// GPS PPS) with small corrections of a few ticks. The trim applies to the period in
+    /// progress and accumulates over calls; the on-time follows the configured duty cycle from
+    /// the next period on. A period shortened below the ticks already elapsed ends on the next
+    /// tick.
+    ///
+    /// # Parameters
+    /// - `delta_ticks`: Ticks added to (positive) or removed from (negative) the period
+    ///
+    /// # Returns
+    /// The new period length in ticks.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the period would become shorter than 2 ticks or
+    /// overflow.
+    pub fn trim_period(&self, delta_ticks: i32) -> Result<u32, SpwmError> {
+        let period_ticks = self
+            .period_ticks
+            .load(Ordering::Relaxed)
+            .checked_add_signed(delta_ticks)
+            .filter(|&period_ticks| period_ticks >= MIN_PERIOD_TICKS)
+            .ok_or(SpwmError::InvalidFrequency)?;
+
+        self.set_period_ticks_keep_duty(period_ticks);
+
+        Ok(period_ticks)
+    }
+
+    /// Moves the on-time within the period by `offset_ticks` ticks from the period start.
+    ///
+    /// The output switches on `offset_ticks` after the period start and stays on for the
+    /// on-time set by the duty cycle, which places a pulse precisely relative to the edges of
+    /// other channels or encodes a value in the pulse position. An on-time reaching past the
+    /// period end is cut there. The offset applies from the next period on, or immediately if
+    /// the channel is disabled.
+    ///
+    /// # Parameters
+    /// - `offset_ticks`: Ticks from the period start to the start of the on-time
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidPulseWidth` if the offset is not shorter than the period.
+    pub fn set_pulse_offset(&self, offset_ticks: u32) -> Result<(), SpwmError> {
+        if offset_ticks >= self.effective_period_ticks() {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        self.update_pulse_offset
+            .store(offset_ticks, Ordering::SeqCst);
+
+        if !self.enabled.load(Ordering::Relaxed) {
+            self.pulse_offset.store(offset_ticks, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the configured on-time offset in ticks (the pending value if not yet applied).
+    pub fn pulse_offset(&self) -> u32 {
+        self.update_pulse_offset.load(Ordering::Relaxed)
+    }
+
+    /// Updates the duty cycle for this channel.
+    ///
+    /// The new on-time latches as the `UpdatePolicy` of the channel requires, by default at the
+    /// next period boundary.
+    ///
+    /// # Parameters
+    /// - `duty_cycle`: Duty cycle percentage (0-100)
+    ///
+    /// # Returns
+    /// The previously configured duty cycle, e.g. to restore it later.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100 or the channel
+    /// is a trigger output or signal generator, whose on-time is set by its pulse width or
+    /// waveform.
+    pub fn update_duty_cycle(&self, duty_cycle: u8) -> Result<u8, SpwmError> {
+        self.check_duty_writable()?;
+
+        if duty_cycle > MAX_DUTY_CYCLE {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        let previous = self.swap_duty_cycle(duty_cycle);
+        self.sync_on_ticks();
+
+        Ok(previous)
+    }
+
+    /// Updates the duty cycle for this channel, including the period in progress.
+    ///
+    /// Unlike `update_duty_cycle()`, which defers the change to the next period boundary, the
+    /// new on-time also applies to the current period, so control loops see the update within
+    /// one tick instead of up to one period. If the counter has already passed the new on-time,
+    /// the output switches off on the next tick; an output that already switched off in this
+    /// period stays off until the next period. A one-shot pulse or bit-stream period in progress
+    /// is cut or extended the same way.
+    ///
+    /// # Parameters
+    /// - `duty_cycle`: Duty cycle percentage (0-100)
+    ///
+    /// # Returns
+    /// The previously configured duty cycle.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100.
+    pub fn update_duty_cycle_immediate(&self, duty_cycle: u8) -> Result<u8, SpwmError> {
+        let previous = self.update_duty_cycle(duty_cycle)?;
+        self.update_pending.store(false, Ordering::SeqCst);
+        self.set_on_ticks(self.limit_step(
+            self.on_ticks.load(Ordering::SeqCst),
+            self.update_on_ticks.load(Ordering::SeqCst),
+        ));
+        self.reschedule(SCHEDULE_CHANGED);
+
+        Ok(previous)
+    }
+
+    /// Returns the configured duty cycle percentage.
+    ///
+    /// If the channel is enabled and an update has not been applied at the period boundary yet,
+    /// the pending value is returned.
+    pub fn duty_cycle(&self) -> u8 {
+        self.duty_cycle.load(Ordering::Relaxed)
+    }
+
+    /// Reports how the channel output behaves with its current configuration.
+    ///
+    /// The output is on for exactly `on_ticks` ticks of every period. 0 on-time ticks keep the
+    /// output off and an on-time covering the whole period keeps it on, without any edges in
+    /// between. A period shorter than 2 ticks leaves no room for both output states and is
+    /// treated as 2 ticks. The report reflects the configured duty cycle, including a pending
+    /// update, and does not cover one-shot pulses, bit streams or level sources.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{OutputWaveform, SpwmChannelBuilder};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let channel = SpwmChannelBuilder::new(100_000)
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(100)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    ///
+    /// assert_eq!(channel.validate().waveform, OutputWaveform::ConstantOn);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> ChannelValidation {
+        let period_ticks = self.effective_period_ticks();
+        let on_ticks = self.update_on_ticks.load(Ordering::Relaxed);
+        let waveform = if on_ticks == 0 {
+            OutputWaveform::ConstantOff
+        } else if on_ticks >= period_ticks && self.update_pulse_offset.load(Ordering::Relaxed) == 0
+        {
+            OutputWaveform::ConstantOn
+        } else {
+            OutputWaveform::Modulated
+        };
+
+        ChannelValidation {
+            period_ticks,
+            on_ticks,
+            waveform,
+            period_clamped: self.period_ticks.load(Ordering::Relaxed) < MIN_PERIOD_TICKS,
+        }
+    }
+
+    /// Atomically updates the duty cycle based on its current value.
+    ///
+    /// The closure receives the configured duty cycle and returns the new one. If another
+    /// context changes the duty cycle concurrently, the closure is re-evaluated with the fresh
+    /// value, so incremental adjustments from the main loop and an ISR are never lost.
+    ///
+    /// # Parameters
+    /// - `f`: Function mapping the current duty cycle percentage to the new one
+    ///
+    /// # Returns
+    /// The previously configured duty cycle.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the closure returns a value greater than 100 or
+    /// the channel is a trigger output or signal generator. The closure is not called in the
+    /// latter case.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::Spwm;
+    /// # let spwm: Spwm<1> = Spwm::new(100_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(100)
+    ///     .duty_cycle(40)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()
+    ///     .unwrap();
+    /// let previous = channel.modify_duty(|current| current + 5).unwrap();
+    /// assert_eq!(previous, 40);
+    /// assert_eq!(channel.duty_cycle(), 45);
+    /// ```
+    pub fn modify_duty<F: FnMut(u8) -> u8>(&self, mut f: F) -> Result<u8, SpwmError> {
+        self.check_duty_writable()?;
+        self.fetch_update_duty(|current| {
+            let duty_cycle = f(current);
+
+            (duty_cycle <= MAX_DUTY_CYCLE).then_some(duty_cycle)
+        })
+        .map_err(|_| SpwmError::InvalidDutyCycle)
+    }
+
+    /// Increases the duty cycle by `step` percent, saturating at 100% or the `max_duty` of the
+    /// protection profile, whichever is lower.
+    ///
+    /// # Parameters
+    /// - `step`: Duty cycle increment in percent
+    ///
+    /// # Returns
+    /// The previously configured duty cycle.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the channel is a trigger output or signal
+    /// generator.
+    pub fn increase_duty(&self, step: u8) -> Result<u8, SpwmError> {
+        self.check_duty_writable()?;
+
+        let max_duty_cycle = self.max_duty_cycle();
+
+        match self
+            .fetch_update_duty(|current| Some(current.saturating_add(step).min(max_duty_cycle)))
+        {
+            Ok(previous) | Err(previous) => Ok(previous),
+        }
+    }
+
+    /// Decreases the duty cycle by `step` percent, saturating at 0%.
+    ///
+    /// # Parameters
+    /// - `step`: Duty cycle decrement in percent
+    ///
+    /// # Returns
+    /// The previously configured duty cycle.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the channel is a trigger output or signal
+    /// generator.
+    pub fn decrease_duty(&self, step: u8) -> Result<u8, SpwmError> {
+        self.check_duty_writable()?;
+
+        match self.fetch_update_duty(|current| Some(current.saturating_sub(step))) {
+            Ok(previous) | Err(previous) => Ok(previous),
+        }
+    }
+
+    /// Rejects duty cycle writes on channels whose on-time is not set by the duty cycle.
+    ///
+    /// Trigger outputs take their on-time from the pulse width and signal generators from the
+    /// waveform.
+    pub(crate) fn check_duty_writable(&self) -> Result<(), SpwmError> {
+        if self.is_trigger_output() || self.signal_waveform().is_some() {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the share of the period the output is on in percent, as counted by
+    /// `Spwm::total_duty()`.
+    ///
+    /// This is the configured duty cycle, except for trigger outputs and signal generators,
+    /// whose on-time of the period in progress is rounded up to the next percent.
+    pub(crate) fn load_percent(&self) -> u8 {
+        if self.check_duty_writable().is_ok() {
+            return self.duty_cycle();
+        }
+
+        let period_ticks = u64::from(self.period_ticks.load(Ordering::Relaxed));
+        let on_ticks = u64::from(self.on_ticks.load(Ordering::Relaxed)).min(period_ticks);
+
+        on_ticks
+            .saturating_mul(u64::from(MAX_DUTY_CYCLE))
+            .checked_next_multiple_of(period_ticks)
+            .and_then(|scaled| scaled.checked_div(period_ticks))
+            .and_then(|percent| u8::try_from(percent).ok())
+            .unwrap_or(0)
+    }
+
+    /// Applies `f` to the configured duty cycle with a compare-exchange loop and syncs the
+    /// on-time ticks when the update succeeds.
+    fn fetch_update_duty<F: FnMut(u8) -> Option<u8>>(&self, f: F) -> Result<u8, u8> {
+        let result = self
+            .duty_cycle
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, f);
+
+        if result.is_ok() {
+            self.sync_on_ticks();
+        }
+
+        result
+    }
+
+    /// Produces a single pulse of `on_ticks` width during the next period.
+    ///
+    /// After that period the channel returns to its configured duty cycle. This is useful for
+    /// trigger outputs and handshake strobes on a channel that otherwise idles at 0% duty.
+    ///
+    /// # Parameters
+    /// - `on_ticks`: Pulse width in hardware timer ticks
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidPulseWidth` if `on_ticks` is 0 or longer than the channel period.
+    pub fn pulse_once(&self, on_ticks: u32) -> Result<(), SpwmError> {
+        if on_ticks == 0 || on_ticks > self.period_ticks.load(Ordering::Relaxed) {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        self.pulse_ticks.store(on_ticks, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Returns the channel processing priority.
+    pub fn priority(&self) -> u8 {
+        self.priority.load(Ordering::Relaxed)
+    }
+
+    /// Returns the channel load-shedding priority.
+    pub fn shed_priority(&self) -> u8 {
+        self.shed_priority.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the channel is currently enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Holds the output off while the waveform keeps running.
     ///
-    /// # Parameters
-    /// - `freq_hz`: Desired PWM frequency in Hz
-    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    /// Unlike `disable()`, the tick counter, period callbacks and triggers of chained channels
+    /// carry on, so the channel stays in phase with the other channels, e.g. when blanking an
+    /// output during a measurement.
     ///
     /// # Errors
-    /// Returns `SpwmError::InvalidFrequency` if the frequency is 0 or too high relative
-    /// to the hardware timer frequency (must be at least 100x lower).
-    pub fn update_frequency(&self, freq_hz: u32, hardware_freq_hz: u32) -> Result<(), SpwmError> {
-        input_frequency_validate(freq_hz, hardware_freq_hz)?;
-        let ticks = hardware_freq_hz / freq_hz;
-        self.set_period_ticks(ticks);
+    /// Returns `SpwmError::AlreadyPaused` if the output is already paused.
+    pub fn pause(&self) -> Result<(), SpwmError> {
+        if self.paused.swap(true, Ordering::SeqCst) {
+            return Err(SpwmError::AlreadyPaused);
+        }
+
+        self.report_output(false);
 
         Ok(())
     }
 
-    /// Updates the duty cycle for this channel.
-    ///
-    /// # Parameters
-    /// - `duty_cycle`: Duty cycle percentage (0-100)
+    /// Releases a paused output, which immediately takes the current state of the waveform
+    /// (unless the manager is suspended).
     ///
     /// # Errors
-    /// Returns `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100.
-    pub fn update_duty_cycle(&self, duty_cycle: u8) -> Result<(), SpwmError> {
-        if duty_cycle > MAX_DUTY_CYCLE {
-            return Err(SpwmError::InvalidDutyCycle);
+    /// Returns `SpwmError::NotPaused` if the output is not paused.
+    pub fn resume(&self) -> Result<(), SpwmError> {
+        if !self.paused.swap(false, Ordering::SeqCst) {
+            return Err(SpwmError::NotPaused);
         }
 
-        let period_ticks = self.period_ticks.load(Ordering::Relaxed);
-        self.update_on_ticks(period_ticks / 100 * u32::from(duty_cycle));
+        if !self.suspended.load(Ordering::SeqCst) {
+            self.report_output(self.waveform_on.load(Ordering::SeqCst));
+        }
 
         Ok(())
     }
 
-    /// Enables the channel and invokes the on/off callback with the initial state.
+    /// Returns `true` if the channel output is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Enables a disabled channel or disables an enabled one.
+    ///
+    /// # Returns
+    /// `true` if the channel is enabled after the call, `false` otherwise.
+    ///
+    /// # Errors
+    /// Returns the error reported by `enable()` or `disable()`.
+    pub fn toggle_enable(&self) -> Result<bool, SpwmError> {
+        if self.is_enabled() {
+            self.disable()?;
+
+            Ok(false)
+        } else {
+            self.enable()?;
+
+            Ok(true)
+        }
+    }
+
+    /// Enables the channel and switches the output to the initial state of the first period.
+    ///
+    /// A chained channel does not start a period right away but waits for its first trigger. A
+    /// channel built with a start delay keeps the output off and starts its first period
+    /// `start_delay_ticks` ticks later.
     ///
     /// # Errors
     /// Returns `SpwmError::AlreadyEnabled` if the channel is already enabled, or
@@ -147,16 +1429,31 @@ impl SpwmChannel {
             return Err(SpwmError::EnableFailed);
         }
 
-        if let Some(callback) = self.on_off_callback.get()
-            && self.on_ticks.load(Ordering::Relaxed) != 0
-        {
-            callback(&SpwmState::On);
+        if let Some(callback) = self.enable_callback.get() {
+            callback(true);
+        }
+
+        self.latch_staged();
+        self.start_peak();
+
+        if !self.waiting.load(Ordering::Relaxed) {
+            let start_delay_ticks = self.start_delay_ticks.load(Ordering::Relaxed);
+
+            if start_delay_ticks == 0 {
+                self.start_period();
+            } else {
+                self.start_countdown
+                    .store(start_delay_ticks, Ordering::SeqCst);
+            }
         }
 
+        self.reschedule(SCHEDULE_RESTARTED);
+
         Ok(())
     }
 
-    /// Disables the channel, resets the counter, and invokes the on/off callback with Off state.
+    /// Disables the channel, resets the counter, and switches the output off (invoking the on/off
+    /// callback if the output was on).
     ///
     /// # Errors
     /// Returns `SpwmError::AlreadyDisabled` if the channel is already disabled, or
@@ -176,9 +1473,25 @@ impl SpwmChannel {
         }
 
         self.counter.store(0, Ordering::Relaxed);
+        self.enabled_ticks.reset();
+        self.protection
+            .continuous_on_periods
+            .store(0, Ordering::Relaxed);
+        self.triggered.store(false, Ordering::SeqCst);
+        self.trigger_countdown.store(0, Ordering::SeqCst);
+        self.start_countdown.store(0, Ordering::SeqCst);
+        self.phase_delay.store(0, Ordering::SeqCst);
+        self.stop_peak();
+        self.burst_period_count.store(0, Ordering::Relaxed);
+        self.double_pulse_stage.store(0, Ordering::SeqCst);
+        self.tripped.store(false, Ordering::SeqCst);
+        self.waiting
+            .store(self.chained.load(Ordering::Relaxed), Ordering::SeqCst);
 
-        if let Some(callback) = self.on_off_callback.get() {
-            callback(&SpwmState::Off);
+        self.set_output(&SpwmState::Off);
+
+        if let Some(callback) = self.enable_callback.get() {
+            callback(false);
         }
 
         Ok(())
@@ -201,6 +1514,31 @@ pub struct SpwmChannelBuilder<T> {
     duty_cycle: u8,
     on_off_callback: Option<OnOffCallback>,
     period_callback: Option<PeriodCallback>,
+    transmit_complete_callback: Option<TransmitCompleteCallback>,
+    level_source: Option<LevelSourceCallback>,
+    update_applied_callback: Option<UpdateAppliedCallback>,
+    period_ticks_callback: Option<PeriodTicksCallback>,
+    period_callback_timing: PeriodCallbackTiming,
+    prepare_callback: Option<(PrepareCallback, u32)>,
+    measurement_window: Option<(MeasurementWindowCallback, u32, u32)>,
+    enable_callback: Option<EnableCallback>,
+    protection: Option<(ProtectionProfile, ProtectionViolationCallback)>,
+    peak_and_hold: Option<(u8, u32)>,
+    calibration: Option<&'static [(u8, u8)]>,
+    burst: Option<(u32, u32)>,
+    burst_complete_callback: Option<BurstCompleteCallback>,
+    double_pulse_complete_callback: Option<DoublePulseCompleteCallback>,
+    trigger_output: Option<(u32, u32)>,
+    signal_generator: Option<(SignalWaveform, u32)>,
+    pulse_offset_ticks: u32,
+    blanking_ticks: u32,
+    burst_dimming: u8,
+    flicker_safe: bool,
+    update_policy: UpdatePolicy,
+    priority: u8,
+    shed_priority: u8,
+    start_delay_ticks: u32,
+    period_s: u32,
     _phantom: PhantomData<T>,
 }
 
@@ -216,6 +1554,267 @@ impl<T> SpwmChannelBuilder<T> {
         self.period_callback = Some(period_callback);
         self
     }
+
+    /// Sets a period callback receiving the number of ticks since `enable()`.
+    ///
+    /// Can be used instead of or in addition to `period_callback()` to derive elapsed time at
+    /// each period end, e.g. to schedule long-horizon actions without a separate timebase. The
+    /// count includes start delays and ticks a chained channel waits for its trigger, and
+    /// restarts from 0 after `disable()`.
+    #[must_use]
+    pub fn period_ticks_callback(mut self, period_ticks_callback: PeriodTicksCallback) -> Self {
+        self.period_ticks_callback = Some(period_ticks_callback);
+        self
+    }
+
+    /// Sets the callback invoked when a bit-stream transmission completes (optional).
+    #[must_use]
+    pub fn transmit_complete_callback(
+        mut self,
+        transmit_complete_callback: TransmitCompleteCallback,
+    ) -> Self {
+        self.transmit_complete_callback = Some(transmit_complete_callback);
+        self
+    }
+
+    /// Sets the callback providing the output level of each period (optional).
+    ///
+    /// The callback is invoked at every period boundary. If it returns `Some(level)`, the whole
+    /// next period is on (`true`) or off (`false`); `None` falls back to the configured duty
+    /// cycle. This allows line-coded outputs such as `SoftSerial` to be driven by the channel.
+    #[must_use]
+    pub fn level_source(mut self, level_source: LevelSourceCallback) -> Self {
+        self.level_source = Some(level_source);
+        self
+    }
+
+    /// Sets the callback invoked when a pending duty cycle or frequency update takes effect
+    /// (optional).
+    ///
+    /// Updates of an enabled channel are applied at the next period boundary; the callback is
+    /// invoked right there with the old and new on-time, so control loops can timestamp when
+    /// their command took effect. Updates of a disabled channel and immediate duty cycle updates
+    /// apply right away and are not reported.
+    #[must_use]
+    pub fn update_applied_callback(
+        mut self,
+        update_applied_callback: UpdateAppliedCallback,
+    ) -> Self {
+        self.update_applied_callback = Some(update_applied_callback);
+        self
+    }
+
+    /// Sets whether the period callbacks run before or after pending updates are latched at
+    /// the period boundary (default: `PeriodCallbackTiming::BeforeUpdate`).
+    #[must_use]
+    pub fn period_callback_timing(mut self, period_callback_timing: PeriodCallbackTiming) -> Self {
+        self.period_callback_timing = period_callback_timing;
+        self
+    }
+
+    /// Sets the callback invoked `lead_ticks` ticks before the end of every period (optional).
+    ///
+    /// The callback can compute the next duty cycle just in time, e.g. from a fresh ADC
+    /// reading, and apply it with `update_duty_cycle()` before the boundary latches it, so the
+    /// latency between measurement and applied duty cycle stays below a period. A lead time of
+    /// 0 is treated as 1, and a lead time of a whole period or more invokes the callback one
+    /// tick into the period. Channels whose periods are driven by a master channel do not
+    /// invoke the callback, as their period end is not known in advance.
+    #[must_use]
+    pub fn prepare_callback(mut self, prepare_callback: PrepareCallback, lead_ticks: u32) -> Self {
+        self.prepare_callback = Some((prepare_callback, lead_ticks.max(1)));
+        self
+    }
+
+    /// Sets the callback invoked at the start and stop of a measurement window within every
+    /// period (optional).
+    ///
+    /// The callback receives `true` `start_ticks` after the period start and `false`
+    /// `stop_ticks` after it, e.g. to start and stop a sensor measurement during the quiet
+    /// off-time. See `SpwmChannel::set_measurement_window()`.
+    #[must_use]
+    pub fn measurement_window(
+        mut self,
+        callback: MeasurementWindowCallback,
+        start_ticks: u32,
+        stop_ticks: u32,
+    ) -> Self {
+        self.measurement_window = Some((callback, start_ticks, stop_ticks));
+        self
+    }
+
+    /// Sets the callback invoked when the channel is enabled or disabled (optional).
+    ///
+    /// Unlike the on/off callback, which follows the output within each period, this callback
+    /// follows `enable()` and `disable()`, so peripherals powering the load (e.g. a gate driver
+    /// enable pin or a boost converter) can be switched alongside the PWM. It is invoked before
+    /// the first period starts and after the output was switched off.
+    #[must_use]
+    pub fn enable_callback(mut self, enable_callback: EnableCallback) -> Self {
+        self.enable_callback = Some(enable_callback);
+        self
+    }
+
+    /// Sets a protection profile limiting the on-time of the channel (optional).
+    ///
+    /// Duty cycle updates are clamped to the maximum duty cycle when requested and spread over
+    /// several periods if they exceed the maximum step, including immediate updates. One-shot
+    /// pulses, bit-stream and level source periods are clamped at the period boundary, where the
+    /// continuous on-time limit is enforced as well. Every intervention is reported through
+    /// `violation_callback`, invoked from `irq_handler()` for limits hit at a period boundary.
+    #[must_use]
+    pub fn protection(
+        mut self,
+        profile: ProtectionProfile,
+        violation_callback: ProtectionViolationCallback,
+    ) -> Self {
+        self.protection = Some((profile, violation_callback));
+        self
+    }
+
+    /// Sets a calibration table mapping output values to duty cycles (optional).
+    ///
+    /// The configured duty cycle (from `duty_cycle()`, `update_duty_cycle()` and the other duty
+    /// cycle setters) becomes an output value in percent, and the channel runs at the duty cycle
+    /// interpolated between the `(output value, duty cycle)` points of `table`, to compensate
+    /// driver, LED or RC-filter non-linearity. Values outside the table are clamped to its
+    /// first and last point. The points must have strictly increasing output values.
+    #[must_use]
+    pub fn calibration(mut self, table: &'static [(u8, u8)]) -> Self {
+        self.calibration = Some(table);
+        self
+    }
+
+    /// Sets a peak-and-hold drive profile (optional).
+    ///
+    /// After `enable()` or `SpwmChannel::retrigger()` the channel runs at `peak_duty` until
+    /// `pull_in_ticks` ticks of whole periods have elapsed, and then drops to the configured
+    /// duty cycle, which serves as the hold duty cycle. This is the usual way to pull in a
+    /// solenoid or injector quickly and hold it with less current.
+    #[must_use]
+    pub fn peak_and_hold(mut self, peak_duty: u8, pull_in_ticks: u32) -> Self {
+        self.peak_and_hold = Some((peak_duty, pull_in_ticks));
+        self
+    }
+
+    /// Runs the channel in bursts of `periods` carrier periods, each followed by a quiet window
+    /// of `quiet_ticks` ticks with the output off (optional).
+    ///
+    /// This drives piezo and ultrasonic transducers for ranging: the burst transmits, the quiet
+    /// window listens for the echo. The first burst starts when the channel is enabled.
+    #[must_use]
+    pub fn burst(mut self, periods: u32, quiet_ticks: u32) -> Self {
+        self.burst = Some((periods, quiet_ticks));
+        self
+    }
+
+    /// Sets the callback invoked when a burst completes and its quiet window starts (optional).
+    #[must_use]
+    pub fn burst_complete_callback(
+        mut self,
+        burst_complete_callback: BurstCompleteCallback,
+    ) -> Self {
+        self.burst_complete_callback = Some(burst_complete_callback);
+        self
+    }
+
+    /// Sets the ticks from the period start to the start of the on-time (default: 0).
+    ///
+    /// See `SpwmChannel::set_pulse_offset()`.
+    #[must_use]
+    pub fn pulse_offset_ticks(mut self, pulse_offset_ticks: u32) -> Self {
+        self.pulse_offset_ticks = pulse_offset_ticks;
+        self
+    }
+
+    /// Sets the callback invoked when a double-pulse pattern started with
+    /// `SpwmChannel::double_pulse()` completes (optional).
+    #[must_use]
+    pub fn double_pulse_complete_callback(
+        mut self,
+        double_pulse_complete_callback: DoublePulseCompleteCallback,
+    ) -> Self {
+        self.double_pulse_complete_callback = Some(double_pulse_complete_callback);
+        self
+    }
+
+    /// Sets the blanking window after each on edge in ticks (default: 0).
+    ///
+    /// See `SpwmChannel::set_blanking_ticks()`.
+    #[must_use]
+    pub fn blanking_ticks(mut self, blanking_ticks: u32) -> Self {
+        self.blanking_ticks = blanking_ticks;
+        self
+    }
+
+    /// Sets the duty cycle below which periods are skipped instead of shortened (default: 0,
+    /// disabled).
+    ///
+    /// See `SpwmChannel::set_burst_dimming()`.
+    #[must_use]
+    pub fn burst_dimming(mut self, threshold: u8) -> Self {
+        self.burst_dimming = threshold;
+        self
+    }
+
+    /// Rejects channel frequencies in the known flicker ranges, at build time and on later
+    /// frequency changes (default: no constraint).
+    ///
+    /// See `assert_flicker_safe()`.
+    #[must_use]
+    pub fn flicker_safe(mut self) -> Self {
+        self.flicker_safe = true;
+        self
+    }
+
+    /// Sets when duty cycle and on-time updates latch (default: `UpdatePolicy::NextPeriod`).
+    ///
+    /// The initial duty cycle is applied before the policy takes effect.
+    #[must_use]
+    pub fn update_policy(mut self, update_policy: UpdatePolicy) -> Self {
+        self.update_policy = update_policy;
+        self
+    }
+
+    /// Sets the channel processing priority (default: 0).
+    ///
+    /// When several channels have an edge on the same tick, channels with a higher priority
+    /// have their callbacks invoked first. Channels with equal priority keep slot order.
+    #[must_use]
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the channel load-shedding priority (default: 0).
+    ///
+    /// `Spwm::shed_load()` sheds channels with a lower priority first, so essential loads
+    /// should get a higher priority than comfort loads.
+    #[must_use]
+    pub fn shed_priority(mut self, shed_priority: u8) -> Self {
+        self.shed_priority = shed_priority;
+        self
+    }
+
+    /// Sets the number of ticks between `enable()` and the start of the first period
+    /// (default: 0).
+    ///
+    /// The output stays off during the delay, which gives external devices setup time or
+    /// offsets a channel enabled later against already running channels. Chained channels
+    /// ignore the delay and start with their first trigger.
+    #[must_use]
+    pub fn start_delay_ticks(mut self, start_delay_ticks: u32) -> Self {
+        self.start_delay_ticks = start_delay_ticks;
+        self
+    }
+
+    /// Sets a period of whole seconds replacing the frequency, for time-proportioning outputs
+    /// slower than 1 Hz.
+    #[must_use]
+    pub(crate) fn period_s(mut self, period_s: u32) -> Self {
+        self.period_s = period_s;
+        self
+    }
 }
 
 impl SpwmChannelBuilder<SpwmChannelFreqHzBuildState> {
@@ -228,10 +1827,61 @@ impl SpwmChannelBuilder<SpwmChannelFreqHzBuildState> {
             duty_cycle: 0,
             on_off_callback: None,
             period_callback: None,
+            transmit_complete_callback: None,
+            level_source: None,
+            update_applied_callback: None,
+            period_callback_timing: PeriodCallbackTiming::BeforeUpdate,
+            prepare_callback: None,
+            measurement_window: None,
+            peak_and_hold: None,
+            calibration: None,
+            burst: None,
+            burst_complete_callback: None,
+            double_pulse_complete_callback: None,
+            trigger_output: None,
+            signal_generator: None,
+            pulse_offset_ticks: 0,
+            blanking_ticks: 0,
+            burst_dimming: 0,
+            flicker_safe: false,
+            update_policy: UpdatePolicy::NextPeriod,
+            enable_callback: None,
+            period_ticks_callback: None,
+            protection: None,
+            priority: 0,
+            shed_priority: 0,
+            start_delay_ticks: 0,
+            period_s: 0,
             _phantom: PhantomData,
         }
     }
 
+    /// Sets the PWM frequency like `freq_hz()`, validating it right away instead of in
+    /// `build()`.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidHardwareFrequency` if the hardware frequency is 0 or above
+    ///   `MAX_HARDWARE_FREQ_HZ`
+    /// - `SpwmError::InvalidFrequency` if the frequency is 0, too high relative to the hardware
+    ///   timer frequency (must be at least 100x lower) or in a flicker range of a flicker-safe
+    ///   channel
+    pub fn try_freq_hz(
+        self,
+        freq_hz: u32,
+    ) -> Result<SpwmChannelBuilder<SpwmChannelDutyCycleBuildState>, SpwmError> {
+        if !is_valid_hardware_frequency(self.hardware_freq_hz) {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        input_frequency_validate(freq_hz, self.hardware_freq_hz)?;
+
+        if self.flicker_safe {
+            assert_flicker_safe(freq_hz)?;
+        }
+
+        Ok(self.freq_hz(freq_hz))
+    }
+
     #[must_use]
     pub fn freq_hz(self, freq_hz: u32) -> SpwmChannelBuilder<SpwmChannelDutyCycleBuildState> {
         SpwmChannelBuilder {
@@ -240,12 +1890,110 @@ impl SpwmChannelBuilder<SpwmChannelFreqHzBuildState> {
             duty_cycle: 0,
             on_off_callback: self.on_off_callback,
             period_callback: self.period_callback,
+            transmit_complete_callback: self.transmit_complete_callback,
+            level_source: self.level_source,
+            update_applied_callback: self.update_applied_callback,
+            period_callback_timing: self.period_callback_timing,
+            prepare_callback: self.prepare_callback,
+            measurement_window: self.measurement_window,
+            peak_and_hold: self.peak_and_hold,
+            calibration: self.calibration,
+            burst: self.burst,
+            burst_complete_callback: self.burst_complete_callback,
+            double_pulse_complete_callback: self.double_pulse_complete_callback,
+            trigger_output: self.trigger_output,
+            signal_generator: self.signal_generator,
+            pulse_offset_ticks: self.pulse_offset_ticks,
+            blanking_ticks: self.blanking_ticks,
+            burst_dimming: self.burst_dimming,
+            flicker_safe: self.flicker_safe,
+            update_policy: self.update_policy,
+            enable_callback: self.enable_callback,
+            period_ticks_callback: self.period_ticks_callback,
+            protection: self.protection,
+            priority: self.priority,
+            shed_priority: self.shed_priority,
+            start_delay_ticks: self.start_delay_ticks,
+            period_s: self.period_s,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Configures a camera trigger or strobe output from a frame rate and a pulse width.
+    ///
+    /// Replaces `freq_hz()` and `duty_cycle()`: the channel produces one pulse of
+    /// `pulse_width_us` per frame, rounded to the nearest tick, and keeps that pulse width when
+    /// the frame rate is changed with `Spwm::set_frame_rate()`. Duty cycle updates are rejected.
+    /// The pulse width resolution is one hardware timer tick, and unlike `freq_hz()` the period
+    /// only needs to be longer than the pulse rather than 100 ticks.
+    ///
+    /// # Parameters
+    /// - `frame_rate_mhz`: Frame rate in mHz (e.g. `30_000` for 30 fps)
+    /// - `pulse_width_us`: Pulse width in µs
+    #[must_use]
+    pub fn trigger_output(
+        self,
+        frame_rate_mhz: u32,
+        pulse_width_us: u32,
+    ) -> SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
+        SpwmChannelBuilder {
+            hardware_freq_hz: self.hardware_freq_hz,
+            channel_freq_hz: 0,
+            duty_cycle: 0,
+            on_off_callback: self.on_off_callback,
+            period_callback: self.period_callback,
+            transmit_complete_callback: self.transmit_complete_callback,
+            level_source: self.level_source,
+            update_applied_callback: self.update_applied_callback,
+            period_callback_timing: self.period_callback_timing,
+            prepare_callback: self.prepare_callback,
+            measurement_window: self.measurement_window,
+            peak_and_hold: self.peak_and_hold,
+            calibration: self.calibration,
+            burst: self.burst,
+            burst_complete_callback: self.burst_complete_callback,
+            double_pulse_complete_callback: self.double_pulse_complete_callback,
+            trigger_output: Some((frame_rate_mhz, pulse_width_us)),
+            signal_generator: self.signal_generator,
+            pulse_offset_ticks: self.pulse_offset_ticks,
+            blanking_ticks: self.blanking_ticks,
+            burst_dimming: self.burst_dimming,
+            flicker_safe: self.flicker_safe,
+            update_policy: self.update_policy,
+            enable_callback: self.enable_callback,
+            period_ticks_callback: self.period_ticks_callback,
+            protection: self.protection,
+            priority: self.priority,
+            shed_priority: self.shed_priority,
+            start_delay_ticks: self.start_delay_ticks,
+            period_s: self.period_s,
             _phantom: PhantomData,
         }
     }
 }
 
 impl SpwmChannelBuilder<SpwmChannelDutyCycleBuildState> {
+    /// Sets the duty cycle like `duty_cycle()`, validating it right away instead of in
+    /// `build()`.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100 or above the
+    /// maximum duty cycle of the protection profile.
+    pub fn try_duty_cycle(
+        self,
+        duty_cycle: u8,
+    ) -> Result<SpwmChannelBuilder<SpwmChannelFinalizedBuildState>, SpwmError> {
+        let max_duty = self
+            .protection
+            .map_or(MAX_DUTY_CYCLE, |(profile, _)| profile.max_duty);
+
+        if duty_cycle > MAX_DUTY_CYCLE.min(max_duty) {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        Ok(self.duty_cycle(duty_cycle))
+    }
+
     #[must_use]
     pub fn duty_cycle(self, duty_cycle: u8) -> SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
         SpwmChannelBuilder {
@@ -254,6 +2002,82 @@ impl SpwmChannelBuilder<SpwmChannelDutyCycleBuildState> {
             duty_cycle,
             on_off_callback: self.on_off_callback,
             period_callback: self.period_callback,
+            transmit_complete_callback: self.transmit_complete_callback,
+            level_source: self.level_source,
+            update_applied_callback: self.update_applied_callback,
+            period_callback_timing: self.period_callback_timing,
+            prepare_callback: self.prepare_callback,
+            measurement_window: self.measurement_window,
+            peak_and_hold: self.peak_and_hold,
+            calibration: self.calibration,
+            burst: self.burst,
+            burst_complete_callback: self.burst_complete_callback,
+            double_pulse_complete_callback: self.double_pulse_complete_callback,
+            trigger_output: self.trigger_output,
+            signal_generator: self.signal_generator,
+            pulse_offset_ticks: self.pulse_offset_ticks,
+            blanking_ticks: self.blanking_ticks,
+            burst_dimming: self.burst_dimming,
+            flicker_safe: self.flicker_safe,
+            update_policy: self.update_policy,
+            enable_callback: self.enable_callback,
+            period_ticks_callback: self.period_ticks_callback,
+            protection: self.protection,
+            priority: self.priority,
+            shed_priority: self.shed_priority,
+            start_delay_ticks: self.start_delay_ticks,
+            period_s: self.period_s,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Configures a signal generator modulating the duty cycle along a waveform.
+    ///
+    /// Replaces `duty_cycle()`: the frequency set with `freq_hz()` is the carrier, and the duty
+    /// cycle of every period follows `waveform` at `signal_freq_mhz`, swinging by 50% around
+    /// 50% until changed with `SpwmChannel::set_signal_amplitude()` and
+    /// `SpwmChannel::set_signal_offset()`. Duty cycle updates are rejected.
+    ///
+    /// # Parameters
+    /// - `waveform`: Generated waveform
+    /// - `signal_freq_mhz`: Signal frequency in mHz
+    #[must_use]
+    pub fn signal_generator(
+        self,
+        waveform: SignalWaveform,
+        signal_freq_mhz: u32,
+    ) -> SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
+        SpwmChannelBuilder {
+            hardware_freq_hz: self.hardware_freq_hz,
+            channel_freq_hz: self.channel_freq_hz,
+            duty_cycle: 0,
+            on_off_callback: self.on_off_callback,
+            period_callback: self.period_callback,
+            transmit_complete_callback: self.transmit_complete_callback,
+            level_source: self.level_source,
+            update_applied_callback: self.update_applied_callback,
+            period_callback_timing: self.period_callback_timing,
+            prepare_callback: self.prepare_callback,
+            measurement_window: self.measurement_window,
+            peak_and_hold: self.peak_and_hold,
+            calibration: self.calibration,
+            burst: self.burst,
+            burst_complete_callback: self.burst_complete_callback,
+            double_pulse_complete_callback: self.double_pulse_complete_callback,
+            trigger_output: self.trigger_output,
+            signal_generator: Some((waveform, signal_freq_mhz)),
+            pulse_offset_ticks: self.pulse_offset_ticks,
+            blanking_ticks: self.blanking_ticks,
+            burst_dimming: self.burst_dimming,
+            flicker_safe: self.flicker_safe,
+            update_policy: self.update_policy,
+            enable_callback: self.enable_callback,
+            period_ticks_callback: self.period_ticks_callback,
+            protection: self.protection,
+            priority: self.priority,
+            shed_priority: self.shed_priority,
+            start_delay_ticks: self.start_delay_ticks,
+            period_s: self.period_s,
             _phantom: PhantomData,
         }
     }
@@ -264,19 +2088,48 @@ impl SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
     ///
     /// # Errors
     /// Returns an error if:
-    /// - `SpwmError::InvalidHardwareFrequency` if the hardware frequency is 0
-    /// - `SpwmError::InvalidFrequency` if the channel frequency is invalid
-    /// - `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100
+    /// - `SpwmError::InvalidHardwareFrequency` if the hardware frequency is 0 or above
+    ///   `MAX_HARDWARE_FREQ_HZ`
+    /// - `SpwmError::InvalidFrequency` if the channel frequency, trigger frame rate or signal
+    ///   frequency is invalid, or the channel frequency is in a flicker range of a flicker-safe
+    ///   channel
+    /// - `SpwmError::InvalidPulseWidth` if a burst has no carrier periods, the pulse offset is
+    ///   not shorter than the period, the measurement window does not stop after it starts, or
+    ///   the trigger pulse width is too short for the timer resolution or not shorter than the
+    ///   period
+    /// - `SpwmError::InvalidDutyCycle` if the duty cycle or the burst dimming threshold is
+    ///   greater than 100, the protection profile limits are out of range or below the duty
+    ///   cycle, or the calibration table is invalid
     /// - `SpwmError::CallbackSetError` if callbacks are not set or failed to be set
     pub fn build(self) -> Result<SpwmChannel, SpwmError> {
-        if self.hardware_freq_hz == 0 {
+        if !is_valid_hardware_frequency(self.hardware_freq_hz) {
             return Err(SpwmError::InvalidHardwareFrequency);
         }
 
         let channel = SpwmChannel::default();
 
-        channel.update_frequency(self.channel_freq_hz, self.hardware_freq_hz)?;
-        channel.update_duty_cycle(self.duty_cycle)?;
+        channel.priority.store(self.priority, Ordering::Relaxed);
+        channel
+            .blanking_ticks
+            .store(self.blanking_ticks, Ordering::Relaxed);
+        channel
+            .shed_priority
+            .store(self.shed_priority, Ordering::Relaxed);
+        channel.set_burst_dimming(self.burst_dimming)?;
+        channel
+            .flicker_safe
+            .store(self.flicker_safe, Ordering::Relaxed);
+        channel
+            .start_delay_ticks
+            .store(self.start_delay_ticks, Ordering::Relaxed);
+        channel.period_callback_after_update.store(
+            self.period_callback_timing == PeriodCallbackTiming::AfterUpdate,
+            Ordering::Relaxed,
+        );
+
+        self.set_timing(&channel)?;
+        self.set_profiles(&channel)?;
+        channel.set_update_policy(self.update_policy);
 
         match self.on_off_callback {
             Some(cb) => channel
@@ -287,21 +2140,203 @@ impl SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
             }
         }
 
-        match self.period_callback {
-            Some(cb) => channel
+        if self.period_callback.is_none() && self.period_ticks_callback.is_none() {
+            return Err(SpwmError::CallbackSetError);
+        }
+
+        if let Some(cb) = self.period_callback {
+            channel
                 .set_period_callback(cb)
-                .map_err(|_| SpwmError::CallbackSetError)?,
-            None => {
-                return Err(SpwmError::CallbackSetError);
-            }
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.period_ticks_callback {
+            channel
+                .period_ticks_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.transmit_complete_callback {
+            channel
+                .transmit_complete_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.level_source {
+            channel
+                .level_source
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.update_applied_callback {
+            channel
+                .update_applied_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some((cb, lead_ticks)) = self.prepare_callback {
+            channel
+                .prepare_lead_ticks
+                .store(lead_ticks, Ordering::Relaxed);
+            channel
+                .prepare_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some((cb, start_ticks, stop_ticks)) = self.measurement_window {
+            channel.set_measurement_window(start_ticks, stop_ticks)?;
+            channel
+                .measurement_window_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.burst_complete_callback {
+            channel
+                .burst_complete_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.double_pulse_complete_callback {
+            channel
+                .double_pulse_complete_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some(cb) = self.enable_callback {
+            channel
+                .enable_callback
+                .set(cb)
+                .map_err(|_| SpwmError::CallbackSetError)?;
         }
 
         Ok(channel)
     }
+
+    /// Sets the calibration table, the period and on-time from the frequency and duty cycle or
+    /// the trigger output, and the on-time offset.
+    fn set_timing(&self, channel: &SpwmChannel) -> Result<(), SpwmError> {
+        if let Some(table) = self.calibration {
+            channel.set_calibration(table)?;
+        }
+
+        if let Some((frame_rate_mhz, pulse_width_us)) = self.trigger_output {
+            let period_ticks = trigger::frame_period_ticks(frame_rate_mhz, self.hardware_freq_hz)?;
+            let pulse_ticks =
+                trigger::pulse_width_ticks(pulse_width_us, self.hardware_freq_hz, period_ticks)?;
+
+            channel.set_trigger_output(period_ticks, pulse_ticks);
+        } else {
+            channel.update_frequency(self.channel_freq_hz, self.hardware_freq_hz)?;
+
+            if self.period_s != 0 {
+                channel.set_period_ticks(
+                    self.hardware_freq_hz
+                        .checked_mul(self.period_s)
+                        .ok_or(SpwmError::InvalidFrequency)?,
+                );
+            }
+
+            if let Some((waveform, signal_freq_mhz)) = self.signal_generator {
+                channel.set_signal_generator(waveform, signal_freq_mhz, self.hardware_freq_hz)?;
+            } else {
+                channel.update_duty_cycle(self.duty_cycle)?;
+            }
+        }
+
+        channel.set_pulse_offset(self.pulse_offset_ticks)
+    }
+
+    /// Applies the burst, protection and peak-and-hold profiles after validating them.
+    fn set_profiles(&self, channel: &SpwmChannel) -> Result<(), SpwmError> {
+        if let Some((periods, quiet_ticks)) = self.burst {
+            if periods == 0 {
+                return Err(SpwmError::InvalidPulseWidth);
+            }
+
+            channel.burst_periods.store(periods, Ordering::Relaxed);
+            channel.quiet_ticks.store(quiet_ticks, Ordering::Relaxed);
+        }
+
+        if let Some((profile, violation_callback)) = self.protection {
+            if profile.max_duty > MAX_DUTY_CYCLE
+                || profile.max_step == 0
+                || profile.max_step > MAX_DUTY_CYCLE
+                || self.duty_cycle > profile.max_duty
+            {
+                return Err(SpwmError::InvalidDutyCycle);
+            }
+
+            channel
+                .protection
+                .profile
+                .set(profile)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+            channel
+                .protection
+                .violation_callback
+                .set(violation_callback)
+                .map_err(|_| SpwmError::CallbackSetError)?;
+        }
+
+        if let Some((peak_duty, pull_in_ticks)) = self.peak_and_hold {
+            let max_duty = self
+                .protection
+                .map_or(MAX_DUTY_CYCLE, |(profile, _)| profile.max_duty);
+
+            if peak_duty > max_duty {
+                return Err(SpwmError::InvalidDutyCycle);
+            }
+
+            channel.peak_duty.store(peak_duty, Ordering::Relaxed);
+            channel
+                .pull_in_ticks
+                .store(pull_in_ticks, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
 }
 
-fn input_frequency_validate(freq_hz: u32, hardware_freq_hz: u32) -> Result<(), SpwmError> {
-    if freq_hz == 0 || freq_hz > hardware_freq_hz / FREQUENCY_DIFFERENCE_REQUIRED {
+/// Returns the on-time ticks of `duty_cycle` percent of a `period_ticks` long period.
+///
+/// The product is formed before dividing, so periods that are not a multiple of 100 ticks keep
+/// their exact on-time instead of multiplying a truncated 1% step back up; 100% always covers
+/// the whole period. The division by 100 is a 64-bit multiplication with a fixed-point
+/// fraction and a shift, followed by a correction of the last tick, so the conversion
+/// needs no divide instruction.
+pub(crate) fn duty_ticks(period_ticks: u32, duty_cycle: u8) -> u32 {
+    if duty_cycle >= MAX_DUTY_CYCLE {
+        return period_ticks;
+    }
+
+    // `duty_cycle * 2^32 / 100` rounded up: every step is rounded up by 0.04, which the
+    // subtracted `duty_cycle / 25` (as `duty_cycle * 41 >> 10`, exact below 100) takes back
+    let fraction = u32::from(duty_cycle)
+        .saturating_mul(DUTY_STEP)
+        .saturating_sub(u32::from(duty_cycle).saturating_mul(41) >> 10);
+    let on_ticks = u64::from(period_ticks).saturating_mul(u64::from(fraction)) >> 32;
+    let exceeds = on_ticks.saturating_mul(u64::from(MAX_DUTY_CYCLE))
+        > u64::from(period_ticks).saturating_mul(u64::from(duty_cycle));
+
+    u32::try_from(on_ticks.saturating_sub(u64::from(exceeds))).unwrap_or(period_ticks)
+}
+
+pub(crate) fn input_frequency_validate(
+    freq_hz: u32,
+    hardware_freq_hz: u32,
+) -> Result<(), SpwmError> {
+    let min_hardware_freq_hz =
+        u64::from(freq_hz).saturating_mul(u64::from(FREQUENCY_DIFFERENCE_REQUIRED));
+
+    if freq_hz == 0 || min_hardware_freq_hz > u64::from(hardware_freq_hz) {
         return Err(SpwmError::InvalidFrequency);
     }
 
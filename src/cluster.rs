@@ -0,0 +1,142 @@
+//! Several SPWM managers sharing one hardware timer.
+//!
+//! `SpwmCluster` owns a group of `Spwm` instances, each running at the hardware timer frequency
+//! divided by its own prescaler, behind a single interrupt handler. A fast group (e.g. a 20 kHz
+//! motor drive) keeps the full timer resolution, while a slow group (LEDs, relays) is only
+//! processed on every n-th tick instead of burning CPU time on every tick.
+
+use crate::{Spwm, SpwmError, is_valid_hardware_frequency};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// An `Spwm` instance of a cluster with its prescaler.
+///
+/// # Fields
+/// - `spwm`: The manager driven by the cluster
+/// - `prescaler`: Number of hardware timer ticks per tick of the manager
+/// - `countdown`: Hardware timer ticks until the next tick of the manager
+struct ClusterMember<const N: usize, const A: usize> {
+    spwm: Spwm<N, A>,
+    prescaler: u32,
+    countdown: AtomicU32,
+}
+
+/// A group of `M` software PWM managers with up to `N` channels and `A` alarms each, driven by
+/// one hardware timer.
+///
+/// # Example
+/// ```
+/// # use spwm::SpwmCluster;
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// // 2 MHz timer: the motor group runs at 2 MHz, the LED group at 10 kHz
+/// let mut cluster = SpwmCluster::<2, 2>::new(2_000_000, [1, 200])?;
+/// let leds = cluster.instance_mut(1).unwrap();
+/// let channel = leds
+///     .create_channel()
+///     .freq_hz(100)
+///     .duty_cycle(25)
+///     .on_off_callback(|_| {})
+///     .period_callback(|| {})
+///     .build()?;
+/// let id = leds.register_channel(channel)?;
+///
+/// leds.get_channel(id).unwrap().enable()?;
+/// // in the timer interrupt
+/// cluster.irq_handler();
+/// # Ok(())
+/// # }
+/// ```
+pub struct SpwmCluster<const N: usize, const M: usize, const A: usize = 0> {
+    members: [ClusterMember<N, A>; M],
+}
+
+impl<const N: usize, const M: usize, const A: usize> SpwmCluster<N, M, A> {
+    /// Creates a cluster of managers running at `hardware_freq_hz / prescalers[i]`.
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: Frequency of the shared hardware timer in Hertz
+    /// - `prescalers`: Number of hardware timer ticks per tick of every manager
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidTickDivider` if a prescaler is 0 or does not divide the hardware
+    ///   timer frequency
+    /// - `SpwmError::InvalidHardwareFrequency` if the frequency of a manager is 0 or above
+    ///   `MAX_HARDWARE_FREQ_HZ` (see `Spwm::try_new()`)
+    pub fn new(hardware_freq_hz: u32, prescalers: [u32; M]) -> Result<Self, SpwmError> {
+        if prescalers
+            .iter()
+            .any(|&prescaler| hardware_freq_hz.checked_rem(prescaler) != Some(0))
+        {
+            return Err(SpwmError::InvalidTickDivider);
+        }
+
+        if !prescalers.iter().all(|&prescaler| {
+            hardware_freq_hz
+                .checked_div(prescaler)
+                .is_some_and(is_valid_hardware_frequency)
+        }) {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        Ok(Self {
+            members: prescalers.map(|prescaler| ClusterMember {
+                spwm: Spwm::new(hardware_freq_hz.checked_div(prescaler).unwrap_or(0)),
+                prescaler,
+                countdown: AtomicU32::new(prescaler),
+            }),
+        })
+    }
+
+    /// Returns a manager of the cluster.
+    ///
+    /// # Parameters
+    /// - `index`: Index of the manager in the cluster
+    pub fn instance(&self, index: usize) -> Option<&Spwm<N, A>> {
+        self.members.get(index).map(|member| &member.spwm)
+    }
+
+    /// Returns a manager of the cluster for configuration, e.g. to register channels.
+    ///
+    /// # Parameters
+    /// - `index`: Index of the manager in the cluster
+    pub fn instance_mut(&mut self, index: usize) -> Option<&mut Spwm<N, A>> {
+        self.members.get_mut(index).map(|member| &mut member.spwm)
+    }
+
+    /// Returns the prescaler of a manager of the cluster.
+    ///
+    /// # Parameters
+    /// - `index`: Index of the manager in the cluster
+    pub fn prescaler(&self, index: usize) -> Option<u32> {
+        self.members.get(index).map(|member| member.prescaler)
+    }
+
+    /// Handles the hardware timer interrupt by running `irq_handler()` of every manager whose
+    /// prescaler elapsed on this tick.
+    ///
+    /// Managers are processed in index order, so the group with the tightest timing should be
+    /// placed first.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[interrupt]
+    /// fn TIMER_IRQ() {
+    ///     cluster.irq_handler();
+    /// }
+    /// ```
+    pub fn irq_handler(&self) {
+        for member in &self.members {
+            let countdown = member.countdown.load(Ordering::Relaxed);
+
+            if countdown > 1 {
+                member
+                    .countdown
+                    .store(countdown.saturating_sub(1), Ordering::Relaxed);
+                continue;
+            }
+
+            member.countdown.store(member.prescaler, Ordering::Relaxed);
+            member.spwm.irq_handler();
+        }
+    }
+}
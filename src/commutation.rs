@@ -0,0 +1,386 @@
+//! Six-step (trapezoidal) BLDC commutation.
+//!
+//! Each motor phase is driven by a high-side and a low-side switch channel. In every one of the
+//! six commutation steps one phase is sourced through its high side, which carries the PWM
+//! duty cycle, one phase is sunk through its constantly on low side and the third phase floats.
+//! The step is derived from the hall sensor state through a decode callback, so the motor is
+//! commutated entirely from software PWM outputs.
+//!
+//! Hall edges reported with their tick from `Spwm::ticks()` also measure the electrical period,
+//! which times commutation ahead of the next hall edge (phase advance) without an extra
+//! hardware timer.
+
+use crate::{ChannelId, Spwm, SpwmError};
+
+/// Number of motor phases.
+const PHASES: usize = 3;
+
+/// Sourcing and sinking phase of every commutation step.
+const STEPS: [(usize, usize); 6] = [(0, 1), (0, 2), (1, 2), (1, 0), (2, 0), (2, 1)];
+
+/// Number of commutation steps per electrical period.
+const STEP_COUNT: u8 = 6;
+
+/// Electrical degrees per commutation step.
+const STEP_DEGREES: u8 = 60;
+
+/// Returns the step following `step` in forward rotation.
+fn following_step(step: u8) -> u8 {
+    let step = step.saturating_add(1);
+
+    if step >= STEP_COUNT { 0 } else { step }
+}
+
+/// Returns the step preceding `step` in forward rotation.
+fn preceding_step(step: u8) -> u8 {
+    step.checked_sub(1).unwrap_or(STEP_COUNT.saturating_sub(1))
+}
+
+/// Callback decoding a hall sensor state into a commutation step.
+///
+/// # Parameters
+/// - `hall_state`: Hall sensor inputs, one bit per sensor
+///
+/// # Returns
+/// The commutation step (0-5), or `None` for an invalid state.
+pub type HallDecodeCallback = fn(u8) -> Option<u8>;
+
+/// Decodes the hall states of 120° spaced sensors in the common sequence
+/// `001, 011, 010, 110, 100, 101` into steps 0-5.
+///
+/// `000` and `111` cannot occur with working sensors and decode to `None`.
+#[must_use]
+pub fn decode_hall_120(hall_state: u8) -> Option<u8> {
+    match hall_state & 0b111 {
+        0b001 => Some(0),
+        0b011 => Some(1),
+        0b010 => Some(2),
+        0b110 => Some(3),
+        0b100 => Some(4),
+        0b101 => Some(5),
+        _ => None,
+    }
+}
+
+/// Six-step commutator driving three phases through high-side and low-side channels.
+///
+/// # Example
+///
+/// ```
+/// # use spwm::{decode_hall_120, Commutator, Spwm};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<6>::new(1_000_000);
+/// let mut ids = [0; 6];
+///
+/// for id in &mut ids {
+///     let channel = spwm.create_channel()
+///         .freq_hz(10_000)
+///         .duty_cycle(40)
+///         .on_off_callback(|_| {})
+///         .period_callback(|| {})
+///         .build()?;
+///     *id = spwm.register_channel(channel)?;
+/// }
+///
+/// let mut commutator = Commutator::new(
+///     &spwm,
+///     [ids[0], ids[1], ids[2]],
+///     [ids[3], ids[4], ids[5]],
+///     decode_hall_120,
+/// )?;
+///
+/// // called from the hall sensor interrupt
+/// commutator.commutate(&spwm, 0b011)?;
+///
+/// assert_eq!(commutator.step(), Some(1));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Commutator {
+    high: [ChannelId; PHASES],
+    low: [ChannelId; PHASES],
+    decode: HallDecodeCallback,
+    step: Option<u8>,
+    advance_degrees: u8,
+    hall_step: Option<u8>,
+    hall_tick: u64,
+    step_ticks: Option<u64>,
+    pending: Option<(u64, u8)>,
+}
+
+impl Commutator {
+    /// Configures the channels for six-step commutation.
+    ///
+    /// The high-side channels keep their frequency and duty cycle, which sets the motor
+    /// voltage; the low-side channels are set to 100% duty cycle. All channels are disabled,
+    /// so the motor coasts until the first commutation.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `high`: The identifiers of the high-side channels of phases A, B and C
+    /// - `low`: The identifiers of the low-side channels of phases A, B and C
+    /// - `decode`: Callback decoding the hall sensor state into a commutation step
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel is not registered or used twice.
+    pub fn new<const N: usize, const A: usize>(
+        spwm: &Spwm<N, A>,
+        high: [ChannelId; PHASES],
+        low: [ChannelId; PHASES],
+        decode: HallDecodeCallback,
+    ) -> Result<Self, SpwmError> {
+        for (i, id) in high.iter().chain(&low).enumerate() {
+            if spwm.get_channel(*id).is_none()
+                || high
+                    .iter()
+                    .chain(&low)
+                    .skip(i.saturating_add(1))
+                    .any(|other| other == id)
+            {
+                return Err(SpwmError::InvalidChannel);
+            }
+        }
+
+        for id in low {
+            spwm.get_channel(id)
+                .ok_or(SpwmError::InvalidChannel)?
+                .update_duty_cycle(100)?;
+        }
+
+        let mut commutator = Self {
+            high,
+            low,
+            decode,
+            step: None,
+            advance_degrees: 0,
+            hall_step: None,
+            hall_tick: 0,
+            step_ticks: None,
+            pending: None,
+        };
+
+        commutator.set_step(spwm, None)?;
+
+        Ok(commutator)
+    }
+
+    /// Returns the applied commutation step, or `None` while the motor coasts.
+    #[must_use]
+    pub fn step(&self) -> Option<u8> {
+        self.step
+    }
+
+    /// Decodes a hall sensor state and applies its commutation step.
+    ///
+    /// Meant to be called from the hall sensor interrupt. An invalid hall state lets the motor
+    /// coast.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `hall_state`: Hall sensor inputs, one bit per sensor
+    ///
+    /// # Returns
+    /// The applied commutation step.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn commutate<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        hall_state: u8,
+    ) -> Result<Option<u8>, SpwmError> {
+        let step = (self.decode)(hall_state);
+
+        self.set_step(spwm, step)?;
+
+        Ok(self.step)
+    }
+
+    /// Applies a commutation step, or lets the motor coast with `None`.
+    ///
+    /// Channels that are switched off are disabled before the channels of the new step are
+    /// enabled, so the high and low side of a phase are never on at the same time. Steps above
+    /// 5 let the motor coast.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `step`: Commutation step (0-5)
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn set_step<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        step: Option<u8>,
+    ) -> Result<(), SpwmError> {
+        let phases = step.and_then(|step| STEPS.get(usize::from(step)));
+        let (source, sink) =
+            phases.map_or((None, None), |&(source, sink)| (Some(source), Some(sink)));
+        let outputs = |phase: usize| {
+            self.high
+                .get(phase)
+                .map(|&id| (id, source == Some(phase)))
+                .into_iter()
+                .chain(self.low.get(phase).map(|&id| (id, sink == Some(phase))))
+        };
+
+        for (id, on) in (0..PHASES).flat_map(outputs) {
+            let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            if !on && channel.is_enabled() {
+                channel.disable()?;
+            }
+        }
+
+        for (id, on) in (0..PHASES).flat_map(outputs) {
+            let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            if on && !channel.is_enabled() {
+                channel.enable()?;
+            }
+        }
+
+        self.step = phases.and(step);
+
+        Ok(())
+    }
+
+    /// Sets the phase advance in electrical degrees (default: 0).
+    ///
+    /// With a nonzero advance, `on_hall_change()` schedules the step following a hall edge
+    /// `advance_degrees` before the next hall edge is expected, which compensates the winding
+    /// inductance at speed. The scheduled step is applied by `poll()`.
+    ///
+    /// # Parameters
+    /// - `advance_degrees`: Phase advance in electrical degrees (0-59)
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidConfiguration` if the advance is 60 degrees or more.
+    pub fn set_phase_advance(&mut self, advance_degrees: u8) -> Result<(), SpwmError> {
+        if advance_degrees >= STEP_DEGREES {
+            return Err(SpwmError::InvalidConfiguration);
+        }
+
+        self.advance_degrees = advance_degrees;
+
+        Ok(())
+    }
+
+    /// Returns the measured electrical period in ticks, or `None` until two consecutive hall
+    /// edges of a rotation were seen.
+    #[must_use]
+    pub fn electrical_period_ticks(&self) -> Option<u64> {
+        self.step_ticks
+            .map(|ticks| ticks.saturating_mul(u64::from(STEP_COUNT)))
+    }
+
+    /// Handles a hall sensor change: measures the time since the previous hall edge, applies the
+    /// decoded step and schedules the advanced next step.
+    ///
+    /// Meant to be called from the hall sensor interrupt with `Spwm::ticks()`. The interval is
+    /// only measured between adjacent steps, so an invalid or skipped hall state restarts the
+    /// measurement. A step already applied ahead of its hall edge by `poll()` is not applied
+    /// again.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `hall_state`: Hall sensor inputs, one bit per sensor
+    /// - `tick`: Tick count of the hall edge
+    ///
+    /// # Returns
+    /// The applied commutation step.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn on_hall_change<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        hall_state: u8,
+        tick: u64,
+    ) -> Result<Option<u8>, SpwmError> {
+        let step = (self.decode)(hall_state).filter(|&step| step < STEP_COUNT);
+        let next = match (self.hall_step, step) {
+            (Some(previous), Some(step)) if following_step(previous) == step => {
+                Some(following_step(step))
+            }
+            (Some(previous), Some(step)) if following_step(step) == previous => {
+                Some(preceding_step(step))
+            }
+            _ => None,
+        };
+
+        self.step_ticks = next.map(|_| tick.saturating_sub(self.hall_tick));
+        self.hall_step = step;
+        self.hall_tick = tick;
+        self.pending = None;
+
+        if self.step != step || step.is_none() {
+            self.set_step(spwm, step)?;
+        }
+
+        if let (Some(next), Some(step_ticks)) = (next, self.step_ticks)
+            && self.advance_degrees != 0
+        {
+            let delay = step_ticks
+                .saturating_mul(u64::from(STEP_DEGREES.saturating_sub(self.advance_degrees)))
+                .checked_div(u64::from(STEP_DEGREES))
+                .unwrap_or(0);
+
+            self.pending = Some((tick.saturating_add(delay), next));
+        }
+
+        Ok(self.step)
+    }
+
+    /// Applies the advanced step scheduled by `on_hall_change()` once it is due.
+    ///
+    /// Call it with `Spwm::ticks()` often enough for the required timing accuracy, e.g. from a
+    /// period callback or a periodic alarm.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `tick`: Current tick count
+    ///
+    /// # Returns
+    /// The step applied by this call, if any.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn poll<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        tick: u64,
+    ) -> Result<Option<u8>, SpwmError> {
+        let Some((_, step)) = self.pending.filter(|&(due, _)| tick >= due) else {
+            return Ok(None);
+        };
+
+        self.pending = None;
+        self.set_step(spwm, Some(step))?;
+
+        Ok(Some(step))
+    }
+
+    /// Sets the duty cycle of the high-side channels, i.e. the motor voltage.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `duty_cycle`: Duty cycle percentage (0-100)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100
+    /// - `SpwmError::InvalidChannel` if a channel was removed from `spwm`
+    pub fn set_duty_cycle<const N: usize, const A: usize>(
+        &self,
+        spwm: &Spwm<N, A>,
+        duty_cycle: u8,
+    ) -> Result<(), SpwmError> {
+        for id in self.high {
+            spwm.get_channel(id)
+                .ok_or(SpwmError::InvalidChannel)?
+                .update_duty_cycle(duty_cycle)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+//! Serializable channel configuration.
+//!
+//! `SpwmConfig` describes the runtime parameters of every channel slot of an `Spwm` manager, so
+//! it can be stored in flash/EEPROM with any `serde` format (e.g. postcard or CBOR) and applied
+//! at boot or through a configuration protocol. Callbacks are not part of the configuration:
+//! channels are built and registered as usual, and the configuration adjusts them afterwards.
+
+use crate::channel::{MAX_DUTY_CYCLE, input_frequency_validate};
+use crate::{Spwm, SpwmError};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Runtime parameters of a single channel.
+///
+/// # Fields
+/// - `freq_hz`: Channel frequency in Hz
+/// - `duty_cycle`: Duty cycle percentage (0-100)
+/// - `priority`: Processing priority within `Spwm::irq_handler()`
+/// - `enabled`: Whether the channel output runs
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    pub freq_hz: u32,
+    pub duty_cycle: u8,
+    pub priority: u8,
+    pub enabled: bool,
+}
+
+/// Runtime parameters of all channel slots of an `Spwm<N>` manager.
+///
+/// # Fields
+/// - `channels`: Configuration per channel slot; `None` leaves the slot untouched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpwmConfig<const N: usize> {
+    pub channels: [Option<ChannelConfig>; N],
+}
+
+impl<const N: usize> Default for SpwmConfig<N> {
+    fn default() -> Self {
+        Self {
+            channels: [None; N],
+        }
+    }
+}
+
+impl<const N: usize> Serialize for SpwmConfig<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+
+        for channel in &self.channels {
+            tuple.serialize_element(channel)?;
+        }
+
+        tuple.end()
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for SpwmConfig<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(N, ConfigVisitor(PhantomData))
+    }
+}
+
+/// Visitor reading the fixed number of channel slots of an `SpwmConfig`.
+struct ConfigVisitor<const N: usize>(PhantomData<SpwmConfig<N>>);
+
+impl<'de, const N: usize> Visitor<'de> for ConfigVisitor<N> {
+    type Value = SpwmConfig<N>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of {N} channel configurations")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut config = SpwmConfig::default();
+
+        for (i, channel) in config.channels.iter_mut().enumerate() {
+            *channel = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Applies a configuration to the registered channels.
+    ///
+    /// The whole configuration is validated before any channel is changed, so an invalid entry
+    /// leaves all channels as they were. Duty cycle changes of running channels take effect at
+    /// their next period boundary.
+    ///
+    /// # Parameters
+    /// - `config`: Configuration per channel slot
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if a configured slot has no registered channel
+    /// - `SpwmError::InvalidFrequency` if a frequency is 0 or too high for the hardware timer
+    /// - `SpwmError::InvalidDutyCycle` if a duty cycle is greater than 100
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{ChannelConfig, Spwm, SpwmConfig};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<2>::new(100_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(0)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    /// let id = spwm.register_channel(channel)?;
+    /// let mut config = SpwmConfig::<2>::default();
+    ///
+    /// config.channels[id] = Some(ChannelConfig {
+    ///     freq_hz: 500,
+    ///     duty_cycle: 30,
+    ///     priority: 0,
+    ///     enabled: true,
+    /// });
+    /// spwm.apply_config(&config)?;
+    /// assert!(spwm.get_channel(id).unwrap().is_enabled());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_config(&mut self, config: &SpwmConfig<N>) -> Result<(), SpwmError> {
+        for (id, channel_config) in config.channels.iter().enumerate() {
+            if let Some(channel_config) = channel_config {
+                if self.get_channel(id).is_none() {
+                    return Err(SpwmError::InvalidChannel);
+                }
+
+                input_frequency_validate(channel_config.freq_hz, self.freq_hz)?;
+
+                if channel_config.duty_cycle > MAX_DUTY_CYCLE {
+                    return Err(SpwmError::InvalidDutyCycle);
+                }
+            }
+        }
+
+        for (id, channel_config) in config.channels.iter().enumerate() {
+            let Some(channel_config) = channel_config else {
+                continue;
+            };
+            let channel = self.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            channel.update_frequency(channel_config.freq_hz, self.freq_hz)?;
+            channel.update_duty_cycle(channel_config.duty_cycle)?;
+
+            if channel_config.enabled != channel.is_enabled() {
+                channel.toggle_enable()?;
+            }
+
+            self.set_channel_priority(id, channel_config.priority)?;
+        }
+
+        Ok(())
+    }
+}
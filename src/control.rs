@@ -0,0 +1,179 @@
+//! Cross-core control of an SPWM manager.
+//!
+//! `Spwm` and its channels are not `Sync`: the callbacks and bit-stream buffers they hold are
+//! not meant to be shared, so a manager lives on the core running its timer interrupt. On
+//! dual-core microcontrollers (e.g. RP2040) the application logic often runs on the other
+//! core. `SpwmControl` is a `Sync` mailbox of atomics placed in a `static`: the control core
+//! posts duty cycle and enable requests, and the interrupt core applies them with
+//! `Spwm::apply_control()`. Requests are published with release and taken with acquire
+//! ordering, so the duty cycle written with a request is always visible when it is applied.
+
+use crate::channel::MAX_DUTY_CYCLE;
+use crate::{ChannelId, Spwm, SpwmError};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Request flag: a new duty cycle was posted.
+const REQUEST_DUTY: u8 = 1 << 0;
+
+/// Request flag: the channel is to be enabled.
+const REQUEST_ENABLE: u8 = 1 << 1;
+
+/// Request flag: the channel is to be disabled.
+const REQUEST_DISABLE: u8 = 1 << 2;
+
+/// Mailbox of channel requests shared between a control core and the core running
+/// `Spwm::irq_handler()`.
+///
+/// Later requests of the same kind overwrite earlier ones not yet applied, and an enable
+/// request cancels a pending disable request and vice versa.
+///
+/// # Example
+/// ```
+/// # use spwm::{Spwm, SpwmControl};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// static CONTROL: SpwmControl<2> = SpwmControl::new();
+///
+/// // control core
+/// CONTROL.request_duty_cycle(1, 40)?;
+/// CONTROL.request_enable(1)?;
+///
+/// // interrupt core, e.g. at the start of the timer interrupt
+/// # let spwm = Spwm::<2>::new(100_000);
+/// spwm.apply_control(&CONTROL);
+/// spwm.irq_handler();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SpwmControl<const N: usize> {
+    requests: [AtomicU8; N],
+    duty_cycles: [AtomicU8; N],
+}
+
+impl<const N: usize> SpwmControl<N> {
+    /// Creates an empty mailbox, usable as a `static` initializer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            requests: [const { AtomicU8::new(0) }; N],
+            duty_cycles: [const { AtomicU8::new(0) }; N],
+        }
+    }
+
+    /// Posts a duty cycle update of a channel.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel
+    /// - `duty_cycle`: Duty cycle percentage (0-100)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the identifier is out of range
+    /// - `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100
+    pub fn request_duty_cycle(
+        &self,
+        channel_id: ChannelId,
+        duty_cycle: u8,
+    ) -> Result<(), SpwmError> {
+        let (requests, duty_cycles) = self.mailbox(channel_id)?;
+
+        if duty_cycle > MAX_DUTY_CYCLE {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        duty_cycles.store(duty_cycle, Ordering::Relaxed);
+        requests.fetch_or(REQUEST_DUTY, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Posts an enable request of a channel.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the identifier is out of range.
+    pub fn request_enable(&self, channel_id: ChannelId) -> Result<(), SpwmError> {
+        self.post(channel_id, REQUEST_ENABLE, REQUEST_DISABLE)
+    }
+
+    /// Posts a disable request of a channel.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the identifier is out of range.
+    pub fn request_disable(&self, channel_id: ChannelId) -> Result<(), SpwmError> {
+        self.post(channel_id, REQUEST_DISABLE, REQUEST_ENABLE)
+    }
+
+    /// Returns whether requests of a channel were not applied yet.
+    pub fn is_pending(&self, channel_id: ChannelId) -> bool {
+        self.requests
+            .get(channel_id)
+            .is_some_and(|requests| requests.load(Ordering::Acquire) != 0)
+    }
+
+    /// Sets `flag` and clears `cancelled` in the requests of a channel.
+    fn post(&self, channel_id: ChannelId, flag: u8, cancelled: u8) -> Result<(), SpwmError> {
+        let (requests, _) = self.mailbox(channel_id)?;
+
+        // The closure always returns `Some`, so the update cannot fail
+        let _ = requests.fetch_update(Ordering::Release, Ordering::Relaxed, |pending| {
+            Some((pending & !cancelled) | flag)
+        });
+
+        Ok(())
+    }
+
+    /// Returns the request flags and posted duty cycle of a channel.
+    fn mailbox(&self, channel_id: ChannelId) -> Result<(&AtomicU8, &AtomicU8), SpwmError> {
+        self.requests
+            .get(channel_id)
+            .zip(self.duty_cycles.get(channel_id))
+            .ok_or(SpwmError::InvalidChannel)
+    }
+}
+
+impl<const N: usize> Default for SpwmControl<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Applies the requests posted to a control mailbox by another core.
+    ///
+    /// Must be called on the core running `irq_handler()`, e.g. at the start of the timer
+    /// interrupt, so the requests never race with the tick processing. Duty cycle updates take
+    /// effect at the next period boundary as with `SpwmChannel::update_duty_cycle()`. Requests
+    /// for unregistered channels are dropped, and enabling an enabled channel or disabling a
+    /// disabled one has no effect.
+    ///
+    /// # Returns
+    /// The number of channels whose requests were applied.
+    pub fn apply_control(&self, control: &SpwmControl<N>) -> usize {
+        control
+            .requests
+            .iter()
+            .zip(&control.duty_cycles)
+            .enumerate()
+            .filter(|(_, (requests, _))| requests.load(Ordering::Relaxed) != 0)
+            .filter(|(id, (requests, duty_cycle))| {
+                let requests = requests.swap(0, Ordering::Acquire);
+                let Some(channel) = self.get_channel(*id) else {
+                    return false;
+                };
+
+                if requests & REQUEST_DUTY != 0 {
+                    channel.apply_duty_cycle(duty_cycle.load(Ordering::Relaxed));
+                }
+
+                if requests & REQUEST_DISABLE != 0 {
+                    let _ = channel.disable();
+                }
+
+                if requests & REQUEST_ENABLE != 0 {
+                    let _ = channel.enable();
+                }
+
+                true
+            })
+            .count()
+    }
+}
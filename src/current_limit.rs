@@ -0,0 +1,85 @@
+//! Cycle-by-cycle current limiting.
+//!
+//! Converters terminate the on-pulse as soon as a comparator detects the current limit and
+//! start the next period normally, so an overload shortens pulses instead of shutting the
+//! converter down. `trip_current_limit()` is meant to be called from the comparator interrupt.
+//! A blanking window after each on edge masks trips caused by the switching noise of the
+//! turn-on transition.
+
+use crate::channel::SCHEDULE_CHANGED;
+use crate::{IRQ_ERROR_CURRENT_LIMIT, SpwmChannel, SpwmState};
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Terminates the on-pulse of the current period, keeping the output off until the next
+    /// period starts.
+    ///
+    /// The channel stays enabled and its duty cycle unchanged. A trip before the on-time of a
+    /// channel with a pulse offset suppresses the pulse of that period. Trips within the
+    /// blanking window after the on edge are ignored.
+    ///
+    /// # Returns
+    /// Whether an on-pulse was terminated.
+    pub fn trip_current_limit(&self) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) || self.is_blanking() {
+            return false;
+        }
+
+        self.tripped.store(true, Ordering::SeqCst);
+        self.flag_error(IRQ_ERROR_CURRENT_LIMIT);
+
+        if !self.waveform_on.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        self.set_output(&SpwmState::Off);
+        self.reschedule(SCHEDULE_CHANGED);
+
+        true
+    }
+
+    /// Returns whether the current period was cut short by `trip_current_limit()`.
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.load(Ordering::Relaxed)
+    }
+
+    /// Sets the number of ticks after each on edge during which `trip_current_limit()` is
+    /// ignored (0 to disable blanking).
+    ///
+    /// # Parameters
+    /// - `blanking_ticks`: Length of the blanking window in ticks
+    pub fn set_blanking_ticks(&self, blanking_ticks: u32) {
+        self.blanking_ticks.store(blanking_ticks, Ordering::SeqCst);
+        self.reschedule(SCHEDULE_CHANGED);
+    }
+
+    /// Returns the length of the blanking window in ticks.
+    pub fn blanking_ticks(&self) -> u32 {
+        self.blanking_ticks.load(Ordering::Relaxed)
+    }
+
+    /// Returns the counter value at which the blanking window of the current on-pulse ends, or
+    /// `None` if the output is off or blanking is disabled.
+    ///
+    /// `Spwm::irq_handler()` wakes up on that tick, so the counter is exact when the window
+    /// ends and never ahead of the real position inside it.
+    pub(crate) fn blanking_end(&self) -> Option<u32> {
+        let blanking_ticks = self.blanking_ticks.load(Ordering::Relaxed);
+
+        if blanking_ticks == 0 || !self.waveform_on.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        Some(
+            self.pulse_offset
+                .load(Ordering::Relaxed)
+                .saturating_add(blanking_ticks),
+        )
+    }
+
+    /// Returns whether the output is within the blanking window after its on edge.
+    fn is_blanking(&self) -> bool {
+        self.blanking_end()
+            .is_some_and(|end| self.counter.load(Ordering::SeqCst) < end)
+    }
+}
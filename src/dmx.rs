@@ -0,0 +1,205 @@
+//! DMX512 level sink adapter.
+//!
+//! Maps the slot levels of a received DMX512 (or Art-Net) frame onto SPWM channels, so stage
+//! lighting style dimmers can be built by pairing this crate with any DMX receiver. Every patched
+//! channel selects its DMX address, the maximum duty cycle reached at full level and a dimming
+//! curve compensating the perceived brightness of LEDs.
+
+use crate::{ChannelId, Spwm, SpwmError, channel::MAX_DUTY_CYCLE};
+
+/// Number of level slots in a DMX512 frame.
+pub const DMX_SLOTS: u16 = 512;
+
+/// Maximum DMX level.
+const MAX_LEVEL: u32 = 255;
+
+/// Curve applied to a DMX level before it is scaled to a duty cycle.
+#[derive(Debug, Clone, Copy)]
+pub enum DmxCurve {
+    /// The duty cycle is proportional to the level
+    Linear,
+    /// The duty cycle follows the square of the level (gamma 2), a common LED dimming curve
+    Square,
+    /// The duty cycle follows the cube of the level (gamma 3), for finer control at low levels
+    Cube,
+    /// User-provided curve mapping a level to a level
+    Custom(fn(u8) -> u8),
+}
+
+impl DmxCurve {
+    /// Applies the curve to a DMX level.
+    fn apply(self, level: u8) -> u32 {
+        let value = u32::from(level);
+
+        match self {
+            Self::Linear => value,
+            Self::Square => value.saturating_mul(value).saturating_add(MAX_LEVEL / 2) / MAX_LEVEL,
+            Self::Cube => {
+                value
+                    .saturating_mul(value)
+                    .saturating_mul(value)
+                    .saturating_add(MAX_LEVEL * MAX_LEVEL / 2)
+                    / (MAX_LEVEL * MAX_LEVEL)
+            }
+            Self::Custom(curve) => u32::from(curve(level)),
+        }
+    }
+}
+
+/// Assignment of a DMX address to an SPWM channel.
+///
+/// # Fields
+/// - `address`: DMX address (1-512) of the slot controlling the channel
+/// - `channel`: Identifier of the controlled channel
+/// - `max_duty`: Duty cycle percentage (0-100) at full level
+/// - `curve`: Dimming curve applied to the level
+#[derive(Debug, Clone, Copy)]
+pub struct DmxPatch {
+    pub address: u16,
+    pub channel: ChannelId,
+    pub max_duty: u8,
+    pub curve: DmxCurve,
+}
+
+impl DmxPatch {
+    /// Creates a linear patch reaching 100% duty cycle at full level.
+    ///
+    /// # Parameters
+    /// - `address`: DMX address (1-512) of the slot controlling the channel
+    /// - `channel`: Identifier of the controlled channel
+    #[must_use]
+    pub const fn new(address: u16, channel: ChannelId) -> Self {
+        Self {
+            address,
+            channel,
+            max_duty: MAX_DUTY_CYCLE,
+            curve: DmxCurve::Linear,
+        }
+    }
+
+    /// Sets the duty cycle percentage reached at full level.
+    #[must_use]
+    pub const fn max_duty(mut self, max_duty: u8) -> Self {
+        self.max_duty = max_duty;
+        self
+    }
+
+    /// Sets the dimming curve applied to the level.
+    #[must_use]
+    pub const fn curve(mut self, curve: DmxCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Converts a DMX level into the duty cycle of the patched channel.
+    fn duty_cycle(&self, level: u8) -> u8 {
+        let duty_cycle = self
+            .curve
+            .apply(level)
+            .min(MAX_LEVEL)
+            .saturating_mul(u32::from(self.max_duty))
+            .saturating_add(MAX_LEVEL / 2)
+            / MAX_LEVEL;
+
+        u8::try_from(duty_cycle).unwrap_or(MAX_DUTY_CYCLE)
+    }
+}
+
+/// Adapter applying DMX frames to `P` patched channels.
+///
+/// # Example
+///
+/// ```
+/// use spwm::{DmxAdapter, DmxCurve, DmxPatch, Spwm};
+///
+/// static DIMMER: DmxAdapter<2> = DmxAdapter::new([
+///     DmxPatch::new(1, 0).curve(DmxCurve::Square),
+///     DmxPatch::new(2, 1).max_duty(80),
+/// ]);
+///
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<2>::new(100_000);
+///
+/// for _ in 0..2 {
+///     let channel = spwm.create_channel()
+///         .freq_hz(200)
+///         .duty_cycle(0)
+///         .on_off_callback(|_| {})
+///         .period_callback(|| {})
+///         .build()?;
+///     spwm.register_channel(channel)?;
+/// }
+///
+/// // Slot levels of a received frame, without the start code
+/// let levels = [255, 128];
+/// DIMMER.apply(&spwm, &levels)?;
+///
+/// assert_eq!(spwm.get_channel(0).unwrap().duty_cycle(), 100);
+/// assert_eq!(spwm.get_channel(1).unwrap().duty_cycle(), 40);
+/// # Ok(())
+/// # }
+/// ```
+pub struct DmxAdapter<const P: usize> {
+    patches: [DmxPatch; P],
+}
+
+impl<const P: usize> DmxAdapter<P> {
+    /// Creates an adapter with the given patches.
+    ///
+    /// # Parameters
+    /// - `patches`: Assignments of DMX addresses to channels
+    #[must_use]
+    pub const fn new(patches: [DmxPatch; P]) -> Self {
+        Self { patches }
+    }
+
+    /// Applies the levels of a DMX frame to the patched channels.
+    ///
+    /// The duty cycles take effect at the next period boundary of each channel. Patches whose
+    /// address lies beyond the end of a short frame keep their duty cycle. All patches are
+    /// validated before any channel is changed.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager owning the patched channels
+    /// - `levels`: Slot levels of the frame starting at address 1 (without the start code)
+    ///
+    /// # Returns
+    /// The number of channels whose duty cycle changed.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if a patch addresses an unregistered channel or a DMX
+    ///   address outside of 1-512
+    /// - `SpwmError::InvalidDutyCycle` if the maximum duty cycle of a patch is greater than 100
+    pub fn apply<const N: usize, const A: usize>(
+        &self,
+        spwm: &Spwm<N, A>,
+        levels: &[u8],
+    ) -> Result<usize, SpwmError> {
+        for patch in &self.patches {
+            if patch.address == 0
+                || patch.address > DMX_SLOTS
+                || spwm.get_channel(patch.channel).is_none()
+            {
+                return Err(SpwmError::InvalidChannel);
+            }
+
+            if patch.max_duty > MAX_DUTY_CYCLE {
+                return Err(SpwmError::InvalidDutyCycle);
+            }
+        }
+
+        let mut changed = 0usize;
+
+        for patch in &self.patches {
+            let level = levels.get(usize::from(patch.address.saturating_sub(1)));
+
+            if let (Some(&level), Some(channel)) = (level, spwm.get_channel(patch.channel))
+                && channel.apply_duty_cycle(patch.duty_cycle(level))
+            {
+                changed = changed.saturating_add(1);
+            }
+        }
+
+        Ok(changed)
+    }
+}
@@ -0,0 +1,222 @@
+//! Double-pulse test pattern.
+//!
+//! The double-pulse test characterizes the switching of a power stage: a long first pulse
+//! builds up the load current, a short gap captures the turn-off transition and a short second
+//! pulse the turn-on transition at that current, then the output stays off. The pattern runs
+//! once on a disabled channel, independently of its PWM configuration, and reports completion
+//! through a callback.
+
+use crate::channel::SCHEDULE_RESTARTED;
+use crate::{SpwmChannel, SpwmError, SpwmState};
+use core::sync::atomic::Ordering;
+
+/// Microseconds per second.
+const MICROS_PER_SEC: u64 = 1_000_000;
+
+/// Stage of a running pattern: the delay before the first pulse.
+const STAGE_DELAY: u8 = 1;
+
+/// Stage of a running pattern: the first pulse, followed by the gap stage.
+const STAGE_FIRST: u8 = 2;
+
+/// Stage of a running pattern: the second pulse, after which the pattern completes.
+const STAGE_SECOND: u8 = 4;
+
+/// Durations of a double-pulse pattern in hardware timer ticks.
+///
+/// # Fields
+/// - `delay_ticks`: Delay before the first pulse (0 to start immediately)
+/// - `first_ticks`: Length of the first pulse
+/// - `gap_ticks`: Off time between the pulses
+/// - `second_ticks`: Length of the second pulse
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DoublePulse {
+    pub delay_ticks: u32,
+    pub first_ticks: u32,
+    pub gap_ticks: u32,
+    pub second_ticks: u32,
+}
+
+impl DoublePulse {
+    /// Creates a pattern from durations in µs, rounded to the nearest tick.
+    ///
+    /// # Parameters
+    /// - `delay_us`: Delay before the first pulse
+    /// - `first_us`: Length of the first pulse
+    /// - `gap_us`: Off time between the pulses
+    /// - `second_us`: Length of the second pulse
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidPulseWidth` if a pulse or the gap is shorter than half a tick
+    /// or a duration does not fit into 32 bits of ticks.
+    pub fn from_micros(
+        delay_us: u32,
+        first_us: u32,
+        gap_us: u32,
+        second_us: u32,
+        hardware_freq_hz: u32,
+    ) -> Result<Self, SpwmError> {
+        let ticks = |us: u32| {
+            u64::from(us)
+                .saturating_mul(u64::from(hardware_freq_hz))
+                .saturating_add(MICROS_PER_SEC / 2)
+                .checked_div(MICROS_PER_SEC)
+                .and_then(|ticks| u32::try_from(ticks).ok())
+                .ok_or(SpwmError::InvalidPulseWidth)
+        };
+        let pattern = Self {
+            delay_ticks: ticks(delay_us)?,
+            first_ticks: ticks(first_us)?,
+            gap_ticks: ticks(gap_us)?,
+            second_ticks: ticks(second_us)?,
+        };
+
+        pattern.validate()?;
+
+        Ok(pattern)
+    }
+
+    /// Checks that both pulses and the gap are at least one tick long.
+    fn validate(&self) -> Result<(), SpwmError> {
+        if self.first_ticks == 0 || self.gap_ticks == 0 || self.second_ticks == 0 {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        Ok(())
+    }
+}
+
+impl SpwmChannel {
+    /// Runs a double-pulse pattern once on the disabled channel.
+    ///
+    /// The channel is enabled for the duration of the pattern and disabled again after the
+    /// second pulse, when the double-pulse complete callback is invoked. The configured
+    /// frequency and duty cycle are not used and stay unchanged. `disable()` aborts a running
+    /// pattern without invoking the callback.
+    ///
+    /// # Parameters
+    /// - `pattern`: Pattern durations in ticks
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidPulseWidth` if a pulse or the gap is 0 ticks long
+    /// - `SpwmError::AlreadyEnabled` if the channel is enabled or a pattern is running
+    pub fn double_pulse(&self, pattern: &DoublePulse) -> Result<(), SpwmError> {
+        pattern.validate()?;
+
+        if self
+            .enabled
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(SpwmError::AlreadyEnabled);
+        }
+
+        for (ticks, stage_ticks) in self.double_pulse_ticks.iter().zip([
+            pattern.first_ticks,
+            pattern.gap_ticks,
+            pattern.second_ticks,
+        ]) {
+            ticks.store(stage_ticks, Ordering::Relaxed);
+        }
+
+        if pattern.delay_ticks == 0 {
+            self.enter_double_pulse_stage(STAGE_FIRST);
+        } else {
+            self.double_pulse_countdown
+                .store(pattern.delay_ticks, Ordering::SeqCst);
+            self.double_pulse_stage.store(STAGE_DELAY, Ordering::SeqCst);
+            self.set_output(&SpwmState::Off);
+        }
+
+        self.reschedule(SCHEDULE_RESTARTED);
+
+        Ok(())
+    }
+
+    /// Returns whether a double-pulse pattern is running.
+    pub fn is_double_pulse_active(&self) -> bool {
+        self.double_pulse_stage.load(Ordering::SeqCst) != 0
+    }
+
+    /// Starts a pulse or gap stage of the running pattern and switches the output to its level.
+    fn enter_double_pulse_stage(&self, stage: u8) {
+        let stage_ticks = self
+            .double_pulse_ticks
+            .get(usize::from(stage.saturating_sub(STAGE_FIRST)))
+            .map_or(1, |ticks| ticks.load(Ordering::Relaxed));
+
+        self.double_pulse_countdown
+            .store(stage_ticks, Ordering::SeqCst);
+        self.double_pulse_stage.store(stage, Ordering::SeqCst);
+        self.set_output(if stage.is_multiple_of(2) {
+            &SpwmState::On
+        } else {
+            &SpwmState::Off
+        });
+    }
+
+    /// Advances a running double-pulse pattern by one tick.
+    ///
+    /// # Returns
+    /// `true` if a pattern is running and the tick was consumed by it.
+    pub(crate) fn step_double_pulse(&self) -> bool {
+        let stage = self.double_pulse_stage.load(Ordering::SeqCst);
+
+        if stage == 0 {
+            return false;
+        }
+
+        let countdown = self
+            .double_pulse_countdown
+            .load(Ordering::Relaxed)
+            .saturating_sub(1);
+
+        if countdown != 0 {
+            self.double_pulse_countdown
+                .store(countdown, Ordering::Relaxed);
+        } else if stage < STAGE_SECOND {
+            self.enter_double_pulse_stage(stage.saturating_add(1));
+        } else {
+            self.double_pulse_stage.store(0, Ordering::SeqCst);
+            self.set_output(&SpwmState::Off);
+            self.enabled.store(false, Ordering::SeqCst);
+
+            if let Some(callback) = self.double_pulse_complete_callback.get() {
+                callback();
+            }
+        }
+
+        true
+    }
+
+    /// Returns the ticks `Spwm::irq_handler()` may skip while a pattern is running.
+    pub(crate) fn double_pulse_idle_ticks(&self) -> Option<u32> {
+        if self.is_double_pulse_active() {
+            Some(
+                self.double_pulse_countdown
+                    .load(Ordering::Relaxed)
+                    .saturating_sub(1),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Accounts ticks skipped by `Spwm::irq_handler()` to a running pattern.
+    ///
+    /// # Returns
+    /// `true` if a pattern is running.
+    pub(crate) fn catch_up_double_pulse(&self, ticks: u32) -> bool {
+        if !self.is_double_pulse_active() {
+            return false;
+        }
+
+        let countdown = self.double_pulse_countdown.load(Ordering::Relaxed);
+
+        self.double_pulse_countdown
+            .store(countdown.saturating_sub(ticks).max(1), Ordering::Relaxed);
+
+        true
+    }
+}
@@ -0,0 +1,36 @@
+//! Per-channel duty cycle lookup table.
+//!
+//! Holds the on-time ticks of every duty cycle percentage for the current channel period, so
+//! duty cycle updates from a fast control loop avoid division and multiplication.
+
+use crate::channel::{MAX_DUTY_CYCLE, duty_ticks};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Number of table entries (0% to 100% inclusive).
+const DUTY_LUT_SIZE: usize = 101;
+
+/// On-time ticks indexed by duty cycle percentage.
+#[derive(Debug)]
+pub(crate) struct DutyLut([AtomicU32; DUTY_LUT_SIZE]);
+
+impl Default for DutyLut {
+    fn default() -> Self {
+        Self([const { AtomicU32::new(0) }; DUTY_LUT_SIZE])
+    }
+}
+
+impl DutyLut {
+    /// Recomputes the table for a new period length.
+    pub(crate) fn fill(&self, period_ticks: u32) {
+        for (duty_cycle, entry) in (0..=MAX_DUTY_CYCLE).zip(&self.0) {
+            entry.store(duty_ticks(period_ticks, duty_cycle), Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the on-time ticks for `duty_cycle` (0 for out-of-range values).
+    pub(crate) fn get(&self, duty_cycle: u8) -> u32 {
+        self.0
+            .get(usize::from(duty_cycle))
+            .map_or(0, |entry| entry.load(Ordering::Relaxed))
+    }
+}
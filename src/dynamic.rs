@@ -0,0 +1,283 @@
+//! Heap-backed SPWM manager (`alloc` feature).
+//!
+//! `Spwm<N>` stores its channels in an array sized at compile time. Applications that only
+//! learn the channel count at runtime (plugin-style configurations, scripted test fixtures)
+//! would have to size `N` for the worst case. `SpwmDyn` keeps the channels in a `Vec` that
+//! grows on registration instead. It drives independent channels with the same tick
+//! processing as `Spwm`, including skipping ticks without events and reporting failures through
+//! `take_errors()`, but offers no chaining, mirroring, alarms or tick divider.
+
+use crate::channel::SpwmChannelFreqHzBuildState;
+use crate::irq_errors::{self, IRQ_ERROR_OVERRUN};
+use crate::reschedule::RescheduleGeneration;
+use crate::tick_count::{TickCount, is_valid_hardware_frequency};
+use crate::{ChannelId, ChannelSlot, SpwmChannel, SpwmChannelBuilder, SpwmError, slots};
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// A software PWM manager with a runtime-sized set of channels.
+///
+/// # Fields
+/// - `channel_slots`: Registered channels, indexed by `ChannelId`.
+/// - `freq_hz`: The hardware timer frequency in hertz (Hz).
+/// - `order`: Slot indices in the order they are processed by `irq_handler()`, sorted by
+///   channel priority.
+/// - `idle_ticks`: Remaining ticks before the next channel event, which `irq_handler()` skips.
+/// - `schedule_generation`: Reschedule generation bumped by the registered channels.
+/// - `ticks`: Number of hardware timer ticks processed by `irq_handler()`.
+/// - `event_tick`: Low 32 bits of `ticks` at the last processed event.
+/// - `in_irq`: Whether an `irq_handler()` call is in progress.
+/// - `overruns`: Number of `irq_handler()` calls dropped because they preempted a call.
+/// - `irq_errors`: `IRQ_ERROR_*` flags collected by `take_errors()`.
+///
+/// # Example
+/// ```
+/// # use spwm::SpwmDyn;
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = SpwmDyn::new(100_000);
+///
+/// for duty_cycle in [10, 50, 90] {
+///     let channel = spwm
+///         .create_channel()
+///         .freq_hz(1000)
+///         .duty_cycle(duty_cycle)
+///         .on_off_callback(|_| {})
+///         .period_callback(|| {})
+///         .build()?;
+///     let id = spwm.register_channel(channel);
+///
+///     spwm.get_channel(id).unwrap().enable()?;
+/// }
+///
+/// assert_eq!(spwm.channel_count(), 3);
+/// // in the timer interrupt
+/// spwm.irq_handler();
+/// # Ok(())
+/// # }
+/// ```
+pub struct SpwmDyn {
+    channel_slots: Vec<ChannelSlot>,
+    freq_hz: u32,
+    order: Vec<ChannelId>,
+    idle_ticks: AtomicU32,
+    schedule_generation: RescheduleGeneration,
+    ticks: TickCount,
+    event_tick: AtomicU32,
+    in_irq: AtomicBool,
+    overruns: AtomicU32,
+    irq_errors: AtomicU32,
+}
+
+impl SpwmDyn {
+    /// Creates a manager without channels for a hardware timer running at `freq_hz`.
+    #[must_use]
+    pub fn new(freq_hz: u32) -> Self {
+        Self::with_capacity(freq_hz, 0)
+    }
+
+    /// Creates a manager like `new()` after checking the hardware timer frequency.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidHardwareFrequency` if the frequency is 0 or above
+    /// `MAX_HARDWARE_FREQ_HZ`.
+    pub fn try_new(freq_hz: u32) -> Result<Self, SpwmError> {
+        Self::try_with_capacity(freq_hz, 0)
+    }
+
+    /// Creates a manager like `with_capacity()` after checking the hardware timer frequency.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidHardwareFrequency` if the frequency is 0 or above
+    /// `MAX_HARDWARE_FREQ_HZ`.
+    pub fn try_with_capacity(freq_hz: u32, capacity: usize) -> Result<Self, SpwmError> {
+        if !is_valid_hardware_frequency(freq_hz) {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        Ok(Self::with_capacity(freq_hz, capacity))
+    }
+
+    /// Creates a manager with room for `capacity` channels before it reallocates.
+    #[must_use]
+    pub fn with_capacity(freq_hz: u32, capacity: usize) -> Self {
+        Self {
+            channel_slots: Vec::with_capacity(capacity),
+            freq_hz,
+            order: Vec::with_capacity(capacity),
+            idle_ticks: AtomicU32::new(0),
+            schedule_generation: RescheduleGeneration::new(),
+            ticks: TickCount::default(),
+            event_tick: AtomicU32::new(0),
+            in_irq: AtomicBool::new(false),
+            overruns: AtomicU32::new(0),
+            irq_errors: AtomicU32::new(0),
+        }
+    }
+
+    /// Creates a channel builder for the hardware timer frequency of the manager.
+    pub fn create_channel(&self) -> SpwmChannelBuilder<SpwmChannelFreqHzBuildState> {
+        SpwmChannelBuilder::new(self.freq_hz)
+    }
+
+    /// Registers a PWM channel and returns its unique identifier.
+    ///
+    /// The slot of a removed channel is reused before the storage grows.
+    pub fn register_channel(&mut self, channel: SpwmChannel) -> ChannelId {
+        let id = if let Some(id) = self
+            .channel_slots
+            .iter()
+            .position(|slot| slot.channel.is_none())
+        {
+            id
+        } else {
+            self.channel_slots.push(ChannelSlot::default());
+            self.channel_slots.len().saturating_sub(1)
+        };
+
+        channel
+            .reschedule_index
+            .store(self.schedule_generation.index(), Ordering::Relaxed);
+
+        if let Some(slot) = self.channel_slots.get_mut(id) {
+            slot.channel = Some(channel);
+        }
+
+        self.order.push(id);
+        self.sort_order();
+        self.idle_ticks.store(0, Ordering::Relaxed);
+
+        id
+    }
+
+    /// Removes a registered channel and returns it, leaving its slot free for reuse.
+    ///
+    /// An enabled channel is disabled first, so its output is switched off (invoking the on/off
+    /// callback if the output was on) instead of staying in its last state without a manager.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if no channel is registered under `channel_id`
+    /// - `SpwmError::DisableFailed` if the channel could not be disabled; it stays registered
+    pub fn remove_channel(&mut self, channel_id: ChannelId) -> Result<SpwmChannel, SpwmError> {
+        match self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?
+            .disable()
+        {
+            Ok(()) | Err(SpwmError::AlreadyDisabled) => {}
+            Err(error) => return Err(error),
+        }
+
+        let channel = self
+            .channel_slots
+            .get_mut(channel_id)
+            .and_then(|slot| slot.channel.take())
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        self.order.retain(|&id| id != channel_id);
+        self.idle_ticks.store(0, Ordering::Relaxed);
+        channel.reschedule_index.store(0, Ordering::Relaxed);
+
+        Ok(channel)
+    }
+
+    /// Returns the channel registered under `channel_id`, if any.
+    pub fn get_channel(&self, channel_id: ChannelId) -> Option<&SpwmChannel> {
+        self.channel_slots.get(channel_id)?.channel.as_ref()
+    }
+
+    /// Returns the number of registered channels.
+    pub fn channel_count(&self) -> usize {
+        self.channels().count()
+    }
+
+    /// Changes the processing priority of a registered channel.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if no channel is registered under `channel_id`.
+    pub fn set_channel_priority(
+        &mut self,
+        channel_id: ChannelId,
+        priority: u8,
+    ) -> Result<(), SpwmError> {
+        self.get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?
+            .priority
+            .store(priority, Ordering::Relaxed);
+        self.sort_order();
+
+        Ok(())
+    }
+
+    /// Handles the timer interrupt for all registered channels.
+    ///
+    /// Behaves like `Spwm::irq_handler()`: channels are processed in descending priority order
+    /// and ticks without a due event are only counted down.
+    pub fn irq_handler(&self) {
+        if self.in_irq.swap(true, Ordering::Acquire) {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+            self.irq_errors
+                .fetch_or(IRQ_ERROR_OVERRUN, Ordering::Relaxed);
+            return;
+        }
+
+        self.handle_tick();
+        self.in_irq.store(false, Ordering::Release);
+    }
+
+    /// Returns the number of `irq_handler()` calls dropped because they preempted a call in
+    /// progress.
+    pub fn overrun_count(&self) -> u32 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Returns and clears the failures detected in interrupt context since the last call.
+    ///
+    /// # Returns
+    /// A bitfield of `IRQ_ERROR_*` flags, 0 if nothing failed (see `Spwm::take_errors()`).
+    pub fn take_errors(&self) -> u32 {
+        irq_errors::take_errors(&self.irq_errors, &self.channel_slots)
+    }
+
+    /// Returns the number of hardware timer ticks processed by `irq_handler()`.
+    pub fn ticks(&self) -> u64 {
+        self.ticks.get()
+    }
+
+    /// Processes one tick of `irq_handler()` without the reentrancy guard.
+    fn handle_tick(&self) {
+        let tick = self.ticks.add(1);
+
+        if !self.schedule_generation.take_rescheduled()
+            && let Some(idle_ticks) = self.idle_ticks.load(Ordering::Relaxed).checked_sub(1)
+        {
+            self.idle_ticks.store(idle_ticks, Ordering::Relaxed);
+            return;
+        }
+
+        let skipped_ticks = tick
+            .wrapping_sub(self.event_tick.swap(tick, Ordering::Relaxed))
+            .wrapping_sub(1);
+
+        slots::process_tick(
+            &self.channel_slots,
+            &self.order,
+            skipped_ticks,
+            0,
+            |_, channel| {
+                channel.process_tick();
+            },
+        );
+        self.idle_ticks
+            .store(slots::idle_ticks(&self.channel_slots), Ordering::Relaxed);
+    }
+
+    /// Returns an iterator over the registered channels.
+    fn channels(&self) -> impl Iterator<Item = &SpwmChannel> {
+        slots::channels(&self.channel_slots)
+    }
+
+    /// Sorts the processing order by descending channel priority.
+    fn sort_order(&mut self) {
+        slots::sort_order(&mut self.order, &self.channel_slots);
+    }
+}
@@ -0,0 +1,175 @@
+//! Incremental encoder simulation for hardware-in-the-loop testing.
+//!
+//! The simulator drives a quadrature channel pair (A/B) and an optional index channel through the
+//! regular tick engine: A runs at the line rate, B is phase-locked to A with a quarter-period shift
+//! whose sign encodes the direction, and the index channel is chained to A to pulse once per
+//! revolution.
+
+use crate::{ChainMode, ChannelId, Spwm, SpwmError};
+
+/// Duty cycle of the index pulse, in percent of one line period.
+const INDEX_DUTY_CYCLE: u8 = 25;
+
+/// Rotation direction of the simulated encoder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncoderDirection {
+    /// A leads B by 90°
+    Forward,
+    /// B leads A by 90°
+    Reverse,
+}
+
+/// Simulated incremental encoder built on top of SPWM channels.
+///
+/// # Example
+///
+/// ```
+/// # use spwm::{EncoderSim, Spwm};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<3>::new(100_000);
+/// let mut ids = [0; 3];
+///
+/// for id in &mut ids {
+///     let channel = spwm.create_channel()
+///         .freq_hz(1_000)
+///         .duty_cycle(50)
+///         .on_off_callback(|_| {})
+///         .period_callback(|| {})
+///         .build()?;
+///     *id = spwm.register_channel(channel)?;
+/// }
+///
+/// let mut encoder = EncoderSim::new(&mut spwm, ids[0], ids[1], Some((ids[2], 360)))?;
+///
+/// // 500 lines per second backwards
+/// encoder.set_velocity(&mut spwm, -500)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EncoderSim {
+    a: ChannelId,
+    b: ChannelId,
+    index: Option<ChannelId>,
+    direction: EncoderDirection,
+}
+
+impl EncoderSim {
+    /// Configures the channels for encoder simulation.
+    ///
+    /// The channels keep their callbacks (which should drive the A, B and index pins) while
+    /// their frequency and duty cycle are managed by the simulator. All channels are left
+    /// disabled until a non-zero velocity is set.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `a`: The identifier of the A channel
+    /// - `b`: The identifier of the B channel
+    /// - `index`: Optional index channel identifier and number of lines per revolution
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if a channel is not registered or used twice
+    /// - `SpwmError::InvalidChainMode` if the number of lines per revolution is 0
+    pub fn new<const N: usize, const A: usize>(
+        spwm: &mut Spwm<N, A>,
+        a: ChannelId,
+        b: ChannelId,
+        index: Option<(ChannelId, u32)>,
+    ) -> Result<Self, SpwmError> {
+        if let Some((index_id, lines_per_rev)) = index {
+            if index_id == a || index_id == b {
+                return Err(SpwmError::InvalidChannel);
+            }
+
+            spwm.chain(a, index_id, ChainMode::EveryNthPeriod(lines_per_rev))?;
+            spwm.get_channel(index_id)
+                .ok_or(SpwmError::InvalidChannel)?
+                .update_duty_cycle(INDEX_DUTY_CYCLE)?;
+        }
+
+        spwm.quadrature(a, b)?;
+
+        let encoder = Self {
+            a,
+            b,
+            index: index.map(|(index_id, _)| index_id),
+            direction: EncoderDirection::Forward,
+        };
+
+        encoder.stop(spwm);
+
+        Ok(encoder)
+    }
+
+    /// Returns the current rotation direction.
+    #[must_use]
+    pub fn direction(&self) -> EncoderDirection {
+        self.direction
+    }
+
+    /// Sets the simulated velocity.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `lines_per_sec`: Velocity in encoder lines per second; the sign selects the direction
+    ///   and 0 stops the outputs
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the line rate is too high for the hardware timer
+    /// frequency, or `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn set_velocity<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &mut Spwm<N, A>,
+        lines_per_sec: i32,
+    ) -> Result<(), SpwmError> {
+        if lines_per_sec == 0 {
+            self.stop(spwm);
+
+            return Ok(());
+        }
+
+        let direction = if lines_per_sec > 0 {
+            EncoderDirection::Forward
+        } else {
+            EncoderDirection::Reverse
+        };
+        let freq_hz = lines_per_sec.unsigned_abs();
+
+        for id in core::iter::once(self.a).chain(self.index) {
+            let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            channel.update_frequency(freq_hz, spwm.freq_hz)?;
+            channel.sync_on_ticks();
+        }
+
+        if direction != self.direction {
+            let phase = match direction {
+                EncoderDirection::Forward => (1, 4),
+                EncoderDirection::Reverse => (3, 4),
+            };
+
+            spwm.lock_with_phase(self.a, self.b, 1, phase)?;
+            self.direction = direction;
+        }
+
+        for id in [self.a, self.b].into_iter().chain(self.index) {
+            let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            if !channel.is_enabled() {
+                channel.enable()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Disables all encoder outputs.
+    fn stop<const N: usize, const A: usize>(&self, spwm: &Spwm<N, A>) {
+        for id in [self.a, self.b].into_iter().chain(self.index) {
+            if let Some(channel) = spwm.get_channel(id)
+                && channel.is_enabled()
+            {
+                let _ = channel.disable();
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Deterministic tick engine of a channel.
+//!
+//! `step()` holds the timing decisions of `SpwmChannel::process_tick()` as a pure function of a
+//! plain state value, separate from the atomics and callbacks of the channel. Building with
+//! `--cfg fuzzing` (as `cargo fuzz` does) exports it, so fuzz harnesses can drive arbitrary
+//! duty cycle and frequency update sequences through the engine without callback state.
+
+use crate::channel::MIN_PERIOD_TICKS;
+
+/// Timing state of a channel consumed and produced by `step()`.
+///
+/// # Fields
+/// - `counter`: Ticks elapsed in the current period (saturating at `u32::MAX`)
+/// - `period_ticks`: Period length in ticks (raised to 2)
+/// - `on_ticks`: On-time of the current period in ticks
+/// - `on_offset`: Ticks from the period start to the start of the on-time
+/// - `waveform_on`: Whether the waveform is in its on phase
+/// - `locked`: Whether the period ends on a delayed trigger instead of the period length
+/// - `trigger_countdown`: Ticks until a pending delayed trigger plus one (0 if none)
+/// - `start_countdown`: Ticks remaining of the start delay (0 once the channel runs)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EngineState {
+    pub counter: u32,
+    pub period_ticks: u32,
+    pub on_ticks: u32,
+    pub on_offset: u32,
+    pub waveform_on: bool,
+    pub locked: bool,
+    pub trigger_countdown: u32,
+    pub start_countdown: u32,
+}
+
+/// Event produced by a single engine tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickEvent {
+    /// Nothing to do besides advancing the counters
+    Idle,
+    /// The start delay elapsed and the first period starts
+    Start,
+    /// The period completed; the caller latches the next on-time and starts a new period
+    PeriodEnd,
+    /// The on-time elapsed and the output switches off
+    Off,
+    /// The on-time offset elapsed and the output switches on
+    On,
+}
+
+/// Advances `state` by one hardware timer tick.
+///
+/// # Returns
+/// The next state and the event the caller has to act on. `Start` and `PeriodEnd` leave
+/// `waveform_on` untouched since the initial output of a period depends on the on-time latched
+/// by the caller; with a nonzero `on_offset` the output stays off until the `On` event.
+#[must_use]
+pub fn step(mut state: EngineState) -> (EngineState, TickEvent) {
+    if let Some(countdown) = state.start_countdown.checked_sub(1) {
+        state.start_countdown = countdown;
+
+        if countdown == 0 {
+            state.counter = 0;
+
+            return (state, TickEvent::Start);
+        }
+
+        return (state, TickEvent::Idle);
+    }
+
+    // The counter saturates instead of wrapping, so a locked channel waiting for its trigger
+    // for more than 2^32 ticks cannot see its on-time offset again and switch on spuriously
+    let elapsed_ticks = state.counter.saturating_add(1);
+    state.counter = elapsed_ticks;
+
+    let period_end = if state.locked {
+        match state.trigger_countdown {
+            0 => false,
+            1 => {
+                state.trigger_countdown = 0;
+                true
+            }
+            countdown => {
+                state.trigger_countdown = countdown.saturating_sub(1);
+                false
+            }
+        }
+    } else {
+        elapsed_ticks >= state.period_ticks.max(MIN_PERIOD_TICKS)
+    };
+
+    if period_end {
+        state.counter = 0;
+
+        return (state, TickEvent::PeriodEnd);
+    }
+
+    // `>=` also catches an immediate duty update that moved the on-time behind the counter
+    if elapsed_ticks >= state.on_offset.saturating_add(state.on_ticks) && state.waveform_on {
+        state.waveform_on = false;
+
+        return (state, TickEvent::Off);
+    }
+
+    // A range instead of an exact match, so a tick divider stepping over the offset still
+    // switches the output on within the on-time
+    if state.on_offset != 0
+        && elapsed_ticks >= state.on_offset
+        && elapsed_ticks < state.on_offset.saturating_add(state.on_ticks)
+        && !state.waveform_on
+    {
+        state.waveform_on = true;
+
+        return (state, TickEvent::On);
+    }
+
+    (state, TickEvent::Idle)
+}
@@ -0,0 +1,52 @@
+//! Flicker-safe frequency checks for LED channels.
+//!
+//! LED light modulated below a few hundred hertz shows as banding or beating on rolling shutter
+//! cameras and as stroboscopic effects on moving objects. Channels built with
+//! `SpwmChannelBuilder::flicker_safe()` reject such frequencies at configuration time and on
+//! later frequency changes, so product requirements hold for the lifetime of the channel.
+
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Lowest PWM frequency in Hz considered free of visible flicker in video recording
+/// environments.
+pub const FLICKER_SAFE_MIN_HZ: u32 = 300;
+
+/// Checks that an LED PWM frequency is outside the known flicker ranges.
+///
+/// # Parameters
+/// - `freq_hz`: PWM frequency in Hz
+///
+/// # Errors
+/// Returns `SpwmError::InvalidFrequency` if the frequency is below `FLICKER_SAFE_MIN_HZ`.
+///
+/// # Examples
+/// ```
+/// use spwm::{SpwmError, assert_flicker_safe};
+///
+/// assert_eq!(assert_flicker_safe(1000), Ok(()));
+/// assert_eq!(assert_flicker_safe(120), Err(SpwmError::InvalidFrequency));
+/// ```
+pub fn assert_flicker_safe(freq_hz: u32) -> Result<(), SpwmError> {
+    if freq_hz < FLICKER_SAFE_MIN_HZ {
+        return Err(SpwmError::InvalidFrequency);
+    }
+
+    Ok(())
+}
+
+impl SpwmChannel {
+    /// Returns whether the channel rejects frequencies in the known flicker ranges.
+    pub fn is_flicker_safe(&self) -> bool {
+        self.flicker_safe.load(Ordering::Relaxed)
+    }
+
+    /// Checks a new channel frequency against the flicker constraint, if the channel has one.
+    pub(crate) fn flicker_validate(&self, freq_hz: u32) -> Result<(), SpwmError> {
+        if self.is_flicker_safe() {
+            assert_flicker_safe(freq_hz)?;
+        }
+
+        Ok(())
+    }
+}
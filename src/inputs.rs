@@ -0,0 +1,143 @@
+//! Debounced digital inputs sampled from the tick interrupt.
+//!
+//! Simple devices often read a few buttons next to their PWM outputs. Instead of a dedicated
+//! timer, `Inputs::sample()` can be called from a periodic alarm every K ticks. Each input runs
+//! an integrator that counts up while the raw level is high and down while it is low. The
+//! debounced level only changes when the integrator reaches one of its limits, so a bouncing
+//! contact must stay stable for `threshold` samples before its change is reported.
+
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Callback reading the raw level of an input.
+///
+/// # Returns
+/// `true` if the input is high.
+pub type InputSampleCallback = fn() -> bool;
+
+/// Callback invoked when the debounced level of an input changes.
+///
+/// # Parameters
+/// - `level`: The new debounced level
+pub type InputChangeCallback = fn(bool);
+
+/// A debounced input.
+///
+/// # Fields
+/// - `sample`: Callback reading the raw input level
+/// - `on_change`: Callback invoked when the debounced level changes
+/// - `integrator`: Number of high samples counted, between 0 and the threshold
+/// - `level`: The debounced level, initially low
+pub struct Input {
+    sample: InputSampleCallback,
+    on_change: InputChangeCallback,
+    integrator: AtomicU8,
+    level: AtomicBool,
+}
+
+impl Input {
+    /// Creates an input with a low debounced level.
+    ///
+    /// # Parameters
+    /// - `sample`: Callback reading the raw input level
+    /// - `on_change`: Callback invoked when the debounced level changes
+    #[must_use]
+    pub const fn new(sample: InputSampleCallback, on_change: InputChangeCallback) -> Self {
+        Self {
+            sample,
+            on_change,
+            integrator: AtomicU8::new(0),
+            level: AtomicBool::new(false),
+        }
+    }
+
+    /// Samples the raw level and updates the integrator.
+    fn sample(&self, threshold: u8) {
+        let integrator = self.integrator.load(Ordering::Relaxed);
+        let integrator = if (self.sample)() {
+            integrator.saturating_add(1).min(threshold)
+        } else {
+            integrator.saturating_sub(1)
+        };
+
+        self.integrator.store(integrator, Ordering::Relaxed);
+
+        let level = match integrator {
+            0 => false,
+            _ if integrator == threshold => true,
+            _ => return,
+        };
+
+        if self.level.swap(level, Ordering::Relaxed) != level {
+            (self.on_change)(level);
+        }
+    }
+}
+
+/// A group of `M` debounced inputs.
+///
+/// `sample()` must only be called from one context, typically a periodic alarm.
+///
+/// # Example
+///
+/// ```
+/// use spwm::{Input, Inputs, Spwm};
+///
+/// fn read_button() -> bool {
+///     // read the button pin
+///     false
+/// }
+///
+/// fn button_changed(pressed: bool) {
+///     // react to the debounced button level
+/// }
+///
+/// static INPUTS: Inputs<1> = Inputs::new([Input::new(read_button, button_changed)], 5);
+///
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// // Sample the button every millisecond with a 100 kHz tick
+/// let mut spwm = Spwm::<1, 1>::new(100_000);
+/// spwm.set_periodic_alarm(100, || INPUTS.sample())?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Inputs<const M: usize> {
+    inputs: [Input; M],
+    threshold: u8,
+}
+
+impl<const M: usize> Inputs<M> {
+    /// Creates a group of inputs.
+    ///
+    /// # Parameters
+    /// - `inputs`: The inputs to debounce
+    /// - `threshold`: Number of consecutive stable samples required to change the debounced
+    ///   level (0 is treated as 1, i.e. no debouncing)
+    #[must_use]
+    pub const fn new(inputs: [Input; M], threshold: u8) -> Self {
+        Self {
+            inputs,
+            threshold: if threshold == 0 { 1 } else { threshold },
+        }
+    }
+
+    /// Samples every input and invokes the change callbacks of the inputs whose debounced
+    /// level changed.
+    pub fn sample(&self) {
+        for input in &self.inputs {
+            input.sample(self.threshold);
+        }
+    }
+
+    /// Returns the debounced level of an input.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the input in the group
+    ///
+    /// # Returns
+    /// The debounced level, or `None` if `index` is out of range.
+    pub fn level(&self, index: usize) -> Option<bool> {
+        self.inputs
+            .get(index)
+            .map(|input| input.level.load(Ordering::Relaxed))
+    }
+}
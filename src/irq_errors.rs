@@ -0,0 +1,60 @@
+//! Failures detected in interrupt context.
+//!
+//! Overruns, protection limits, current limit trips, mirror divergences and unconfigured channels
+//! are detected inside
+//! `irq_handler()` or the interrupt handlers feeding the channels, where there is no caller to
+//! return an error to. Each condition sets a flag that the application collects with
+//! `Spwm::take_errors()` from its main loop, so failures surface even without callbacks.
+
+use crate::{ChannelSlot, Spwm, SpwmChannel, slots};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// An `irq_handler()` call was dropped because it preempted a call in progress.
+pub const IRQ_ERROR_OVERRUN: u32 = 1 << 0;
+/// A protection limit altered the on-time of a channel.
+pub const IRQ_ERROR_PROTECTION: u32 = 1 << 1;
+/// The current limit of a channel tripped.
+pub const IRQ_ERROR_CURRENT_LIMIT: u32 = 1 << 2;
+/// The output of a mirrored channel stopped agreeing with its primary.
+pub const IRQ_ERROR_DIVERGENCE: u32 = 1 << 3;
+/// An enabled channel without a period, e.g. a default-constructed one, was skipped.
+pub const IRQ_ERROR_UNCONFIGURED: u32 = 1 << 4;
+
+impl SpwmChannel {
+    /// Records a failure for `Spwm::take_errors()`.
+    pub(crate) fn flag_error(&self, error: u32) {
+        self.irq_errors.fetch_or(error, Ordering::Relaxed);
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Records a failure for `take_errors()`.
+    pub(crate) fn flag_error(&self, error: u32) {
+        self.irq_errors.fetch_or(error, Ordering::Relaxed);
+    }
+
+    /// Returns and clears the failures detected in interrupt context since the last call.
+    ///
+    /// # Returns
+    /// A bitfield of `IRQ_ERROR_*` flags, 0 if nothing failed.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{IRQ_ERROR_OVERRUN, Spwm};
+    /// let spwm: Spwm<1> = Spwm::new(100_000);
+    ///
+    /// if spwm.take_errors() & IRQ_ERROR_OVERRUN != 0 {
+    ///     // the tick interrupt preempted itself
+    /// }
+    /// ```
+    pub fn take_errors(&self) -> u32 {
+        take_errors(&self.irq_errors, self.slots())
+    }
+}
+
+/// Returns and clears the failures of a manager and of the channels in its `slots`.
+pub(crate) fn take_errors(errors: &AtomicU32, slots: &[ChannelSlot]) -> u32 {
+    slots::channels(slots).fold(errors.swap(0, Ordering::Relaxed), |errors, channel| {
+        errors | channel.irq_errors.swap(0, Ordering::Relaxed)
+    })
+}
@@ -12,6 +12,55 @@
 //! - **Type-safe builder pattern** - Compile-time guarantees for proper channel configuration
 //! - **Flexible callbacks** - Register callbacks for state changes and period completion
 //! - **Dynamic updates** - Change frequency and duty cycle at runtime
+//! - **Software alarms** - One-shot and periodic alarms fired from the same interrupt handler
+//! - **Clusters** - Several managers with individual prescalers sharing one hardware timer
+//! - **Multicore control** - `Sync` request mailbox to control channels from another core
+//! - **AC mains control** - Phase-angle dimming and burst-fire of AC loads synced to the zero cross
+//! - **BLDC commutation** - Six-step commutation of brushless motors from hall sensor states
+//! - **Signal generator** - Sine, triangle, square and white/pink noise waveforms modulated onto the duty cycle
+//! - **Resonance tracking** - Frequency sweep and tracking of the maximum response of piezo/ultrasonic loads
+//! - **Stepper microstepping** - Sine/cosine coil duty cycles for microstepped motion with plain H-bridges
+//! - **Builder presets** - Ready-made LED, servo, heater and buzzer channel configurations
+//!
+//! ## Cargo Features
+//!
+//! - `duty-lut` - Precompute the on-time ticks of every duty cycle value whenever a channel
+//!   frequency changes, so duty cycle updates are a table read without division or
+//!   multiplication. Costs 404 bytes of RAM per channel.
+//! - `serde` - Serializable `SpwmConfig`/`ChannelConfig` structs and `Spwm::apply_config()` to
+//!   store channel frequencies, duty cycles and priorities in flash/EEPROM and apply them at
+//!   boot.
+//! - `remote` - Compact binary command/response protocol (`Spwm::remote_execute()`) with bounds
+//!   checking and status codes, to drive a test fixture's PWM outputs from a host PC over
+//!   serial.
+//! - `replay` - Record enable, duty cycle and frequency changes made through `Spwm::execute()`
+//!   with their tick count into a user buffer and reproduce them with `Spwm::replay()` on the
+//!   host, to debug field issues tick by tick.
+//! - `shell` - `Spwm::shell_command()` executing text commands (`pwm set 2 33`,
+//!   `pwm freq 2 500`, `pwm status`) for embedded CLIs, with the response written into a
+//!   caller-provided buffer.
+//! - `stats` - Accumulate the enabled and on-time ticks of every channel
+//!   (`SpwmChannel::on_time_ratio()`) to derive delivered energy or average brightness since
+//!   boot.
+//! - `telemetry` - Mirror output edges and period ends from `irq_handler()` into compact records
+//!   passed to a user-provided sink (`Spwm::set_telemetry()`) for post-mortem analysis.
+//! - `watch` - Watch window (`Spwm::arm_watch()`) recording the ticks of the next output edges
+//!   of one channel into a caller-provided buffer, to verify the timing on the real device over
+//!   RTT/serial without an oscilloscope.
+//! - `dmx` - `DmxAdapter` applying the slot levels of received DMX512/Art-Net frames to patched
+//!   channels with per-channel maximum duty cycle and dimming curve, to build stage-lighting
+//!   style dimmers with any DMX receiver.
+//! - `inputs` - `Inputs` group of debounced digital inputs (e.g. buttons) sampled from a periodic
+//!   alarm, with integrator debouncing and change callbacks.
+//! - `alloc` - `SpwmDyn` manager (requires a global allocator) storing its channels in a `Vec`,
+//!   for applications that only know the channel count at runtime.
+//! - `test-util` - `test_util` module (requires `std`) capturing simulation traces, optionally
+//!   with injected interrupt jitter and missed ticks, and asserting on them
+//!   (`assert_duty_within()`, `assert_phase_offset()`) for black-box tests of a PWM
+//!   configuration.
+//! - `proptest` - `test_util::strategies` generating valid channel configurations for `proptest`,
+//!   to be used with `test_util::check_against_model()` comparing the `irq_handler()` output with
+//!   a reference model (implies `test-util`).
 //!
 //! ## Basic Usage
 //!
@@ -105,12 +154,156 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ## Panic Freedom
+//!
+//! A panic inside `irq_handler()` is fatal in interrupt context, so the library code does not
+//! contain panicking operations: arithmetic is checked, saturating or provably in range, slices
+//! are accessed through `get()`, and `unwrap()`/`expect()` are not used. This is enforced by
+//! the `clippy` lints denied below.
 #![no_std]
+#![deny(
+    clippy::arithmetic_side_effects,
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "test-util")]
+extern crate std;
+
+mod adc_trigger;
+mod alarms;
+mod bitstream;
+mod budget;
+mod burst_dimming;
+mod calibration;
+mod chain;
 mod channel;
+mod cluster;
+mod commutation;
+#[cfg(feature = "serde")]
+mod config;
+mod control;
+mod current_limit;
+#[cfg(feature = "dmx")]
+mod dmx;
+mod double_pulse;
+#[cfg(feature = "duty-lut")]
+mod duty_lut;
+#[cfg(feature = "alloc")]
+mod dynamic;
+mod encoder_sim;
+mod engine;
+mod flicker;
+#[cfg(feature = "inputs")]
+mod inputs;
+mod irq_errors;
+mod mains;
+mod measurement;
+mod mirror;
+mod noise;
+mod peak_hold;
+mod pending;
+mod presets;
+mod protection;
+mod pwm_dac;
+mod quantize;
+mod ramp;
+mod reciprocal;
+#[cfg(feature = "remote")]
+mod remote;
+#[cfg(feature = "replay")]
+mod replay;
+mod reschedule;
+mod resonance;
+mod scheduler;
+mod self_test;
+#[cfg(feature = "shell")]
+mod shell;
+mod signal;
+mod single;
+mod slots;
+mod soft_serial;
+#[cfg(feature = "stats")]
+mod stats;
+mod stepper;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod tick_count;
+mod transducer;
+mod trigger;
+mod update_policy;
+#[cfg(feature = "watch")]
+mod watch;
 
-use core::sync::atomic::Ordering;
+use channel::MAX_DUTY_CYCLE;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
+use reschedule::RescheduleGeneration;
+use tick_count::TickCount;
+#[cfg(feature = "watch")]
+use watch::WatchState;
 
-pub use channel::{SpwmChannel, SpwmChannelBuilder, SpwmChannelFreqHzBuildState};
+pub use adc_trigger::AdcTriggerPoint;
+pub use alarms::{AlarmCallback, AlarmId};
+pub use bitstream::{LineCode, PulseTiming};
+pub use chain::ChainMode;
+pub use channel::{
+    AppliedUpdate, ChannelValidation, OutputWaveform, PeriodCallbackTiming, SpwmChannel,
+    SpwmChannelBuilder, SpwmChannelFreqHzBuildState,
+};
+pub use cluster::SpwmCluster;
+pub use commutation::{Commutator, HallDecodeCallback, decode_hall_120};
+#[cfg(feature = "serde")]
+pub use config::{ChannelConfig, SpwmConfig};
+pub use control::SpwmControl;
+#[cfg(feature = "dmx")]
+pub use dmx::{DMX_SLOTS, DmxAdapter, DmxCurve, DmxPatch};
+pub use double_pulse::DoublePulse;
+#[cfg(feature = "alloc")]
+pub use dynamic::SpwmDyn;
+pub use encoder_sim::{EncoderDirection, EncoderSim};
+#[cfg(fuzzing)]
+pub use engine::{EngineState, TickEvent, step};
+pub use flicker::{FLICKER_SAFE_MIN_HZ, assert_flicker_safe};
+#[cfg(feature = "inputs")]
+pub use inputs::{Input, InputChangeCallback, InputSampleCallback, Inputs};
+pub use irq_errors::{
+    IRQ_ERROR_CURRENT_LIMIT, IRQ_ERROR_DIVERGENCE, IRQ_ERROR_OVERRUN, IRQ_ERROR_PROTECTION,
+    IRQ_ERROR_UNCONFIGURED,
+};
+pub use mains::DimmerEdge;
+pub use mirror::DivergenceCallback;
+pub use pending::PendingUpdate;
+pub use protection::{ProtectionProfile, ProtectionViolation, ProtectionViolationCallback};
+pub use pwm_dac::PwmDac;
+#[cfg(feature = "remote")]
+pub use remote::{
+    REMOTE_COMMAND_LEN, REMOTE_RESPONSE_LEN, RemoteAction, RemoteCommand, RemoteResponse,
+    RemoteStatus,
+};
+#[cfg(feature = "replay")]
+pub use replay::{ControlOp, ReplayLog, ReplayRecord};
+pub use resonance::{ResonanceFeedbackCallback, ResonanceTracker};
+pub use scheduler::{CompareCallback, CompareId, TickScheduler};
+pub use self_test::{ReadbackCallback, SelfTestReport};
+pub use signal::SignalWaveform;
+pub use single::SpwmSingle;
+pub use soft_serial::SoftSerial;
+pub use stepper::{StepDirection, Stepper, StepperOutputs};
+#[cfg(feature = "telemetry")]
+pub use telemetry::{TelemetryCallback, TelemetryEvent, TelemetryRecord};
+pub use tick_count::{
+    MAX_HARDWARE_FREQ_HZ, duration_to_ticks, is_valid_hardware_frequency, ticks_to_duration,
+};
+pub use update_policy::UpdatePolicy;
 
 /// Represents the output state of a PWM channel.
 pub enum SpwmState {
@@ -141,8 +334,34 @@ pub enum SpwmError {
     AlreadyDisabled,
     /// A PWM channel disable operation failed
     DisableFailed,
+    /// The requested pulse width is zero or longer than the channel period
+    InvalidPulseWidth,
     /// No free channel slots available for registration
     NoChannelSlotAvailable,
+    /// The channel chaining mode is not valid (e.g. zero period divider)
+    InvalidChainMode,
+    /// A transmission is already in progress on the channel
+    Busy,
+    /// The hardware timer frequency cannot meet the requested timing tolerance
+    TimingToleranceExceeded,
+    /// A PWM channel output is already paused
+    AlreadyPaused,
+    /// A PWM channel output is not paused
+    NotPaused,
+    /// The tick divider is zero or does not keep the timing of the enabled channels exact
+    InvalidTickDivider,
+    /// The SPWM manager is already suspended
+    AlreadySuspended,
+    /// The SPWM manager is not suspended
+    NotSuspended,
+    /// No free alarm slots available
+    NoAlarmSlotAvailable,
+    /// The alarm is not pending or its interval is zero or too long
+    InvalidAlarm,
+    /// A configuration parameter is out of its valid range (e.g. an unsupported microstep count)
+    InvalidConfiguration,
+    /// The channel is not in the mode the operation requires (e.g. not a signal generator)
+    InvalidMode,
 }
 
 /// Callback invoked when a channel's output state changes.
@@ -154,6 +373,50 @@ pub type OnOffCallback = fn(&SpwmState);
 /// Callback invoked at the end of each PWM period.
 pub type PeriodCallback = fn();
 
+/// Callback invoked when a burst of carrier periods completes.
+pub type BurstCompleteCallback = fn();
+
+/// Callback invoked when a double-pulse pattern completes.
+pub type DoublePulseCompleteCallback = fn();
+
+/// Callback invoked shortly before the end of each PWM period to prepare the next one.
+pub type PrepareCallback = fn();
+
+/// Callback invoked at the start and stop of the measurement window within each PWM period.
+///
+/// # Parameters
+/// - `active`: `true` at the window start, `false` at the window stop
+pub type MeasurementWindowCallback = fn(bool);
+
+/// Callback invoked at the end of each PWM period with the elapsed ticks.
+///
+/// # Parameters
+/// - `ticks`: Number of ticks since the channel was enabled
+pub type PeriodTicksCallback = fn(u64);
+
+/// Callback invoked when a bit-stream transmission completes.
+pub type TransmitCompleteCallback = fn();
+
+/// Callback providing the output level of the next period.
+///
+/// # Returns
+/// `Some(true)` for a fully on period, `Some(false)` for a fully off period, or `None` to use
+/// the configured duty cycle.
+pub type LevelSourceCallback = fn() -> Option<bool>;
+
+/// Callback invoked when a pending duty cycle or frequency update takes effect.
+///
+/// # Parameters
+/// - `update`: The on-time change applied at the period boundary
+pub type UpdateAppliedCallback = fn(&AppliedUpdate);
+
+/// Callback invoked when a channel is enabled or disabled.
+///
+/// # Parameters
+/// - `enabled`: `true` before the channel starts its output, `false` after its output was switched
+///   off
+pub type EnableCallback = fn(bool);
+
 /// Callback invoked when the first channel is enabled (timer should start).
 pub type TimerStartCallback = fn();
 
@@ -171,9 +434,13 @@ pub type ChannelId = usize;
 ///   An `Option<SpwmChannel>` instance, which can contain either:
 ///   - `Some(SpwmChannel)`: A valid `SpwmChannel` object.
 ///   - `None`: Indicates the absence of a channel.
+/// - `chain`: Link to the master channel that triggers this channel, if chained.
+/// - `mirror`: Link to the primary channel whose output this channel reproduces, if mirrored.
 #[derive(Default)]
 struct ChannelSlot {
     channel: Option<SpwmChannel>,
+    chain: Option<chain::ChainLink>,
+    mirror: Option<mirror::MirrorLink>,
 }
 
 /// A structure for managing Software Pulse Width Modulation (SPWM) channels.
@@ -185,11 +452,27 @@ struct ChannelSlot {
 /// # Type Parameters
 ///
 /// - `N`: The number of PWM channels, which determines the size of the `channel_slots` array.
+/// - `A`: The number of software alarm slots (see `set_alarm()`), none by default.
 ///
 /// # Fields
 /// - `channel_slots`: An array of `ChannelSlot` instances representing individual
 ///   PWM channels. Each channel can be configured and utilized independently.
+/// - `registered`: Number of registered channels, which occupy the first slots; `irq_handler()`
+///   never walks the empty slots behind them.
 /// - `freq_hz`: The frequency of the PWM signal in hertz (Hz).
+/// - `order`: Slot indices in the order they are processed by `irq_handler()`, sorted by
+///   channel priority.
+/// - `idle_ticks`: Remaining ticks before the next channel event, which `irq_handler()` skips.
+/// - `schedule_generation`: Reschedule generation bumped by the registered channels.
+/// - `telemetry`: Sink receiving channel event records (`telemetry` feature).
+/// - `watch`: Armed watch window recording the edges of one channel (`watch` feature).
+/// - `suspended`: Whether `irq_handler()` is stopped by `suspend()`.
+/// - `derating`: Percentage of the on-time removed from every channel by `derate_all()`.
+/// - `tick_divider`: Number of hardware timer ticks accounted to each `irq_handler()` call.
+/// - `ticks`: Number of hardware timer ticks processed by `irq_handler()`.
+/// - `event_tick`: Low 32 bits of `ticks` at the last processed event.
+/// - `alarms`: Software alarm slots processed by `irq_handler()`.
+/// - `irq_errors`: `IRQ_ERROR_*` flags collected by `take_errors()`.
 ///
 /// # Example
 ///
@@ -204,12 +487,29 @@ struct ChannelSlot {
 /// - The array size for `channel_slots` is determined at compile-time via the generic
 ///   `N` parameter, ensuring that the implementation is efficient and tailored to the
 ///   user's requirements.
-pub struct Spwm<const N: usize> {
+pub struct Spwm<const N: usize, const A: usize = 0> {
     channel_slots: [ChannelSlot; N],
+    registered: usize,
     freq_hz: u32,
+    order: [ChannelId; N],
+    #[cfg(feature = "telemetry")]
+    telemetry: Option<TelemetryCallback>,
+    #[cfg(feature = "watch")]
+    watch: Option<WatchState>,
+    idle_ticks: AtomicU32,
+    schedule_generation: RescheduleGeneration,
+    suspended: bool,
+    derating: AtomicU8,
+    tick_divider: u32,
+    ticks: TickCount,
+    event_tick: AtomicU32,
+    in_irq: AtomicBool,
+    overruns: AtomicU32,
+    alarms: TickScheduler<A>,
+    irq_errors: AtomicU32,
 }
 
-impl<const N: usize> Spwm<N> {
+impl<const N: usize, const A: usize> Spwm<N, A> {
     /// Creates a new instance with the specified frequency (in Hertz).
     ///
     /// # Parameters
@@ -225,14 +525,56 @@ impl<const N: usize> Spwm<N> {
     ///
     /// - `#[must_use]`: Indicates that the returned instance must be used;
     ///   ignoring it may lead to unexpected behavior or logic bugs.
+    ///
+    /// A manager without channel slots is rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// # use spwm::Spwm;
+    /// let spwm: Spwm<0> = Spwm::new(1_000_000);
+    /// ```
+    ///
+    /// The frequency is not checked here; use `try_new()` to reject a frequency of 0 or above
+    /// `MAX_HARDWARE_FREQ_HZ` up front.
     #[must_use]
     pub fn new(freq_hz: u32) -> Self {
+        const { assert!(N > 0, "Spwm needs at least one channel slot") };
+
         Self {
             freq_hz,
             channel_slots: core::array::from_fn(|_| ChannelSlot::default()),
+            registered: 0,
+            order: core::array::from_fn(|i| i),
+            #[cfg(feature = "telemetry")]
+            telemetry: None,
+            #[cfg(feature = "watch")]
+            watch: None,
+            idle_ticks: AtomicU32::new(0),
+            schedule_generation: RescheduleGeneration::new(),
+            suspended: false,
+            derating: AtomicU8::new(0),
+            tick_divider: 1,
+            ticks: TickCount::default(),
+            event_tick: AtomicU32::new(0),
+            in_irq: AtomicBool::new(false),
+            overruns: AtomicU32::new(0),
+            alarms: TickScheduler::new(),
+            irq_errors: AtomicU32::new(0),
         }
     }
 
+    /// Creates a new instance like `new()` after checking the hardware timer frequency.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidHardwareFrequency` if the frequency is 0 or above
+    /// `MAX_HARDWARE_FREQ_HZ`.
+    pub fn try_new(freq_hz: u32) -> Result<Self, SpwmError> {
+        if !is_valid_hardware_frequency(freq_hz) {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        Ok(Self::new(freq_hz))
+    }
+
     /// Creates a new SPWM (Sinusoidal Pulse Width Modulation) channel builder.
     ///
     /// This function initializes and returns an `SpwmChannelBuilder` in the
@@ -265,15 +607,29 @@ impl<const N: usize> Spwm<N> {
     /// # Errors
     /// Returns `SpwmError::NoChannelSlotAvailable` if all channel slots are already occupied.
     pub fn register_channel(&mut self, channel: SpwmChannel) -> Result<ChannelId, SpwmError> {
-        for (i, slot) in self.channel_slots.iter_mut().enumerate() {
-            if slot.channel.is_none() {
-                slot.channel = Some(channel);
-
-                return Ok(i);
-            }
+        if self.suspended {
+            channel.set_suspended(true);
         }
 
-        Err(SpwmError::NoChannelSlotAvailable)
+        channel
+            .derating
+            .store(self.derating.load(Ordering::Relaxed), Ordering::SeqCst);
+        channel
+            .reschedule_index
+            .store(self.schedule_generation.index(), Ordering::Relaxed);
+
+        let id = self.registered;
+        let slot = self
+            .channel_slots
+            .get_mut(id)
+            .ok_or(SpwmError::NoChannelSlotAvailable)?;
+
+        slot.channel = Some(channel);
+        self.registered = id.saturating_add(1);
+        self.sort_order();
+        self.idle_ticks.store(0, Ordering::Relaxed);
+
+        Ok(id)
     }
 
     /// Retrieves a reference to a `SpwmChannel` associated with the specified `channel_id`,
@@ -305,11 +661,475 @@ impl<const N: usize> Spwm<N> {
         self.channel_slots.get(channel_id)?.channel.as_ref()
     }
 
+    /// Changes the processing priority of a registered channel.
+    ///
+    /// Channels with a higher priority have their callbacks invoked first within
+    /// `irq_handler()`, e.g. to always switch a high-side output off before a low-side output
+    /// is switched on.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel
+    /// - `priority`: New channel priority
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if no channel is registered under `channel_id`.
+    pub fn set_channel_priority(
+        &mut self,
+        channel_id: ChannelId,
+        priority: u8,
+    ) -> Result<(), SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        channel.priority.store(priority, Ordering::Relaxed);
+        self.sort_order();
+
+        Ok(())
+    }
+
+    /// Updates the duty cycles of all registered channels from a buffer in a single pass.
+    ///
+    /// Meant for levels refreshed as a block, e.g. from an ADC/DMA buffer driving an LED bar or
+    /// from a received DMX frame. The whole buffer is validated before any channel is changed.
+    /// The updates take effect at the next period boundary of each channel, like
+    /// `update_duty_cycle()`. Channels whose duty cycle is unchanged are skipped, and values for
+    /// empty slots, trigger outputs and signal generators are ignored.
+    ///
+    /// # Parameters
+    /// - `duty_cycles`: Duty cycle percentage (0-100) for every channel slot
+    ///
+    /// # Returns
+    /// The number of channels whose duty cycle changed.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if a value is greater than 100. No channel is
+    /// changed in that case.
+    pub fn apply_duty_buffer(&self, duty_cycles: &[u8; N]) -> Result<usize, SpwmError> {
+        if duty_cycles
+            .iter()
+            .any(|&duty_cycle| duty_cycle > MAX_DUTY_CYCLE)
+        {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        let mut changed = 0usize;
+
+        for (slot, &duty_cycle) in self.channel_slots.iter().zip(duty_cycles) {
+            if let Some(channel) = &slot.channel
+                && channel.apply_duty_cycle(duty_cycle)
+            {
+                changed = changed.saturating_add(1);
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Changes the hardware timer frequency and rescales all registered channels to it.
+    ///
+    /// Call this after the tick timer clock changed, e.g. when entering or leaving a low-power
+    /// mode. Every channel keeps its output frequency and duty cycle: the period length, the
+    /// on-time and the position within the current period are scaled by the frequency ratio, so
+    /// running periods continue seamlessly. Start delays, one-shot pulses and bit-stream timing
+    /// are given in ticks and are not rescaled.
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: New hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// No channel is changed if an error is returned:
+    /// - `SpwmError::InvalidHardwareFrequency` if the frequency is 0 or above
+    ///   `MAX_HARDWARE_FREQ_HZ`
+    /// - `SpwmError::InvalidFrequency` if a channel frequency is too high for the new hardware
+    ///   timer frequency (must be at least 100x lower)
+    pub fn update_hardware_frequency(&mut self, hardware_freq_hz: u32) -> Result<(), SpwmError> {
+        if !is_valid_hardware_frequency(hardware_freq_hz) {
+            return Err(SpwmError::InvalidHardwareFrequency);
+        }
+
+        let freq_hz = self.freq_hz;
+        let scale = |ticks: u32| {
+            u64::from(ticks)
+                .checked_mul(u64::from(hardware_freq_hz))
+                .and_then(|ticks| ticks.checked_div(u64::from(freq_hz)))
+                .and_then(|ticks| u32::try_from(ticks).ok())
+                .unwrap_or(0)
+        };
+
+        if self.channels().any(|channel| {
+            scale(channel.period_ticks.load(Ordering::Relaxed))
+                < channel::FREQUENCY_DIFFERENCE_REQUIRED
+        }) {
+            return Err(SpwmError::InvalidFrequency);
+        }
+
+        // bring skipped ticks in, so they are not counted in units of the new frequency
+        let tick = self.ticks.low();
+        let skipped_ticks = tick.wrapping_sub(self.event_tick.swap(tick, Ordering::Relaxed));
+
+        for channel in self.channels() {
+            channel.catch_up(skipped_ticks, 0);
+            channel.rescale(scale);
+        }
+
+        self.freq_hz = hardware_freq_hz;
+
+        Ok(())
+    }
+
+    /// Returns the largest tick divider that keeps the timing of all enabled channels exact.
+    ///
+    /// The divider is the greatest common divisor of the ticks of every event within the period
+    /// of the enabled channels: period end, on and off edges (including pending updates), the
+    /// prepare callback, the measurement window and the end of the blanking window, e.g. 50 000
+    /// for a single 1 Hz 50% status LED on a 100 kHz timer. Bit streams, level sources,
+    /// one-shot pulses and trigger delays are not taken into account. `u32::MAX` means no
+    /// channel is enabled.
+    #[must_use]
+    pub fn max_tick_divider(&self) -> u32 {
+        let divider = self
+            .channels()
+            .filter(|channel| channel.is_enabled())
+            .flat_map(SpwmChannel::event_ticks)
+            .fold(0, gcd);
+
+        if divider == 0 { u32::MAX } else { divider }
+    }
+
+    /// Sets the number of hardware timer ticks each `irq_handler()` call accounts for.
+    ///
+    /// Lets the firmware slow the tick timer down by `tick_divider` while only slow channels are
+    /// enabled, and speed it up again (divider 1) before a fast channel is enabled. Channel
+    /// timing keeps being expressed in undivided ticks, so the hardware timer frequency has to
+    /// be divided by the same factor at the same time. A period in progress whose tick counter
+    /// is not a multiple of the divider ends up to `tick_divider - 1` ticks late once.
+    ///
+    /// # Parameters
+    /// - `tick_divider`: Tick divider, must divide `max_tick_divider()`
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidTickDivider` if the divider is 0 or does not divide
+    /// `max_tick_divider()`, i.e. it would step over an event tick of an enabled channel.
+    pub fn set_tick_divider(&mut self, tick_divider: u32) -> Result<(), SpwmError> {
+        let max_tick_divider = self.max_tick_divider();
+
+        if tick_divider == 0
+            || (max_tick_divider != u32::MAX
+                && max_tick_divider.checked_rem(tick_divider) != Some(0))
+        {
+            return Err(SpwmError::InvalidTickDivider);
+        }
+
+        self.tick_divider = tick_divider;
+
+        Ok(())
+    }
+
+    /// Returns the current tick divider.
+    #[must_use]
+    pub fn tick_divider(&self) -> u32 {
+        self.tick_divider
+    }
+
+    /// Suspends all channels, e.g. before entering a STOP/STANDBY low-power mode.
+    ///
+    /// All outputs are driven off while `irq_handler()` stops processing, so the tick counters
+    /// of the channels freeze where they are. Enabled channels stay enabled, which records the
+    /// set of running channels for `resume()`.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::AlreadySuspended` if the manager is already suspended.
+    pub fn suspend(&mut self) -> Result<(), SpwmError> {
+        if self.suspended {
+            return Err(SpwmError::AlreadySuspended);
+        }
+
+        self.suspended = true;
+
+        for channel in self.channels() {
+            channel.set_suspended(true);
+        }
+
+        Ok(())
+    }
+
+    /// Resumes the channels suspended by `suspend()`.
+    ///
+    /// The outputs immediately take the state of their waveforms again and the channels continue
+    /// from the tick at which they were suspended, so all channels keep their original phases.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::NotSuspended` if the manager is not suspended.
+    pub fn resume(&mut self) -> Result<(), SpwmError> {
+        if !self.suspended {
+            return Err(SpwmError::NotSuspended);
+        }
+
+        self.suspended = false;
+
+        for channel in self.channels() {
+            channel.set_suspended(false);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the manager is suspended.
+    #[must_use]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Caps the on-time of every channel to `percent` of its configured value.
+    ///
+    /// Meant to shed load across all outputs with a single call, e.g. from a brown-out or
+    /// supply fault interrupt. The cap takes effect at the next period boundary of each channel
+    /// and also applies to channels registered later. The configured duty cycles are kept, so
+    /// `restore_all()` returns every channel to them. Bit-stream and level source periods and
+    /// one-shot pulses are not derated.
+    ///
+    /// # Parameters
+    /// - `percent`: Percentage of the configured on-time to keep (0-100)
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if `percent` is greater than 100.
+    pub fn derate_all(&self, percent: u8) -> Result<(), SpwmError> {
+        let derating = MAX_DUTY_CYCLE
+            .checked_sub(percent)
+            .ok_or(SpwmError::InvalidDutyCycle)?;
+
+        self.set_derating(derating);
+
+        Ok(())
+    }
+
+    /// Removes the cap set by `derate_all()`, returning every channel to its configured duty
+    /// cycle at its next period boundary.
+    pub fn restore_all(&self) {
+        self.set_derating(0);
+    }
+
+    /// Returns the percentage of the configured on-time kept by `derate_all()` (100 if the
+    /// channels are not derated).
+    pub fn derate_percent(&self) -> u8 {
+        MAX_DUTY_CYCLE.saturating_sub(self.derating.load(Ordering::Relaxed))
+    }
+
+    /// Returns the sum of the duty cycles of all enabled channels in percent.
+    ///
+    /// Trigger outputs and signal generators count with the on-time of their period in
+    /// progress, rounded up to the next percent.
+    pub fn total_duty(&self) -> u32 {
+        self.channels()
+            .filter(|channel| channel.is_enabled())
+            .map(|channel| u32::from(channel.load_percent()))
+            .sum()
+    }
+
+    /// Returns the weighted sum of the on-time ratios of all enabled channels.
+    ///
+    /// Each channel contributes its weight scaled by the on-time of the period in progress, so
+    /// with every weight set to the full-scale current of its load the result is the average
+    /// current drawn in the same unit. Unlike `total_duty()` this includes limits applied by
+    /// the tick engine, e.g. `derate_all()`, and is cheap enough for a supervisor loop.
+    ///
+    /// # Parameters
+    /// - `weights`: Weight of every channel slot, e.g. the full-scale current in mA
+    ///
+    /// # Returns
+    /// The weighted sum, saturated at `u32::MAX`.
+    pub fn total_weighted_duty(&self, weights: &[u16; N]) -> u32 {
+        self.channel_slots
+            .iter()
+            .zip(weights)
+            .filter_map(|(slot, &weight)| Some((slot.channel.as_ref()?, weight)))
+            .filter(|(channel, _)| channel.is_enabled())
+            .map(|(channel, weight)| {
+                let period_ticks = u64::from(channel.period_ticks.load(Ordering::Relaxed));
+                let on_ticks =
+                    u64::from(channel.on_ticks.load(Ordering::Relaxed)).min(period_ticks);
+
+                u64::from(weight)
+                    .saturating_mul(on_ticks)
+                    .checked_div(period_ticks)
+                    .unwrap_or(0)
+            })
+            .fold(0u32, |total, weighted| {
+                total.saturating_add(u32::try_from(weighted).unwrap_or(u32::MAX))
+            })
+    }
+
+    /// Reduces the load of the enabled channels until the sum of their duty cycles fits into
+    /// a budget, e.g. to keep the total current of several PWM loads of a battery device below
+    /// a limit.
+    ///
+    /// Channels are shed in the order of their load-shedding priority, lowest first (channels
+    /// with equal priority in reverse slot order): a channel whose whole duty cycle is needed is
+    /// disabled, and the last one shed only has its duty cycle lowered by the remaining excess,
+    /// taking effect at its next period boundary. Trigger outputs and signal generators have no
+    /// duty cycle to lower, so they are always disabled when shed. Duty cycles are counted as
+    /// by `total_duty()`, regardless of `derate_all()`.
+    ///
+    /// # Parameters
+    /// - `max_total_duty`: Budget for the sum of the duty cycles in percent
+    ///
+    /// # Returns
+    /// The sum of the duty cycles of the enabled channels after shedding.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::DisableFailed` if a channel could not be disabled.
+    pub fn shed_load(&self, max_total_duty: u32) -> Result<u32, SpwmError> {
+        let mut total_duty = self.total_duty();
+
+        while let Some(excess) = total_duty
+            .checked_sub(max_total_duty)
+            .filter(|&excess| excess != 0)
+        {
+            let Some(channel) = self
+                .channel_slots
+                .iter()
+                .rev()
+                .filter_map(|slot| slot.channel.as_ref())
+                .filter(|channel| channel.is_enabled() && channel.load_percent() != 0)
+                .min_by_key(|channel| channel.shed_priority())
+            else {
+                break;
+            };
+            let duty_cycle = channel.load_percent();
+
+            if let Some(reduced) = u8::try_from(excess)
+                .ok()
+                .and_then(|excess| duty_cycle.checked_sub(excess))
+                .filter(|&reduced| reduced != 0 && channel.check_duty_writable().is_ok())
+            {
+                channel.update_duty_cycle(reduced)?;
+                total_duty = max_total_duty;
+            } else {
+                channel.disable()?;
+                total_duty = total_duty.saturating_sub(u32::from(duty_cycle));
+            }
+        }
+
+        Ok(total_duty)
+    }
+
+    /// Sets the percentage of the on-time removed from every channel.
+    fn set_derating(&self, derating: u8) {
+        self.derating.store(derating, Ordering::SeqCst);
+
+        for channel in self.channels() {
+            channel.derating.store(derating, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the number of hardware timer ticks processed by `irq_handler()`.
+    ///
+    /// The counter increases monotonically (by the tick divider per call) and gives the
+    /// application a timebase derived from the PWM tick interrupt. Ticks while the manager is
+    /// suspended are not counted, and ticks counted before `update_hardware_frequency()` keep
+    /// the previous tick length.
+    #[must_use]
+    pub fn ticks(&self) -> u64 {
+        self.ticks.get()
+    }
+
+    /// Returns the position of a channel within its current period in ticks (0 at the start
+    /// of a period).
+    ///
+    /// Accounts for the ticks `irq_handler()` skipped since its last event, so the position is
+    /// exact at any time between two interrupts.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered.
+    pub fn phase_ticks(&self, channel_id: ChannelId) -> Result<u32, SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        Ok(channel.phase_ticks(self.lag_ticks()))
+    }
+
+    /// Shifts the phase of a running channel so that its position within the period, as
+    /// reported by `phase_ticks()`, becomes `offset` at the time of the call.
+    ///
+    /// Meant for closed-loop phase alignment without disable/enable cycles, e.g. to a signal
+    /// measured by input capture. The shift is applied at the next period boundary by keeping
+    /// the output off for up to one period before the following period starts, so the output
+    /// never produces an extra or shortened on-pulse. A new request replaces a pending one.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel
+    /// - `offset`: The requested position within the period in ticks (wrapped to the period)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::AlreadyDisabled` if the channel is disabled
+    /// - `SpwmError::InvalidMode` if the channel periods are driven by a master channel
+    pub fn set_phase_ticks(&self, channel_id: ChannelId, offset: u32) -> Result<(), SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if !channel.is_enabled() {
+            return Err(SpwmError::AlreadyDisabled);
+        }
+
+        if channel.chained.load(Ordering::Relaxed) || channel.locked.load(Ordering::Relaxed) {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        channel.shift_phase(channel.phase_ticks(self.lag_ticks()), offset);
+
+        Ok(())
+    }
+
+    /// Returns the ticks processed by `irq_handler()` since its last event, which the channels
+    /// catch up on at the next event.
+    fn lag_ticks(&self) -> u32 {
+        self.ticks
+            .low()
+            .wrapping_sub(self.event_tick.load(Ordering::Relaxed))
+    }
+
+    /// Returns the time covered by `ticks()` at the current hardware timer frequency.
+    ///
+    /// # Returns
+    /// The elapsed time, or `None` if the hardware timer frequency is 0.
+    #[must_use]
+    pub fn elapsed(&self) -> Option<core::time::Duration> {
+        ticks_to_duration(self.ticks(), self.freq_hz)
+    }
+
+    /// Re-sorts the processing order by descending channel priority, keeping slot order for
+    /// channels with equal priority.
+    fn sort_order(&mut self) {
+        if let Some(order) = self.order.get_mut(..self.registered) {
+            slots::sort_order(order, &self.channel_slots);
+        }
+    }
+
     /// Handles the Interrupt Request (IRQ) for Pulse Width Modulation (PWM) channels.
     ///
     /// This function is invoked to process the state of all PWM channel slots when an IRQ occurs.
     /// It ensures that the PWM signals operate, according to their defined periods, on-times, and
-    /// triggers appropriate callbacks when specific events occur.
+    /// triggers appropriate callbacks when specific events occur. Channels are processed in
+    /// descending priority order.
+    ///
+    /// Only ticks with a due event (an Off edge, a period end or a trigger) process the
+    /// channels. After each event the handler computes how many upcoming ticks contain no event
+    /// for any channel and merely counts them down; the channels catch up on the skipped ticks
+    /// at the next event. Runtime changes that affect the timing before the next event (e.g.
+    /// enabling a channel or changing its frequency) cut the skipped interval short, so a tick
+    /// without a due event only checks one flag per channel.
+    ///
+    /// With a tick divider set by `set_tick_divider()` each call accounts for that many
+    /// hardware timer ticks. While the manager is suspended the handler does nothing.
+    ///
+    /// The handler must not preempt itself. A call made while another call is still in
+    /// progress (e.g. from a second interrupt with a higher priority) returns right away and
+    /// its tick is lost, but it is counted by `overrun_count()` so the misconfiguration can be
+    /// detected instead of corrupting the waveforms.
     ///
     /// # Example
     ///
@@ -320,40 +1140,110 @@ impl<const N: usize> Spwm<N> {
     /// }
     /// ```
     pub fn irq_handler(&self) {
-        for slot in &self.channel_slots {
-            if let Some(ref channel) = slot.channel
-                && channel.enabled.load(Ordering::Relaxed)
-            {
-                let current_ticks = channel.counter_tick();
-                let period_ticks = channel.period_ticks.load(Ordering::Relaxed);
-                let on_ticks = channel.on_ticks.load(Ordering::Relaxed);
-
-                if current_ticks >= (period_ticks - 1) {
-                    let update_ticks = channel.update_on_ticks.load(Ordering::Relaxed);
-
-                    channel.counter_reset();
-
-                    if let Some(callback) = channel.period_callback.get() {
-                        callback();
-                    }
-
-                    if update_ticks != on_ticks {
-                        channel.set_on_ticks(update_ticks);
-                    }
-
-                    let on_ticks = channel.on_ticks.load(Ordering::Relaxed);
-
-                    if on_ticks != 0
-                        && let Some(callback) = channel.on_off_callback.get()
-                    {
-                        callback(&SpwmState::On);
-                    }
-                } else if current_ticks == on_ticks
-                    && let Some(callback) = channel.on_off_callback.get()
-                {
-                    callback(&SpwmState::Off);
+        if self.suspended {
+            return;
+        }
+
+        if self.in_irq.swap(true, Ordering::Acquire) {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+            self.flag_error(IRQ_ERROR_OVERRUN);
+            return;
+        }
+
+        self.handle_tick();
+        self.in_irq.store(false, Ordering::Release);
+    }
+
+    /// Returns the number of `irq_handler()` calls dropped because they preempted a call in
+    /// progress.
+    ///
+    /// A nonzero count means the tick interrupt can preempt itself, e.g. because the handler is
+    /// called from several interrupts with different priorities, and ticks were lost.
+    pub fn overrun_count(&self) -> u32 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Processes one tick of `irq_handler()` without the reentrancy guard.
+    fn handle_tick(&self) {
+        let tick_divider = self.tick_divider;
+        let tick = self.ticks.add(tick_divider);
+
+        if !self.schedule_generation.take_rescheduled()
+            && let Some(idle_ticks) = self
+                .idle_ticks
+                .load(Ordering::Relaxed)
+                .checked_sub(tick_divider)
+        {
+            self.idle_ticks.store(idle_ticks, Ordering::Relaxed);
+            return;
+        }
+
+        let skipped_ticks = tick
+            .wrapping_sub(self.event_tick.swap(tick, Ordering::Relaxed))
+            .wrapping_sub(1);
+
+        slots::process_tick(
+            self.slots(),
+            self.order.get(..self.registered).unwrap_or_default(),
+            skipped_ticks,
+            tick_divider.saturating_sub(1),
+            |i, channel| {
+                #[cfg(any(feature = "telemetry", feature = "watch"))]
+                let was_on = channel.output_on.load(Ordering::Relaxed);
+                let period_end = channel.process_tick();
+
+                #[cfg(feature = "telemetry")]
+                self.record_telemetry(i, tick, was_on, period_end);
+                #[cfg(feature = "watch")]
+                self.record_watch(i, tick, was_on);
+
+                if period_end {
+                    self.trigger_chained(i);
                 }
+            },
+        );
+
+        for i in 0..self.registered {
+            if !self.is_mirrored(i) {
+                continue;
             }
+
+            #[cfg(any(feature = "telemetry", feature = "watch"))]
+            let was_on = self
+                .get_channel(i)
+                .is_some_and(|channel| channel.output_on.load(Ordering::Relaxed));
+            self.update_mirror(i);
+
+            #[cfg(feature = "telemetry")]
+            self.record_telemetry(i, tick, was_on, false);
+            #[cfg(feature = "watch")]
+            self.record_watch(i, tick, was_on);
         }
+
+        let idle_ticks = slots::idle_ticks(self.slots()).min(self.process_alarms(tick));
+
+        self.idle_ticks.store(idle_ticks, Ordering::Relaxed);
+    }
+
+    /// Returns the slots of the registered channels.
+    fn slots(&self) -> &[ChannelSlot] {
+        self.channel_slots
+            .get(..self.registered)
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over the registered channels.
+    fn channels(&self) -> impl Iterator<Item = &SpwmChannel> {
+        slots::channels(self.slots())
     }
 }
+
+/// Returns the greatest common divisor of `a` and `b` (`gcd(0, b) == b`).
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while let Some(remainder) = a.checked_rem(b) {
+        a = b;
+        b = remainder;
+    }
+
+    a
+}
@@ -0,0 +1,255 @@
+//! Mains-synchronized AC load control.
+//!
+//! A channel in phase-angle mode does not run at its own frequency: every call of
+//! `Spwm::sync_to_zero_cross()` (e.g. from the zero-cross detector interrupt) starts a mains
+//! half-cycle, and the duty cycle selects the conducting part of it. Leading-edge control
+//! switches the load on after a phase delay, as TRIAC dimmers do with a short gate pulse.
+//! Trailing-edge control switches the load on at the zero cross and off after the conduction
+//! time, as MOSFET dimmers do for capacitive loads.
+//!
+//! Burst-fire (integral cycle) control instead passes complete half-cycles, `n` out of every
+//! `m`, which suits resistive heaters with far lower EMI than phase cutting.
+
+use crate::{ChannelId, Spwm, SpwmChannel, SpwmError, SpwmState};
+use core::sync::atomic::Ordering;
+
+/// Mains mode: the channel runs on its own.
+pub(crate) const MAINS_NONE: u8 = 0;
+
+/// Mains mode: leading-edge phase-angle control.
+pub(crate) const MAINS_LEADING: u8 = 1;
+
+/// Mains mode: trailing-edge phase-angle control.
+pub(crate) const MAINS_TRAILING: u8 = 2;
+
+/// Mains mode: burst-fire control.
+pub(crate) const MAINS_BURST: u8 = 3;
+
+/// Phase-angle control variant of a dimmer channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DimmerEdge {
+    /// The output switches on after the phase delay. With a nonzero `gate_pulse_ticks` it only
+    /// stays on for that many ticks (TRIAC gate trigger), otherwise until the end of the
+    /// half-cycle.
+    Leading { gate_pulse_ticks: u32 },
+    /// The output switches on at the zero cross and off after the conduction time
+    Trailing,
+}
+
+impl SpwmChannel {
+    /// Returns whether the channel periods are started by `Spwm::sync_to_zero_cross()`.
+    pub(crate) fn is_mains_synced(&self) -> bool {
+        self.mains_mode.load(Ordering::Relaxed) != MAINS_NONE
+    }
+
+    /// Starts a mains half-cycle after the on-time was latched.
+    ///
+    /// A leading-edge channel keeps the output off for the part of the period that does not
+    /// conduct, using the start delay countdown, and then runs the on-time.
+    pub(crate) fn start_half_cycle(&self) {
+        match self.mains_mode.load(Ordering::Relaxed) {
+            MAINS_LEADING => {}
+            MAINS_BURST => {
+                let on_ticks = if self.next_burst_half_cycle() {
+                    self.period_ticks.load(Ordering::Relaxed)
+                } else {
+                    0
+                };
+
+                self.set_on_ticks(on_ticks);
+                self.start_period();
+
+                return;
+            }
+            _ => {
+                self.start_period();
+
+                return;
+            }
+        }
+
+        let on_ticks = self.on_ticks.load(Ordering::Relaxed);
+        let delay_ticks = self
+            .period_ticks
+            .load(Ordering::Relaxed)
+            .saturating_sub(on_ticks);
+        let gate_pulse_ticks = self.gate_pulse_ticks.load(Ordering::Relaxed);
+
+        if gate_pulse_ticks != 0 && on_ticks != 0 {
+            self.set_on_ticks(gate_pulse_ticks.min(on_ticks));
+        }
+
+        if delay_ticks == 0 || on_ticks == 0 {
+            self.start_period();
+        } else {
+            self.start_countdown.store(delay_ticks, Ordering::SeqCst);
+            self.set_output(&SpwmState::Off);
+        }
+    }
+}
+
+impl SpwmChannel {
+    /// Advances the burst-fire pattern by a half-cycle.
+    ///
+    /// The conducting half-cycles are spread evenly over the `m` half-cycles of the pattern
+    /// (e.g. 2 out of 6 conduct every third half-cycle) instead of forming one block.
+    ///
+    /// # Returns
+    /// Whether the starting half-cycle conducts.
+    fn next_burst_half_cycle(&self) -> bool {
+        let on_half_cycles = self.burst_on_half_cycles.load(Ordering::Relaxed);
+        let half_cycles = self.burst_half_cycles.load(Ordering::Relaxed);
+        let accumulator = self
+            .burst_accumulator
+            .load(Ordering::Relaxed)
+            .saturating_add(on_half_cycles);
+        let conducts = accumulator >= half_cycles;
+
+        self.burst_accumulator.store(
+            if conducts {
+                accumulator.saturating_sub(half_cycles)
+            } else {
+                accumulator
+            },
+            Ordering::Relaxed,
+        );
+
+        conducts
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Turns a channel into a phase-angle dimmer synchronized to the mains zero cross.
+    ///
+    /// The channel frequency has to be set to twice the mains frequency, or slightly above, so
+    /// one period covers a half-cycle; the duty cycle then selects the conducting part of each
+    /// half-cycle, and duty cycle updates apply at the next zero cross. An enabled channel
+    /// stays off until the first `sync_to_zero_cross()`, and switches off at the end of its
+    /// period if the next zero cross does not come.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel
+    /// - `edge`: Leading- or trailing-edge control
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidMode` if the channel is chained or mirrored
+    pub fn set_phase_angle(
+        &self,
+        channel_id: ChannelId,
+        edge: DimmerEdge,
+    ) -> Result<(), SpwmError> {
+        let channel = self.mains_channel(channel_id)?;
+        let (mode, gate_pulse_ticks) = match edge {
+            DimmerEdge::Leading { gate_pulse_ticks } => (MAINS_LEADING, gate_pulse_ticks),
+            DimmerEdge::Trailing => (MAINS_TRAILING, 0),
+        };
+
+        channel
+            .gate_pulse_ticks
+            .store(gate_pulse_ticks, Ordering::Relaxed);
+        if channel.mains_mode.swap(mode, Ordering::SeqCst) == MAINS_NONE {
+            channel.set_chained(true);
+        }
+
+        Ok(())
+    }
+
+    /// Turns a channel into a burst-fire controller passing `on_half_cycles` complete mains
+    /// half-cycles out of every `half_cycles`, synchronized by `sync_to_zero_cross()`.
+    ///
+    /// The channel frequency has to be set to twice the mains frequency, or slightly below, so
+    /// the next zero cross arrives before the period ends and consecutive conducting
+    /// half-cycles keep the output on. The duty cycle of the channel is not used. Calling this
+    /// again on a burst-fire channel changes the ratio from the next zero cross on.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel
+    /// - `on_half_cycles`: Number of conducting half-cycles per pattern
+    /// - `half_cycles`: Length of the pattern in half-cycles
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidMode` if the channel is chained or mirrored
+    /// - `SpwmError::InvalidDutyCycle` if `half_cycles` is 0 or less than `on_half_cycles`
+    pub fn set_burst_fire(
+        &self,
+        channel_id: ChannelId,
+        on_half_cycles: u32,
+        half_cycles: u32,
+    ) -> Result<(), SpwmError> {
+        let channel = self.mains_channel(channel_id)?;
+
+        if half_cycles == 0 || on_half_cycles > half_cycles {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        channel
+            .burst_on_half_cycles
+            .store(on_half_cycles, Ordering::Relaxed);
+        channel
+            .burst_half_cycles
+            .store(half_cycles, Ordering::Relaxed);
+
+        if channel.mains_mode.swap(MAINS_BURST, Ordering::SeqCst) != MAINS_BURST {
+            channel.burst_accumulator.store(0, Ordering::Relaxed);
+            channel.set_chained(true);
+        }
+
+        Ok(())
+    }
+
+    /// Returns a channel to running at its own frequency.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered.
+    pub fn clear_mains_sync(&self, channel_id: ChannelId) -> Result<(), SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if channel.mains_mode.swap(MAINS_NONE, Ordering::SeqCst) != MAINS_NONE {
+            channel.set_chained(false);
+        }
+
+        Ok(())
+    }
+
+    /// Starts a mains half-cycle on a mains-synchronized channel.
+    ///
+    /// Meant to be called from the zero-cross detector interrupt; the half-cycle starts on
+    /// the next tick processed by `irq_handler()`, which also cuts a half-cycle still in
+    /// progress short.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidMode` if the channel is not mains-synchronized
+    pub fn sync_to_zero_cross(&self, channel_id: ChannelId) -> Result<(), SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if !channel.is_mains_synced() {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        channel.trigger();
+
+        Ok(())
+    }
+
+    /// Returns a channel that may become mains-synchronized.
+    fn mains_channel(&self, channel_id: ChannelId) -> Result<&SpwmChannel, SpwmError> {
+        let slot = self
+            .channel_slots
+            .get(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let channel = slot.channel.as_ref().ok_or(SpwmError::InvalidChannel)?;
+
+        if slot.chain.is_some() || slot.mirror.is_some() {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        Ok(channel)
+    }
+}
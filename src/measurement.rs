@@ -0,0 +1,80 @@
+//! Measurement windows within the period of a channel.
+//!
+//! A measurement window callback is invoked with `true` at a configurable tick after the period
+//! start and with `false` at a later one, so sensor measurements (ADC conversions, capacitive
+//! sensing) can be gated to a quiet part of the PWM cycle. Both ticks are events of the tick
+//! engine like the output edges, so they cost no processing in between and need no tick
+//! counting by the application.
+
+use crate::channel::SCHEDULE_CHANGED;
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Moves the measurement window from the next window tick on.
+    ///
+    /// # Parameters
+    /// - `start_ticks`: Ticks from the period start to the window start
+    /// - `stop_ticks`: Ticks from the period start to the window stop
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidPulseWidth` if the window does not stop after it starts.
+    pub fn set_measurement_window(
+        &self,
+        start_ticks: u32,
+        stop_ticks: u32,
+    ) -> Result<(), SpwmError> {
+        if start_ticks >= stop_ticks {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        self.window_start_ticks.store(start_ticks, Ordering::SeqCst);
+        self.window_stop_ticks.store(stop_ticks, Ordering::SeqCst);
+        self.reschedule(SCHEDULE_CHANGED);
+
+        Ok(())
+    }
+
+    /// Returns the start and stop ticks of the measurement window.
+    pub fn measurement_window(&self) -> (u32, u32) {
+        (
+            self.window_start_ticks.load(Ordering::Relaxed),
+            self.window_stop_ticks.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Invokes the measurement window callback if the tick counter reached a window tick.
+    ///
+    /// Ticks of a period beyond the period end are never reached, and a start delay or phase
+    /// shift in progress does not count as period time.
+    pub(crate) fn signal_measurement_window(&self, counter: u32) {
+        let Some(callback) = self.measurement_window_callback.get() else {
+            return;
+        };
+
+        if self.start_countdown.load(Ordering::Relaxed) != 0 {
+            return;
+        }
+
+        let (start_ticks, stop_ticks) = self.measurement_window();
+
+        if counter == start_ticks {
+            callback(true);
+        } else if counter == stop_ticks {
+            callback(false);
+        }
+    }
+
+    /// Returns the ticks until the next window tick is processed, given `ticks_until` of the
+    /// channel idle time computation, or `None` if no window tick is ahead in the period.
+    pub(crate) fn window_idle_ticks(
+        &self,
+        ticks_until: impl Fn(u32) -> Option<u32>,
+    ) -> Option<u32> {
+        self.measurement_window_callback.get()?;
+
+        let (start_ticks, stop_ticks) = self.measurement_window();
+
+        ticks_until(start_ticks).or_else(|| ticks_until(stop_ticks))
+    }
+}
@@ -0,0 +1,164 @@
+//! Redundant channel mirroring for safety outputs.
+//!
+//! A mirrored (secondary) channel does not generate its own waveform: after every processed tick
+//! its output is set to the output of the primary channel, optionally inverted. Dual-channel
+//! safety architectures drive two pins that must always agree this way, and a divergence
+//! callback reports when the secondary output cannot follow (e.g. because the secondary is
+//! disabled or paused on its own).
+
+use crate::channel::SCHEDULE_CHANGED;
+use crate::{ChannelId, IRQ_ERROR_DIVERGENCE, Spwm, SpwmError, SpwmState};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Callback invoked when the outputs of a mirrored channel pair stop agreeing.
+///
+/// # Parameters
+/// - `primary_id`: The identifier of the primary channel
+/// - `secondary_id`: The identifier of the mirrored channel
+pub type DivergenceCallback = fn(ChannelId, ChannelId);
+
+/// Link between a mirrored channel and its primary.
+///
+/// # Fields
+/// - `primary`: Identifier of the primary channel
+/// - `inverted`: Whether the mirrored output is the inverse of the primary output
+/// - `divergence_callback`: Callback invoked when the outputs stop agreeing
+/// - `diverged`: Whether a divergence was reported and the outputs did not agree since
+pub(crate) struct MirrorLink {
+    primary: ChannelId,
+    inverted: bool,
+    divergence_callback: Option<DivergenceCallback>,
+    diverged: AtomicBool,
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Makes a secondary channel reproduce the output of a primary channel.
+    ///
+    /// From then on `irq_handler()` sets the secondary output to the primary output (or its
+    /// inverse) on every processed tick, after all channels were processed, so both outputs
+    /// switch on the same tick regardless of priorities. The secondary keeps its own
+    /// enable, pause and callbacks, which gate its output as usual: while the primary is enabled
+    /// and the reported outputs do not agree, e.g. because the secondary was disabled,
+    /// `divergence_callback` is invoked once until they agree again.
+    ///
+    /// # Parameters
+    /// - `primary_id`: The identifier of the primary channel
+    /// - `secondary_id`: The identifier of the mirrored channel
+    /// - `inverted`: Whether the secondary output is the inverse of the primary output
+    /// - `divergence_callback`: Callback invoked when the outputs stop agreeing
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if either channel is not registered or both are the same
+    /// - `SpwmError::InvalidMode` if the primary is itself mirrored, or the secondary is
+    ///   chained or the primary of another mirrored channel
+    pub fn mirror(
+        &mut self,
+        primary_id: ChannelId,
+        secondary_id: ChannelId,
+        inverted: bool,
+        divergence_callback: Option<DivergenceCallback>,
+    ) -> Result<(), SpwmError> {
+        if primary_id == secondary_id
+            || self.get_channel(primary_id).is_none()
+            || self.get_channel(secondary_id).is_none()
+        {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        let is_primary = |id| {
+            self.channel_slots
+                .iter()
+                .filter_map(|slot| slot.mirror.as_ref())
+                .any(|link| link.primary == id)
+        };
+
+        if self.is_mirrored(primary_id)
+            || is_primary(secondary_id)
+            || self
+                .channel_slots
+                .get(secondary_id)
+                .is_some_and(|slot| slot.chain.is_some())
+        {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        let slot = self
+            .channel_slots
+            .get_mut(secondary_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        slot.mirror = Some(MirrorLink {
+            primary: primary_id,
+            inverted,
+            divergence_callback,
+            diverged: AtomicBool::new(false),
+        });
+        self.update_mirror(secondary_id);
+
+        Ok(())
+    }
+
+    /// Stops mirroring a channel, which continues with its own waveform.
+    ///
+    /// # Parameters
+    /// - `secondary_id`: The identifier of the mirrored channel
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered.
+    pub fn unmirror(&mut self, secondary_id: ChannelId) -> Result<(), SpwmError> {
+        let slot = self
+            .channel_slots
+            .get_mut(secondary_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let channel = slot.channel.as_ref().ok_or(SpwmError::InvalidChannel)?;
+
+        if slot.mirror.take().is_some() {
+            channel.counter_reset();
+            channel.reschedule(SCHEDULE_CHANGED);
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether a channel mirrors another channel.
+    pub(crate) fn is_mirrored(&self, id: ChannelId) -> bool {
+        self.channel_slots
+            .get(id)
+            .is_some_and(|slot| slot.mirror.is_some())
+    }
+
+    /// Copies the primary output to a mirrored channel and checks that the outputs agree.
+    pub(crate) fn update_mirror(&self, id: ChannelId) {
+        let Some(slot) = self.channel_slots.get(id) else {
+            return;
+        };
+        let (Some(secondary), Some(link)) = (&slot.channel, &slot.mirror) else {
+            return;
+        };
+        let Some(primary) = self.get_channel(link.primary) else {
+            return;
+        };
+        let primary_on = primary.output_on.load(Ordering::Relaxed);
+
+        if secondary.is_enabled() {
+            secondary.set_output(if primary_on ^ link.inverted {
+                &SpwmState::On
+            } else {
+                &SpwmState::Off
+            });
+        }
+
+        let agree = !primary.is_enabled()
+            || secondary.output_on.load(Ordering::Relaxed) == (primary_on ^ link.inverted);
+
+        if agree {
+            link.diverged.store(false, Ordering::Relaxed);
+        } else if !link.diverged.swap(true, Ordering::Relaxed) {
+            self.flag_error(IRQ_ERROR_DIVERGENCE);
+
+            if let Some(callback) = link.divergence_callback {
+                callback(link.primary, id);
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Noise levels for signal generator channels.
+//!
+//! White noise comes from a xorshift generator, a shift-register (LFSR) generator with a
+//! 2^32 - 1 period that needs three shifts and three XORs per draw. Pink noise sums random rows
+//! refreshed at octave-spaced rates (Voss-McCartney), so its power falls by 3 dB per octave
+//! over the range of the rows. Dither injected into a control loop or a noise source for audio
+//! therefore costs no multiplication besides the scaling of the level.
+
+use crate::SpwmChannel;
+use crate::signal::{SIGNAL_SCALE, SignalWaveform};
+use core::sync::atomic::Ordering;
+
+/// Seed of the generator; any nonzero value works.
+const NOISE_SEED: u32 = 0x2545_F491;
+
+/// Number of 8-bit pink noise rows packed into `noise_rows`.
+const PINK_ROWS: u32 = 4;
+
+/// Maximum sum of the `PINK_ROWS` pink noise rows and the white noise byte.
+const PINK_SUM_MAX: i32 = 255 * 5;
+
+/// Advances the xorshift generator.
+fn xorshift(state: u32) -> u32 {
+    let state = state ^ (state << 13);
+    let state = state ^ (state >> 17);
+
+    state ^ (state << 5)
+}
+
+impl SpwmChannel {
+    /// Draws the noise level of the next signal period of a noise waveform.
+    pub(crate) fn draw_noise(&self, waveform: SignalWaveform) {
+        let random = match self.noise_state.load(Ordering::Relaxed) {
+            0 => xorshift(NOISE_SEED),
+            state => xorshift(state),
+        };
+
+        self.noise_state.store(random, Ordering::Relaxed);
+
+        let sample = match waveform {
+            SignalWaveform::WhiteNoise => {
+                // upper 16 bits of the generator, centered and scaled
+                let level = i32::try_from(random >> 16).unwrap_or(0);
+
+                level.saturating_sub(1 << 15).saturating_mul(SIGNAL_SCALE) >> 15
+            }
+            SignalWaveform::PinkNoise => self.pink_sample(random),
+            _ => return,
+        };
+
+        self.noise_sample.store(sample, Ordering::Relaxed);
+    }
+
+    /// Refreshes the pink noise row selected by the draw count and returns the scaled sum of
+    /// the rows and a white noise byte.
+    fn pink_sample(&self, random: u32) -> i32 {
+        let count = self.noise_count.load(Ordering::Relaxed).wrapping_add(1);
+        let mut rows = self.noise_rows.load(Ordering::Relaxed);
+        let row = count.trailing_zeros();
+
+        self.noise_count.store(count, Ordering::Relaxed);
+
+        if row < PINK_ROWS {
+            let shift = row.saturating_mul(8);
+
+            rows = (rows & !(0xFF << shift)) | ((random & 0xFF) << shift);
+            self.noise_rows.store(rows, Ordering::Relaxed);
+        }
+
+        let sum = rows
+            .to_le_bytes()
+            .into_iter()
+            .chain(core::iter::once((random >> 24).to_le_bytes()[0]))
+            .map(i32::from)
+            .fold(0_i32, i32::saturating_add);
+
+        sum.saturating_mul(2)
+            .saturating_sub(PINK_SUM_MAX)
+            .saturating_mul(SIGNAL_SCALE)
+            .checked_div(PINK_SUM_MAX)
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,76 @@
+//! Peak-and-hold drive profile for solenoids and injectors.
+//!
+//! A channel with a peak-and-hold profile runs at a high peak duty cycle for a pull-in time
+//! after `enable()` or `retrigger()`, and then drops to its configured duty cycle, which acts as
+//! the hold duty cycle. The drop happens at a period boundary inside the tick engine, so the
+//! pull-in time does not depend on application timing.
+
+use crate::SpwmChannel;
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Returns the on-time of the starting period while the pull-in time is not over, and
+    /// counts the period against the pull-in time.
+    pub(crate) fn peak_on_ticks(&self) -> Option<u32> {
+        let remaining_ticks = self.peak_remaining_ticks.load(Ordering::Relaxed);
+
+        self.in_peak.store(remaining_ticks != 0, Ordering::Relaxed);
+
+        if remaining_ticks == 0 {
+            return None;
+        }
+
+        self.peak_remaining_ticks.store(
+            remaining_ticks.saturating_sub(self.effective_period_ticks()),
+            Ordering::Relaxed,
+        );
+
+        Some(self.duty_to_ticks(self.peak_duty.load(Ordering::Relaxed)))
+    }
+
+    /// Restarts the pull-in time of an enabled channel with a peak-and-hold profile.
+    ///
+    /// The peak duty cycle applies from the next period boundary for the configured pull-in
+    /// time, e.g. to re-energize a solenoid that dropped out. Has no effect on a disabled
+    /// channel or a channel without a profile.
+    pub fn retrigger(&self) {
+        if self.is_enabled() {
+            self.peak_remaining_ticks.store(
+                self.pull_in_ticks.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    /// Returns whether the channel runs at its peak duty cycle.
+    pub fn is_in_peak(&self) -> bool {
+        self.in_peak.load(Ordering::Relaxed)
+    }
+
+    /// Starts the pull-in time when the channel is enabled.
+    pub(crate) fn start_peak(&self) {
+        let pull_in_ticks = self.pull_in_ticks.load(Ordering::Relaxed);
+
+        if pull_in_ticks == 0 {
+            return;
+        }
+
+        self.peak_remaining_ticks
+            .store(pull_in_ticks, Ordering::Relaxed);
+
+        if !self.waiting.load(Ordering::Relaxed)
+            && let Some(on_ticks) = self.peak_on_ticks()
+        {
+            self.set_on_ticks(self.derated(on_ticks));
+        }
+    }
+
+    /// Ends the pull-in time when the channel is disabled and restores the hold on-time.
+    pub(crate) fn stop_peak(&self) {
+        self.peak_remaining_ticks.store(0, Ordering::Relaxed);
+
+        if self.in_peak.swap(false, Ordering::Relaxed) {
+            self.set_on_ticks(self.update_on_ticks.load(Ordering::Relaxed));
+        }
+    }
+}
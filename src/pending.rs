@@ -0,0 +1,120 @@
+//! Inspection and cancellation of updates not applied yet.
+//!
+//! Duty cycle, on-time and on-time offset updates of a running channel wait for the next period
+//! boundary (or for the next enable, see `UpdatePolicy::OnEnable`) and frequency ramps move
+//! period by period. A control routine can check what is still in flight and revoke it, which
+//! restores the values in effect.
+
+use crate::SpwmChannel;
+use core::sync::atomic::Ordering;
+
+/// Updates of a channel that were requested but not applied yet.
+///
+/// # Fields
+/// - `duty_cycle`: Duty cycle percentage whose on-time is not latched yet
+/// - `on_ticks`: On-time ticks latched at the next period boundary or at the next enable
+/// - `freq_hz`: Target frequency of a ramp in progress
+/// - `pulse_offset`: On-time offset ticks applied from the next period on
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PendingUpdate {
+    pub duty_cycle: Option<u8>,
+    pub on_ticks: Option<u32>,
+    pub freq_hz: Option<u32>,
+    pub pulse_offset: Option<u32>,
+}
+
+impl PendingUpdate {
+    /// Returns whether no update is pending.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == PendingUpdate::default()
+    }
+}
+
+impl SpwmChannel {
+    /// Returns the updates of the channel that were requested but not applied yet.
+    pub fn pending_update(&self) -> PendingUpdate {
+        let on_ticks = if self.update_pending.load(Ordering::SeqCst) {
+            Some(self.update_on_ticks.load(Ordering::SeqCst))
+        } else if self.staged_pending.load(Ordering::SeqCst) {
+            Some(self.staged_on_ticks.load(Ordering::SeqCst))
+        } else {
+            None
+        };
+        let pulse_offset = self.update_pulse_offset.load(Ordering::SeqCst);
+
+        PendingUpdate {
+            duty_cycle: on_ticks
+                .filter(|_| self.is_duty_driven())
+                .map(|_| self.duty_cycle()),
+            on_ticks,
+            freq_hz: self.frequency_target(),
+            pulse_offset: Some(pulse_offset)
+                .filter(|&offset| offset != self.pulse_offset.load(Ordering::SeqCst)),
+        }
+    }
+
+    /// Cancels the updates that were requested but not applied yet.
+    ///
+    /// The channel keeps the duty cycle, on-time and offset of the period in progress (or the
+    /// values a disabled channel starts with), and a frequency ramp stops at the frequency
+    /// reached so far.
+    ///
+    /// # Returns
+    /// The cancelled updates.
+    pub fn cancel_pending(&self) -> PendingUpdate {
+        let pending = self.pending_update();
+
+        if self.update_pending.swap(false, Ordering::SeqCst) {
+            self.update_on_ticks.store(
+                self.latched_on_ticks.load(Ordering::SeqCst),
+                Ordering::SeqCst,
+            );
+        }
+
+        self.staged_pending.store(false, Ordering::SeqCst);
+
+        if pending.duty_cycle.is_some() {
+            self.duty_cycle.store(
+                self.latched_duty_cycle.load(Ordering::SeqCst),
+                Ordering::SeqCst,
+            );
+        }
+
+        self.cancel_frequency_ramp();
+        self.update_pulse_offset
+            .store(self.pulse_offset.load(Ordering::SeqCst), Ordering::SeqCst);
+
+        pending
+    }
+
+    /// Sets the configured duty cycle, remembering the duty cycle in effect if no update was
+    /// pending so `cancel_pending()` can restore it.
+    pub(crate) fn swap_duty_cycle(&self, duty_cycle: u8) -> u8 {
+        let previous = self.duty_cycle.swap(duty_cycle, Ordering::SeqCst);
+
+        if !self.update_pending.load(Ordering::SeqCst)
+            && !self.staged_pending.load(Ordering::SeqCst)
+        {
+            self.latched_duty_cycle.store(previous, Ordering::SeqCst);
+        }
+
+        previous
+    }
+
+    /// Remembers the on-time in effect before an update becomes pending at the next period
+    /// boundary.
+    pub(crate) fn note_latched_on_ticks(&self) {
+        if !self.update_pending.load(Ordering::SeqCst) {
+            self.latched_on_ticks.store(
+                self.update_on_ticks.load(Ordering::SeqCst),
+                Ordering::SeqCst,
+            );
+        }
+    }
+
+    /// Returns whether the on-time follows the configured duty cycle.
+    fn is_duty_driven(&self) -> bool {
+        !self.is_trigger_output() && self.signal_waveform().is_none()
+    }
+}
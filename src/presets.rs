@@ -0,0 +1,94 @@
+//! Channel builder presets for common applications.
+//!
+//! Each preset fills in the frequency, initial duty cycle and constraints typical for its
+//! application and a no-op period callback, so only the output callback driving the pin is
+//! left before `build()`. Every setting can still be overridden with the builder methods.
+//!
+//! # Example
+//! ```
+//! # use spwm::{SpwmChannelBuilder, SpwmState};
+//! # fn main() -> Result<(), spwm::SpwmError> {
+//! let led = SpwmChannelBuilder::led(1_000_000, 1_000)
+//!     .on_off_callback(|_: &SpwmState| {})
+//!     .build()?;
+//!
+//! assert!(led.is_flicker_safe());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::SpwmChannelBuilder;
+use crate::channel::SpwmChannelFinalizedBuildState;
+
+/// Servo frame rate in mHz (50 Hz).
+const SERVO_FRAME_RATE_MHZ: u32 = 50_000;
+
+/// Servo pulse width in µs of the center position.
+const SERVO_CENTER_US: u32 = 1_500;
+
+/// Piezo buzzer frequency in Hz, near the resonance of common transducers.
+const BUZZER_FREQ_HZ: u32 = 4_000;
+
+impl SpwmChannelBuilder<SpwmChannelFinalizedBuildState> {
+    /// Creates a builder for an LED dimming channel.
+    ///
+    /// The channel starts dark and rejects frequencies in the known flicker ranges (see
+    /// `assert_flicker_safe()`).
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    /// - `freq_hz`: PWM frequency in Hz
+    #[must_use]
+    pub fn led(hardware_freq_hz: u32, freq_hz: u32) -> Self {
+        SpwmChannelBuilder::new(hardware_freq_hz)
+            .flicker_safe()
+            .period_callback(|| {})
+            .freq_hz(freq_hz)
+            .duty_cycle(0)
+    }
+
+    /// Creates a builder for a hobby servo channel.
+    ///
+    /// The channel produces 50 Hz frames with a 1.5 ms pulse (center position); the position
+    /// is changed with `Spwm::set_pulse_width()`, typically between 1000 and 2000 µs.
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    #[must_use]
+    pub fn servo(hardware_freq_hz: u32) -> Self {
+        SpwmChannelBuilder::new(hardware_freq_hz)
+            .period_callback(|| {})
+            .trigger_output(SERVO_FRAME_RATE_MHZ, SERVO_CENTER_US)
+    }
+
+    /// Creates a builder for a time-proportioning heater channel.
+    ///
+    /// The channel starts off and switches once per period of whole seconds, which suits
+    /// relays and SSRs driving resistive loads.
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    /// - `period_s`: Period in seconds
+    #[must_use]
+    pub fn heater(hardware_freq_hz: u32, period_s: u32) -> Self {
+        SpwmChannelBuilder::new(hardware_freq_hz)
+            .period_callback(|| {})
+            .period_s(period_s)
+            .freq_hz(1)
+            .duty_cycle(0)
+    }
+
+    /// Creates a builder for a piezo buzzer channel.
+    ///
+    /// The channel drives a 4 kHz square wave, which sounds as soon as it is enabled.
+    ///
+    /// # Parameters
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    #[must_use]
+    pub fn buzzer(hardware_freq_hz: u32) -> Self {
+        SpwmChannelBuilder::new(hardware_freq_hz)
+            .period_callback(|| {})
+            .freq_hz(BUZZER_FREQ_HZ)
+            .duty_cycle(50)
+    }
+}
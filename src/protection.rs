@@ -0,0 +1,186 @@
+//! Output protection profiles for SPWM channels.
+//!
+//! A protection profile bounds the on-time a channel may produce, as required for heaters and
+//! actuators by safety standards (e.g. IEC 60730 class B style constraints). The limits are
+//! enforced by the tick engine whenever a new on-time is applied, so application code cannot
+//! bypass them by accident, and every intervention is reported through a violation callback.
+
+use crate::{IRQ_ERROR_PROTECTION, MAX_DUTY_CYCLE, SpwmChannel};
+use core::cell::OnceCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Limits enforced on the on-time of a channel.
+///
+/// The default profile does not limit anything.
+///
+/// # Fields
+/// - `max_continuous_on_periods`: Number of consecutive periods the output may stay on for the
+///   whole period before a period is forced off (0 for no limit)
+/// - `max_duty`: Maximum duty cycle percentage (0-100)
+/// - `max_step`: Maximum duty cycle change in percent per period (1-100), larger updates are
+///   spread over several periods
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProtectionProfile {
+    pub max_continuous_on_periods: u32,
+    pub max_duty: u8,
+    pub max_step: u8,
+}
+
+impl Default for ProtectionProfile {
+    fn default() -> Self {
+        Self {
+            max_continuous_on_periods: 0,
+            max_duty: 100,
+            max_step: 100,
+        }
+    }
+}
+
+/// Protection limit that was hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtectionViolation {
+    /// The output stayed on for `max_continuous_on_periods` periods; the next period is forced off
+    ContinuousOn,
+    /// A duty cycle update or the on-time of a period was limited to `max_duty`
+    MaxDuty,
+    /// A duty cycle update was limited to `max_step` for this period
+    MaxStep,
+}
+
+/// Callback invoked when a protection limit alters the on-time of a channel.
+///
+/// # Parameters
+/// - `violation`: The limit that was hit
+pub type ProtectionViolationCallback = fn(ProtectionViolation);
+
+/// Protection profile and state of a channel.
+///
+/// # Fields
+/// - `profile`: The enforced limits (unset for an unprotected channel)
+/// - `violation_callback`: Callback invoked when a limit is hit
+/// - `continuous_on_periods`: Consecutive periods the output stayed on for the whole period
+#[derive(Default, Debug)]
+pub(crate) struct ProtectionState {
+    pub(crate) profile: OnceCell<ProtectionProfile>,
+    pub(crate) violation_callback: OnceCell<ProtectionViolationCallback>,
+    pub(crate) continuous_on_periods: AtomicU32,
+}
+
+impl SpwmChannel {
+    /// Returns the highest duty cycle the protection profile allows (100% without a profile).
+    pub(crate) fn max_duty_cycle(&self) -> u8 {
+        self.protection
+            .profile
+            .get()
+            .map_or(MAX_DUTY_CYCLE, |profile| {
+                profile.max_duty.min(MAX_DUTY_CYCLE)
+            })
+    }
+
+    /// Applies the `max_duty` limit to an on-time.
+    ///
+    /// # Parameters
+    /// - `on_ticks`: The requested on-time
+    ///
+    /// # Returns
+    /// The on-time allowed by the protection profile.
+    pub(crate) fn limit_duty(&self, on_ticks: u32) -> u32 {
+        let Some(profile) = self.protection.profile.get() else {
+            return on_ticks;
+        };
+        let max_ticks = self.duty_to_ticks(profile.max_duty);
+
+        if on_ticks <= max_ticks {
+            return on_ticks;
+        }
+
+        self.report_violation(ProtectionViolation::MaxDuty);
+
+        max_ticks
+    }
+
+    /// Applies the `max_step` limit to a duty cycle update.
+    ///
+    /// A limited update stays pending, so it continues towards its target in the following
+    /// periods.
+    ///
+    /// # Parameters
+    /// - `on_ticks`: The on-time of the current period
+    /// - `update_ticks`: The on-time of the update
+    ///
+    /// # Returns
+    /// The on-time allowed by the protection profile.
+    pub(crate) fn limit_step(&self, on_ticks: u32, update_ticks: u32) -> u32 {
+        let Some(profile) = self.protection.profile.get() else {
+            return update_ticks;
+        };
+        let max_step_ticks = self.duty_to_ticks(profile.max_step);
+
+        if update_ticks.abs_diff(on_ticks) <= max_step_ticks {
+            return update_ticks;
+        }
+
+        self.update_pending.store(true, Ordering::SeqCst);
+        self.report_violation(ProtectionViolation::MaxStep);
+
+        if update_ticks > on_ticks {
+            on_ticks.saturating_add(max_step_ticks)
+        } else {
+            on_ticks.saturating_sub(max_step_ticks)
+        }
+    }
+
+    /// Counts the periods the output stayed on continuously and forces the starting period off
+    /// once the `max_continuous_on_periods` limit is reached.
+    ///
+    /// # Parameters
+    /// - `previous_on_ticks`: The on-time of the period that just ended
+    /// - `on_ticks`: The on-time of the starting period
+    ///
+    /// # Returns
+    /// The on-time allowed for the starting period.
+    pub(crate) fn limit_continuous_on(&self, previous_on_ticks: u32, on_ticks: u32) -> u32 {
+        let Some(limit) = self
+            .protection
+            .profile
+            .get()
+            .map(|profile| profile.max_continuous_on_periods)
+            .filter(|&limit| limit != 0)
+        else {
+            return on_ticks;
+        };
+        let period_ticks = self.period_ticks.load(Ordering::Relaxed);
+        let continuous_on_periods = if previous_on_ticks >= period_ticks {
+            self.protection
+                .continuous_on_periods
+                .load(Ordering::Relaxed)
+                .saturating_add(1)
+        } else {
+            0
+        };
+
+        if on_ticks >= period_ticks && continuous_on_periods >= limit {
+            self.protection
+                .continuous_on_periods
+                .store(0, Ordering::Relaxed);
+            self.report_violation(ProtectionViolation::ContinuousOn);
+
+            return 0;
+        }
+
+        self.protection
+            .continuous_on_periods
+            .store(continuous_on_periods, Ordering::Relaxed);
+
+        on_ticks
+    }
+
+    /// Records the violation for `Spwm::take_errors()` and invokes the violation callback.
+    fn report_violation(&self, violation: ProtectionViolation) {
+        self.flag_error(IRQ_ERROR_PROTECTION);
+
+        if let Some(callback) = self.protection.violation_callback.get() {
+            callback(violation);
+        }
+    }
+}
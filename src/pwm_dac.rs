@@ -0,0 +1,209 @@
+//! PWM DAC through an RC low-pass filter.
+//!
+//! An RC-filtered PWM output settles to `duty * vref` with a peak-to-peak ripple of about
+//! `vref * duty * (1 - duty) / (f * RC)`, at most `vref / (4 * f * RC)` at 50% duty cycle. The
+//! helper picks the lowest PWM frequency keeping the worst-case ripple within a target, which
+//! leaves the most hardware timer ticks per period and therefore the finest voltage steps, and
+//! sets the on-time in ticks rather than in 1% duty cycle steps.
+
+use crate::channel::input_frequency_validate;
+use crate::{ChannelId, Spwm, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Microseconds per second.
+const MICROS_PER_SEC: u64 = 1_000_000;
+
+/// `ln(2)` scaled by 2^16.
+const LN_2_Q16: u64 = 45_426;
+
+/// Returns `ln(value)` scaled by 2^16 for `value >= 1`, with the fractional part of the binary
+/// logarithm interpolated linearly (less than 0.09 too low).
+fn ln_q16(value: u64) -> u64 {
+    let Some(exponent) = value.checked_ilog2() else {
+        return 0;
+    };
+    let base = 1_u64 << exponent;
+    let fraction = value
+        .saturating_sub(base)
+        .checked_shl(16)
+        .and_then(|scaled| scaled.checked_div(base))
+        .unwrap_or(0);
+
+    (u64::from(exponent) << 16)
+        .saturating_add(fraction)
+        .saturating_mul(LN_2_Q16)
+        >> 16
+}
+
+/// PWM DAC driving one channel into an RC low-pass filter.
+///
+/// # Example
+///
+/// ```
+/// # use spwm::{PwmDac, Spwm};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<1>::new(10_000_000);
+/// let channel = spwm.create_channel()
+///     .freq_hz(1_000)
+///     .duty_cycle(0)
+///     .on_off_callback(|_| {})
+///     .period_callback(|| {})
+///     .build()?;
+/// let id = spwm.register_channel(channel)?;
+///
+/// // 10 kΩ * 10 µF filter, at most 5 mV ripple from a 3.3 V supply
+/// let mut dac = PwmDac::new(&spwm, id, 100_000, 5, 3_300)?;
+///
+/// assert_eq!(dac.frequency_hz(), 1_650);
+///
+/// let settling_periods = dac.set_voltage_mv(&spwm, 1_200, 3_300)?;
+///
+/// assert!(settling_periods > 0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PwmDac {
+    channel: ChannelId,
+    rc_us: u32,
+    freq_hz: u32,
+    period_ticks: u32,
+    on_ticks: u32,
+}
+
+impl PwmDac {
+    /// Configures the channel frequency for a ripple target and sets the output to 0 V.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channel is registered with
+    /// - `channel`: The identifier of the channel driving the filter
+    /// - `rc_us`: Time constant of the RC filter in µs
+    /// - `max_ripple_mv`: Maximum peak-to-peak ripple in mV
+    /// - `vref_mv`: Output high level (supply) in mV
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidFrequency` if the time constant or the ripple is 0, or the ripple
+    ///   target needs a frequency higher than 1/100 of the hardware timer frequency
+    pub fn new<const N: usize, const A: usize>(
+        spwm: &Spwm<N, A>,
+        channel: ChannelId,
+        rc_us: u32,
+        max_ripple_mv: u32,
+        vref_mv: u32,
+    ) -> Result<Self, SpwmError> {
+        let id = channel;
+        let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+        // f >= vref / (4 * RC * ripple), rounded up
+        let divisor = u64::from(rc_us)
+            .saturating_mul(u64::from(max_ripple_mv))
+            .saturating_mul(4);
+        let freq_hz = u64::from(vref_mv)
+            .saturating_mul(MICROS_PER_SEC)
+            .checked_add(divisor.saturating_sub(1))
+            .and_then(|dividend| dividend.checked_div(divisor))
+            .ok_or(SpwmError::InvalidFrequency)?
+            .max(1);
+        let freq_hz = u32::try_from(freq_hz).map_err(|_| SpwmError::InvalidFrequency)?;
+
+        input_frequency_validate(freq_hz, spwm.freq_hz)?;
+        channel.update_frequency(freq_hz, spwm.freq_hz)?;
+
+        let mut dac = Self {
+            channel: id,
+            rc_us,
+            freq_hz,
+            period_ticks: channel.period_ticks.load(Ordering::Relaxed),
+            on_ticks: 0,
+        };
+
+        dac.set_voltage_mv(spwm, 0, vref_mv)?;
+
+        Ok(dac)
+    }
+
+    /// Returns the PWM frequency chosen for the ripple target in Hz.
+    #[must_use]
+    pub fn frequency_hz(&self) -> u32 {
+        self.freq_hz
+    }
+
+    /// Returns the voltage step of one on-time tick in µV for an output high level of
+    /// `vref_mv`.
+    #[must_use]
+    pub fn resolution_uv(&self, vref_mv: u32) -> u32 {
+        vref_mv
+            .saturating_mul(1_000)
+            .checked_div(self.period_ticks)
+            .unwrap_or(0)
+    }
+
+    /// Sets the filtered output voltage from the next period on.
+    ///
+    /// The on-time is rounded to the nearest tick. Passing the measured supply as `vref_mv`
+    /// keeps the output accurate while the supply varies.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channel is registered with
+    /// - `target_mv`: Output voltage in mV
+    /// - `vref_mv`: Output high level (supply) in mV
+    ///
+    /// # Returns
+    /// The expected number of PWM periods until the filter output settles within half a
+    /// voltage step of the target.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel was removed from `spwm`
+    /// - `SpwmError::InvalidDutyCycle` if the target is above `vref_mv`
+    pub fn set_voltage_mv<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        target_mv: u32,
+        vref_mv: u32,
+    ) -> Result<u32, SpwmError> {
+        if target_mv > vref_mv {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        let channel = spwm
+            .get_channel(self.channel)
+            .ok_or(SpwmError::InvalidChannel)?;
+        let on_ticks = u64::from(target_mv)
+            .saturating_mul(u64::from(self.period_ticks))
+            .checked_add(u64::from(vref_mv / 2))
+            .and_then(|scaled| scaled.checked_div(u64::from(vref_mv)))
+            .and_then(|ticks| u32::try_from(ticks).ok())
+            .unwrap_or(0);
+        let duty_cycle = u64::from(on_ticks)
+            .saturating_mul(100)
+            .checked_div(u64::from(self.period_ticks))
+            .and_then(|duty| u8::try_from(duty).ok())
+            .unwrap_or(0);
+
+        // the percentage keeps `duty_cycle()` meaningful, the tick on-time sets the voltage
+        channel.update_duty_cycle(duty_cycle)?;
+        channel.update_on_ticks(on_ticks);
+
+        let settling_periods = self.settling_periods(self.on_ticks.abs_diff(on_ticks));
+
+        self.on_ticks = on_ticks;
+
+        Ok(settling_periods)
+    }
+
+    /// Returns the PWM periods an RC filter needs to settle a step of `step_ticks` on-time
+    /// ticks within half a tick: `RC * ln(2 * step) * f`.
+    fn settling_periods(&self, step_ticks: u32) -> u32 {
+        if step_ticks == 0 {
+            return 0;
+        }
+
+        let ln = ln_q16(u64::from(step_ticks).saturating_mul(2));
+        let periods = u64::from(self.rc_us)
+            .saturating_mul(u64::from(self.freq_hz))
+            .saturating_mul(ln)
+            .checked_div(MICROS_PER_SEC << 16)
+            .unwrap_or(0);
+
+        u32::try_from(periods.saturating_add(1)).unwrap_or(u32::MAX)
+    }
+}
@@ -0,0 +1,60 @@
+//! Duty cycle quantization report.
+//!
+//! The on-time is a whole number of hardware timer ticks, so short periods cannot deliver every
+//! duty cycle percentage: a 2000 tick period moves in exact 1% steps of 20 ticks, while a 150
+//! tick period alternates between steps of one and two ticks (0.67% and 1.33%). The report lets
+//! user interfaces display the duty cycle actually produced instead of the requested one.
+
+use crate::channel::MAX_DUTY_CYCLE;
+use crate::{SpwmChannel, SpwmError};
+
+/// Full scale of a produced duty cycle in hundredths of a percent.
+const LEVEL_SCALE: u64 = 10_000;
+
+impl SpwmChannel {
+    /// Returns the duty cycle the channel actually produces for a requested duty cycle.
+    ///
+    /// The value follows the current period, the calibration table and the derating set with
+    /// `Spwm::derate_all()`, so it changes with the channel frequency.
+    ///
+    /// # Parameters
+    /// - `duty_cycle`: Requested duty cycle percentage
+    ///
+    /// # Returns
+    /// The produced duty cycle in hundredths of a percent (0..=10000).
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidDutyCycle` if the duty cycle is greater than 100.
+    pub fn quantize(&self, duty_cycle: u8) -> Result<u32, SpwmError> {
+        if duty_cycle > MAX_DUTY_CYCLE {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        let on_ticks = self.derated(self.value_to_ticks(duty_cycle));
+
+        Ok(u64::from(on_ticks)
+            .saturating_mul(LEVEL_SCALE)
+            .checked_div(u64::from(self.effective_period_ticks()))
+            .and_then(|level| u32::try_from(level).ok())
+            .unwrap_or(0))
+    }
+
+    /// Returns the duty cycle step the channel can actually deliver at its current period.
+    ///
+    /// # Returns
+    /// The largest change of the produced duty cycle between two consecutive duty cycle
+    /// percentages, in hundredths of a percent (100 for exact 1% steps).
+    pub fn effective_resolution(&self) -> u32 {
+        let mut previous = 0;
+        let mut resolution = 0;
+
+        for duty_cycle in 1..=MAX_DUTY_CYCLE {
+            let level = self.quantize(duty_cycle).unwrap_or(previous);
+
+            resolution = resolution.max(level.abs_diff(previous));
+            previous = level;
+        }
+
+        resolution
+    }
+}
@@ -0,0 +1,103 @@
+//! Acceleration-limited frequency changes.
+//!
+//! A frequency target moves the channel frequency towards the target by a limited step at every
+//! period boundary instead of jumping, which keeps fans, pumps and resonant loads from
+//! mechanical or acoustic stress caused by abrupt frequency changes.
+
+use crate::channel::input_frequency_validate;
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Ramps the PWM frequency towards `freq_hz`, changing it by at most
+    /// `max_delta_hz_per_period` at every period boundary.
+    ///
+    /// The ramp starts from the current frequency (or the frequency reached by a ramp in
+    /// progress) and the duty cycle is kept at every step. A disabled channel keeps the ramp
+    /// until it runs again. `update_frequency()` cancels the ramp.
+    ///
+    /// # Parameters
+    /// - `freq_hz`: Target PWM frequency in Hz
+    /// - `max_delta_hz_per_period`: Maximum frequency change per period in Hz (0 to apply the
+    ///   target at the next period boundary)
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the frequency is 0, too high relative to the
+    /// hardware timer frequency (must be at least 100x lower) or in a flicker range of a
+    /// flicker-safe channel.
+    pub fn set_frequency_target(
+        &self,
+        freq_hz: u32,
+        max_delta_hz_per_period: u32,
+        hardware_freq_hz: u32,
+    ) -> Result<(), SpwmError> {
+        input_frequency_validate(freq_hz, hardware_freq_hz)?;
+        self.flicker_validate(freq_hz)?;
+
+        if self.ramp_target_hz.load(Ordering::SeqCst) == 0 {
+            let current_hz = self
+                .hardware_reciprocal
+                .divide(hardware_freq_hz, self.effective_period_ticks())
+                .unwrap_or(freq_hz);
+
+            self.ramp_freq_hz.store(current_hz, Ordering::SeqCst);
+        }
+
+        self.ramp_hardware_freq_hz
+            .store(hardware_freq_hz, Ordering::SeqCst);
+        self.ramp_step_hz
+            .store(max_delta_hz_per_period, Ordering::SeqCst);
+        self.ramp_target_hz.store(freq_hz, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Returns the target frequency of the ramp in progress, or `None` if the frequency does
+    /// not ramp.
+    pub fn frequency_target(&self) -> Option<u32> {
+        Some(self.ramp_target_hz.load(Ordering::SeqCst)).filter(|&target_hz| target_hz != 0)
+    }
+
+    /// Cancels a frequency ramp in progress, keeping the frequency reached so far.
+    pub fn cancel_frequency_ramp(&self) {
+        self.ramp_target_hz.store(0, Ordering::SeqCst);
+    }
+
+    /// Moves the frequency one step towards the ramp target at a period boundary.
+    pub(crate) fn step_frequency_ramp(&self) {
+        let target_hz = self.ramp_target_hz.load(Ordering::SeqCst);
+
+        if target_hz == 0 {
+            return;
+        }
+
+        let current_hz = self.ramp_freq_hz.load(Ordering::Relaxed);
+        let step_hz = match self.ramp_step_hz.load(Ordering::Relaxed) {
+            0 => u32::MAX,
+            step_hz => step_hz,
+        };
+        let next_hz = if target_hz > current_hz {
+            current_hz.saturating_add(step_hz).min(target_hz)
+        } else {
+            current_hz.saturating_sub(step_hz).max(target_hz)
+        };
+        let period_ticks = self
+            .hardware_reciprocal
+            .divide(self.ramp_hardware_freq_hz.load(Ordering::Relaxed), next_hz)
+            .unwrap_or(0);
+
+        self.ramp_freq_hz.store(next_hz, Ordering::Relaxed);
+
+        if next_hz == target_hz {
+            let _ = self.ramp_target_hz.compare_exchange(
+                target_hz,
+                0,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            );
+        }
+
+        self.set_period_ticks_keep_duty(period_ticks);
+    }
+}
@@ -0,0 +1,115 @@
+//! Division-free conversions between the hardware timer frequency and period lengths.
+//!
+//! Cores without a hardware divider (e.g. Cortex-M0) divide in software, which is slow enough
+//! to matter for frequency ramps stepped from `irq_handler()`. A channel therefore caches a
+//! fixed-point reciprocal of the hardware timer frequency: multiplied with a frequency it gives
+//! the reciprocal of the period length, which a few Newton steps invert with 32x32-bit
+//! multiplications. The estimate is then corrected to the exact quotient, so the results match
+//! a plain division.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Seed of the Newton iteration: `48/17 * 2^31`, the offset of the best linear approximation of
+/// `2^63 / d` on `[2^31, 2^32)`.
+const SEED_OFFSET: u64 = 6_063_483_241;
+
+/// Seed of the Newton iteration: `16/17 * 2^32`, the slope of the linear approximation.
+const SEED_SLOPE: u64 = 4_042_322_160;
+
+/// Newton steps refining the seed (the error squares from 1/17 to below `2^-32`).
+const NEWTON_STEPS: usize = 3;
+
+/// Estimates that are off by more than this are recomputed with a division.
+const MAX_CORRECTION: u32 = 2;
+
+/// Fixed-point reciprocal of a hardware timer frequency.
+///
+/// The frequency is normalized to `[2^31, 2^32)` by shifting out its leading zeros; the
+/// reciprocal holds `2^63` divided by the normalized frequency.
+#[derive(Debug, Default)]
+pub(crate) struct HardwareReciprocal {
+    /// Hardware timer frequency the reciprocal belongs to (0 if not computed yet)
+    hardware_freq_hz: AtomicU32,
+    /// `2^63` divided by the normalized hardware timer frequency
+    reciprocal: AtomicU32,
+}
+
+impl HardwareReciprocal {
+    /// Returns `hardware_freq_hz / divisor` rounded down, e.g. the period length in ticks of a
+    /// frequency, or `None` if `divisor` is 0.
+    ///
+    /// Only a change of the hardware timer frequency costs a division, to update the cached
+    /// reciprocal.
+    pub(crate) fn divide(&self, hardware_freq_hz: u32, divisor: u32) -> Option<u32> {
+        if divisor == 0 || hardware_freq_hz == 0 {
+            return hardware_freq_hz.checked_div(divisor);
+        }
+
+        let shift = hardware_freq_hz.leading_zeros();
+        let reciprocal = self.reciprocal(hardware_freq_hz, shift);
+        // `divisor / hardware_freq_hz` scaled by `2^(63 - shift)`
+        let ratio = u64::from(divisor).saturating_mul(u64::from(reciprocal));
+        let ratio_shift = ratio.leading_zeros();
+        let normalized = ratio
+            .checked_shl(ratio_shift)
+            .and_then(|ratio| ratio.checked_shr(32))
+            .and_then(|ratio| u32::try_from(ratio).ok())
+            .unwrap_or(u32::MAX);
+        let estimate = newton_reciprocal(normalized)
+            .checked_shr(shift.saturating_add(32).saturating_sub(ratio_shift))
+            .and_then(|estimate| u32::try_from(estimate).ok())
+            .unwrap_or(0);
+
+        correct(estimate, hardware_freq_hz, divisor)
+            .or_else(|| hardware_freq_hz.checked_div(divisor))
+    }
+
+    /// Returns the cached reciprocal of `hardware_freq_hz`, recomputing it if the frequency
+    /// changed.
+    fn reciprocal(&self, hardware_freq_hz: u32, shift: u32) -> u32 {
+        if self.hardware_freq_hz.load(Ordering::Acquire) == hardware_freq_hz {
+            return self.reciprocal.load(Ordering::Relaxed);
+        }
+
+        let reciprocal = hardware_freq_hz
+            .checked_shl(shift)
+            .and_then(|normalized| (1u64 << 63).checked_div(u64::from(normalized)))
+            .and_then(|reciprocal| u32::try_from(reciprocal).ok())
+            .unwrap_or(u32::MAX);
+
+        self.reciprocal.store(reciprocal, Ordering::Relaxed);
+        self.hardware_freq_hz
+            .store(hardware_freq_hz, Ordering::Release);
+
+        reciprocal
+    }
+}
+
+/// Returns approximately `2^63 / d` for `d` in `[2^31, 2^32)`, using multiplications only.
+fn newton_reciprocal(d: u32) -> u64 {
+    let d = u64::from(d);
+    let mut r = SEED_OFFSET.saturating_sub(d.saturating_mul(SEED_SLOPE) >> 32);
+
+    for _ in 0..NEWTON_STEPS {
+        // r' = r * (2 - d * r / 2^63), with `2^64 - d * r` holding the second factor in Q63
+        let error = d.saturating_mul(r).wrapping_neg() >> 32;
+        r = r.saturating_mul(error) >> 31;
+    }
+
+    r
+}
+
+/// Corrects an estimate of `dividend / divisor` that is off by at most `MAX_CORRECTION`.
+///
+/// # Returns
+/// The exact quotient, or `None` if it is not within reach of the estimate.
+fn correct(estimate: u32, dividend: u32, divisor: u32) -> Option<u32> {
+    let fits = |quotient: u32| {
+        u64::from(quotient).saturating_mul(u64::from(divisor)) <= u64::from(dividend)
+    };
+
+    (estimate.saturating_sub(MAX_CORRECTION)..=estimate.saturating_add(MAX_CORRECTION))
+        .rev()
+        .find(|&quotient| fits(quotient))
+        .filter(|&quotient| quotient.checked_add(1).is_none_or(|next| !fits(next)))
+}
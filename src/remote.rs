@@ -0,0 +1,217 @@
+//! Compact binary remote control protocol for bench and test fixtures.
+//!
+//! A host (e.g. a PC connected over a serial port) sends fixed-size command frames that are
+//! decoded, bounds-checked and mapped onto `Spwm` operations. Every command is answered with a
+//! fixed-size response frame carrying a status code and a value.
+//!
+//! Command frame (6 bytes): `[action, channel, value (u32, little-endian)]`
+//!
+//! Response frame (5 bytes): `[status, value (u32, little-endian)]`
+
+use crate::{Spwm, SpwmError};
+
+/// Size of a command frame in bytes.
+pub const REMOTE_COMMAND_LEN: usize = 6;
+
+/// Size of a response frame in bytes.
+pub const REMOTE_RESPONSE_LEN: usize = 5;
+
+/// Operation requested by a remote command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum RemoteAction {
+    /// Set the duty cycle percentage to `value`
+    SetDuty = 1,
+    /// Set the channel frequency to `value` Hz
+    SetFrequency = 2,
+    /// Enable the channel
+    Enable = 3,
+    /// Disable the channel
+    Disable = 4,
+    /// Read the configured duty cycle percentage
+    GetDuty = 5,
+    /// Read the channel frequency in Hz
+    GetFrequency = 6,
+    /// Read whether the channel is enabled (1) or disabled (0)
+    GetEnabled = 7,
+}
+
+/// Status code of a remote response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum RemoteStatus {
+    /// The command succeeded
+    Ok = 0,
+    /// The frame has the wrong length
+    MalformedFrame = 1,
+    /// The action code is unknown
+    UnknownAction = 2,
+    /// The value does not fit the action (e.g. duty cycle above 100)
+    ValueOutOfRange = 3,
+    /// The channel is out of range or not registered
+    InvalidChannel = 4,
+    /// The frequency is 0 or too high for the hardware timer
+    InvalidFrequency = 5,
+    /// The channel is already enabled
+    AlreadyEnabled = 6,
+    /// The channel is already disabled
+    AlreadyDisabled = 7,
+    /// The operation failed for another reason
+    Failed = 8,
+}
+
+impl From<SpwmError> for RemoteStatus {
+    fn from(error: SpwmError) -> Self {
+        match error {
+            SpwmError::InvalidChannel => Self::InvalidChannel,
+            SpwmError::InvalidFrequency => Self::InvalidFrequency,
+            SpwmError::InvalidDutyCycle => Self::ValueOutOfRange,
+            SpwmError::AlreadyEnabled => Self::AlreadyEnabled,
+            SpwmError::AlreadyDisabled => Self::AlreadyDisabled,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// A decoded remote command.
+///
+/// # Fields
+/// - `action`: Requested operation
+/// - `channel`: Target channel identifier
+/// - `value`: Operation argument (ignored by actions without one)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RemoteCommand {
+    pub action: RemoteAction,
+    pub channel: u8,
+    pub value: u32,
+}
+
+impl RemoteCommand {
+    /// Decodes a command frame.
+    ///
+    /// # Errors
+    /// - `RemoteStatus::MalformedFrame` if the frame is not `REMOTE_COMMAND_LEN` bytes long
+    /// - `RemoteStatus::UnknownAction` if the action code is unknown
+    pub fn decode(frame: &[u8]) -> Result<Self, RemoteStatus> {
+        let &[action, channel, v0, v1, v2, v3] = frame else {
+            return Err(RemoteStatus::MalformedFrame);
+        };
+        let action = match action {
+            1 => RemoteAction::SetDuty,
+            2 => RemoteAction::SetFrequency,
+            3 => RemoteAction::Enable,
+            4 => RemoteAction::Disable,
+            5 => RemoteAction::GetDuty,
+            6 => RemoteAction::GetFrequency,
+            7 => RemoteAction::GetEnabled,
+            _ => return Err(RemoteStatus::UnknownAction),
+        };
+
+        Ok(Self {
+            action,
+            channel,
+            value: u32::from_le_bytes([v0, v1, v2, v3]),
+        })
+    }
+
+    /// Encodes the command into a frame, e.g. on the host side.
+    #[must_use]
+    pub fn encode(&self) -> [u8; REMOTE_COMMAND_LEN] {
+        let [v0, v1, v2, v3] = self.value.to_le_bytes();
+
+        [self.action as u8, self.channel, v0, v1, v2, v3]
+    }
+}
+
+/// A remote response.
+///
+/// # Fields
+/// - `status`: Result of the command
+/// - `value`: Value read by `Get*` actions, 0 otherwise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RemoteResponse {
+    pub status: RemoteStatus,
+    pub value: u32,
+}
+
+impl RemoteResponse {
+    /// Encodes the response into a frame.
+    #[must_use]
+    pub fn encode(&self) -> [u8; REMOTE_RESPONSE_LEN] {
+        let [v0, v1, v2, v3] = self.value.to_le_bytes();
+
+        [self.status as u8, v0, v1, v2, v3]
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Decodes and executes a remote command frame.
+    ///
+    /// # Parameters
+    /// - `frame`: Command frame received from the host
+    ///
+    /// # Returns
+    /// The response to send back to the host.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::{RemoteAction, RemoteCommand, RemoteStatus, Spwm};
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<1>::new(100_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(10)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    /// spwm.register_channel(channel)?;
+    ///
+    /// let command = RemoteCommand { action: RemoteAction::SetDuty, channel: 0, value: 40 };
+    /// let response = spwm.remote_execute(&command.encode());
+    ///
+    /// assert_eq!(response.status, RemoteStatus::Ok);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remote_execute(&mut self, frame: &[u8]) -> RemoteResponse {
+        match RemoteCommand::decode(frame).and_then(|command| self.remote_dispatch(command)) {
+            Ok(value) => RemoteResponse {
+                status: RemoteStatus::Ok,
+                value,
+            },
+            Err(status) => RemoteResponse { status, value: 0 },
+        }
+    }
+
+    /// Maps a decoded command onto the channel operations.
+    fn remote_dispatch(&self, command: RemoteCommand) -> Result<u32, RemoteStatus> {
+        let channel = self
+            .get_channel(usize::from(command.channel))
+            .ok_or(RemoteStatus::InvalidChannel)?;
+
+        match command.action {
+            RemoteAction::SetDuty => {
+                let duty_cycle =
+                    u8::try_from(command.value).map_err(|_| RemoteStatus::ValueOutOfRange)?;
+
+                channel.update_duty_cycle(duty_cycle)?;
+            }
+            RemoteAction::SetFrequency => {
+                channel.update_frequency(command.value, self.freq_hz)?;
+                channel.sync_on_ticks();
+            }
+            RemoteAction::Enable => channel.enable()?,
+            RemoteAction::Disable => channel.disable()?,
+            RemoteAction::GetDuty => return Ok(u32::from(channel.duty_cycle())),
+            RemoteAction::GetFrequency => {
+                return Ok(self
+                    .freq_hz
+                    .checked_div(channel.validate().period_ticks)
+                    .unwrap_or(0));
+            }
+            RemoteAction::GetEnabled => return Ok(u32::from(channel.is_enabled())),
+        }
+
+        Ok(0)
+    }
+}
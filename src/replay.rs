@@ -0,0 +1,168 @@
+//! Deterministic replay of control operations.
+//!
+//! Control calls made through `Spwm::execute()` are recorded with the tick count at which they
+//! were made into a user-provided buffer. A log saved in the field (e.g. "LED glitched at
+//! 13:05") can then be fed to `Spwm::replay()` on the host with the same channel setup, which
+//! reproduces the outputs tick by tick.
+
+use crate::{ChannelId, Spwm, SpwmError};
+
+/// Control operation applied to a channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ControlOp {
+    /// Enable the channel
+    Enable,
+    /// Disable the channel
+    #[default]
+    Disable,
+    /// Update the duty cycle percentage, applied at the next period boundary
+    DutyCycle(u8),
+    /// Update the frequency in Hertz
+    Frequency(u32),
+}
+
+/// A control operation with the time it was applied.
+///
+/// # Fields
+/// - `tick`: Value of `Spwm::ticks()` when the operation was applied
+/// - `channel`: Identifier of the channel
+/// - `op`: The applied operation
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayRecord {
+    pub tick: u64,
+    pub channel: ChannelId,
+    pub op: ControlOp,
+}
+
+/// Log of control operations stored in a user-provided buffer.
+///
+/// Once the buffer is full further operations are still applied but not recorded, and counted
+/// by `dropped()`.
+#[derive(Debug)]
+pub struct ReplayLog<'a> {
+    buffer: &'a mut [ReplayRecord],
+    len: usize,
+    dropped: u32,
+}
+
+impl<'a> ReplayLog<'a> {
+    /// Creates an empty log backed by `buffer`.
+    ///
+    /// # Parameters
+    /// - `buffer`: Storage for the records
+    #[must_use]
+    pub fn new(buffer: &'a mut [ReplayRecord]) -> Self {
+        Self {
+            buffer,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Returns the recorded operations in the order they were applied.
+    #[must_use]
+    pub fn records(&self) -> &[ReplayRecord] {
+        self.buffer.get(..self.len).unwrap_or_default()
+    }
+
+    /// Returns the number of operations not recorded because the buffer was full.
+    #[must_use]
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Removes all records.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.dropped = 0;
+    }
+
+    /// Appends a record if the buffer has space left.
+    fn push(&mut self, record: ReplayRecord) {
+        if let Some(slot) = self.buffer.get_mut(self.len) {
+            *slot = record;
+            self.len = self.len.saturating_add(1);
+        } else {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Applies a control operation to a channel and records it into `log`.
+    ///
+    /// Only successful operations are recorded, so replaying the log applies the same
+    /// operations at the same ticks.
+    ///
+    /// # Parameters
+    /// - `log`: Log receiving the record
+    /// - `channel_id`: The identifier of the channel
+    /// - `op`: The operation to apply
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered, or the error of
+    /// the channel operation.
+    pub fn execute(
+        &self,
+        log: &mut ReplayLog<'_>,
+        channel_id: ChannelId,
+        op: ControlOp,
+    ) -> Result<(), SpwmError> {
+        self.apply_op(channel_id, op)?;
+        log.push(ReplayRecord {
+            tick: self.ticks(),
+            channel: channel_id,
+            op,
+        });
+
+        Ok(())
+    }
+
+    /// Runs `irq_handler()` until `ticks()` reaches `until_tick`, applying every record right
+    /// before the first tick processed at or after its timestamp.
+    ///
+    /// The manager has to be set up like the one that produced the log (channels, callbacks,
+    /// initial configuration) and start at the same tick count, usually 0. Records that are
+    /// due already are applied before the first tick. Ticks of a suspended manager are not
+    /// counted by `ticks()`, so logs of a firmware that suspends the manager are only
+    /// reproduced up to the suspension.
+    ///
+    /// # Parameters
+    /// - `records`: The recorded operations in the order they were applied
+    /// - `until_tick`: Tick count at which the replay stops
+    ///
+    /// # Errors
+    /// Returns the first error of a replayed operation, which means the setup or the log does
+    /// not match the recording.
+    pub fn replay(&self, records: &[ReplayRecord], until_tick: u64) -> Result<(), SpwmError> {
+        let mut pending = records.iter().peekable();
+
+        loop {
+            let tick = self.ticks();
+
+            while let Some(record) = pending.next_if(|record| record.tick <= tick) {
+                self.apply_op(record.channel, record.op)?;
+            }
+
+            if tick >= until_tick || self.is_suspended() {
+                return Ok(());
+            }
+
+            self.irq_handler();
+        }
+    }
+
+    /// Applies a control operation to a channel.
+    fn apply_op(&self, channel_id: ChannelId, op: ControlOp) -> Result<(), SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        match op {
+            ControlOp::Enable => channel.enable(),
+            ControlOp::Disable => channel.disable(),
+            ControlOp::DutyCycle(duty_cycle) => channel.update_duty_cycle(duty_cycle).map(|_| ()),
+            ControlOp::Frequency(freq_hz) => channel.update_frequency(freq_hz, self.freq_hz),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Per-manager reschedule generations.
+//!
+//! A channel changed outside of `irq_handler()` sets its schedule flags and bumps the
+//! reschedule generation of the manager it is registered with, so the manager detects pending
+//! flags with a single load instead of visiting every channel on each tick. Managers may be
+//! moved after their channels are registered, so a channel refers to the generation through an
+//! index into a static table instead of a reference.
+//!
+//! Index 0 is shared by unregistered channels and by managers created while all other entries
+//! are in use; such managers still work, but reschedules of unrelated channels cost them an
+//! event tick without changes.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Number of table entries: the shared index 0 and one per bit of `ALLOCATED`.
+const GENERATION_COUNT: usize = 33;
+
+/// Reschedule generation of every table index, wrapping on overflow.
+static GENERATIONS: [AtomicU32; GENERATION_COUNT] = [const { AtomicU32::new(0) }; GENERATION_COUNT];
+
+/// Bit `i` is set while index `i + 1` is owned by a manager.
+static ALLOCATED: AtomicU32 = AtomicU32::new(0);
+
+/// Bumps the reschedule generation of table index `index`.
+pub(crate) fn bump(index: u8) {
+    if let Some(generation) = GENERATIONS.get(usize::from(index)) {
+        generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Reschedule generation owned by a manager.
+#[derive(Debug)]
+pub(crate) struct RescheduleGeneration {
+    /// Index into the generation table, handed to the registered channels
+    index: u8,
+    /// Generation seen by the last `take_rescheduled()` call
+    seen: AtomicU32,
+}
+
+impl RescheduleGeneration {
+    /// Claims a free table index, falling back to the shared index 0 if none is left.
+    pub(crate) fn new() -> Self {
+        let claimed = ALLOCATED.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |allocated| {
+            1u32.checked_shl(allocated.trailing_ones())
+                .map(|bit| allocated | bit)
+        });
+        let index = claimed.map_or(0, |allocated| allocated.trailing_ones().saturating_add(1));
+
+        Self {
+            index: u8::try_from(index).unwrap_or(0),
+            seen: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the table index to store in the registered channels.
+    pub(crate) fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns whether a registered channel was rescheduled since the previous call.
+    pub(crate) fn take_rescheduled(&self) -> bool {
+        let current = GENERATIONS
+            .get(usize::from(self.index))
+            .map_or(0, |generation| generation.load(Ordering::SeqCst));
+
+        self.seen.swap(current, Ordering::Relaxed) != current
+    }
+}
+
+impl Drop for RescheduleGeneration {
+    fn drop(&mut self) {
+        if let Some(bit) = u32::from(self.index)
+            .checked_sub(1)
+            .and_then(|bit| 1u32.checked_shl(bit))
+        {
+            ALLOCATED.fetch_and(!bit, Ordering::SeqCst);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Resonance tracking for piezo and ultrasonic transducer drivers.
+//!
+//! The tracker sweeps the frequency of a channel across a band, samples a feedback callback
+//! (e.g. the current amplitude) once per period and settles on the frequency with the maximum
+//! response. It then keeps probing one fine step above and below that frequency, so it follows
+//! a resonance drifting with temperature or load.
+
+use crate::channel::input_frequency_validate;
+use crate::{ChannelId, Spwm, SpwmError};
+
+/// Callback sampling the response of the load at the frequency of the last period.
+///
+/// # Returns
+/// The response magnitude in arbitrary units (higher is closer to resonance).
+pub type ResonanceFeedbackCallback = fn() -> u32;
+
+/// Divider of the sweep step giving the step used to probe around the resonance.
+const FINE_STEP_DIVIDER: u32 = 4;
+
+/// Stage of the resonance tracker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    /// Sweeping the band
+    Sweep,
+    /// Measuring the resonance frequency; the next probe goes up or down
+    Center { next_up: bool },
+    /// Measuring a probe frequency above or below the resonance frequency
+    Probe { up: bool },
+}
+
+/// Resonance tracker driving the frequency of one channel.
+///
+/// # Example
+///
+/// ```
+/// # use spwm::{ResonanceTracker, Spwm};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<1>::new(10_000_000);
+/// let channel = spwm.create_channel()
+///     .freq_hz(40_000)
+///     .duty_cycle(50)
+///     .on_off_callback(|_| {})
+///     .period_callback(|| {})
+///     .build()?;
+/// let id = spwm.register_channel(channel)?;
+///
+/// let mut tracker = ResonanceTracker::new(&spwm, id, 38_000, 42_000, 100, || 0)?;
+///
+/// // called once per period of the channel
+/// tracker.poll(&spwm)?;
+///
+/// assert_eq!(tracker.frequency(), 38_100);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ResonanceTracker {
+    channel: ChannelId,
+    start_hz: u32,
+    end_hz: u32,
+    step_hz: u32,
+    feedback: ResonanceFeedbackCallback,
+    freq_hz: u32,
+    best_hz: u32,
+    best_response: u32,
+    stage: Stage,
+}
+
+impl ResonanceTracker {
+    /// Creates a tracker and starts the sweep at the lower band edge.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channel is registered with
+    /// - `channel`: The identifier of the channel driving the transducer
+    /// - `start_hz`: Lower edge of the band in Hz
+    /// - `end_hz`: Upper edge of the band in Hz
+    /// - `step_hz`: Frequency step of the sweep in Hz
+    /// - `feedback`: Callback sampling the response of the load
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidFrequency` if the band is empty, the step is 0 or a band edge is
+    ///   not a valid channel frequency
+    pub fn new<const N: usize, const A: usize>(
+        spwm: &Spwm<N, A>,
+        channel: ChannelId,
+        start_hz: u32,
+        end_hz: u32,
+        step_hz: u32,
+        feedback: ResonanceFeedbackCallback,
+    ) -> Result<Self, SpwmError> {
+        spwm.get_channel(channel).ok_or(SpwmError::InvalidChannel)?;
+        input_frequency_validate(start_hz, spwm.freq_hz)?;
+        input_frequency_validate(end_hz, spwm.freq_hz)?;
+
+        if start_hz >= end_hz || step_hz == 0 {
+            return Err(SpwmError::InvalidFrequency);
+        }
+
+        let mut tracker = Self {
+            channel,
+            start_hz,
+            end_hz,
+            step_hz,
+            feedback,
+            freq_hz: start_hz,
+            best_hz: start_hz,
+            best_response: 0,
+            stage: Stage::Sweep,
+        };
+
+        tracker.restart(spwm)?;
+
+        Ok(tracker)
+    }
+
+    /// Returns the frequency currently applied to the channel in Hz.
+    #[must_use]
+    pub fn frequency(&self) -> u32 {
+        self.freq_hz
+    }
+
+    /// Returns the frequency with the maximum response in Hz, or `None` while sweeping.
+    #[must_use]
+    pub fn resonance_hz(&self) -> Option<u32> {
+        if self.stage == Stage::Sweep {
+            None
+        } else {
+            Some(self.best_hz)
+        }
+    }
+
+    /// Restarts the sweep at the lower band edge, e.g. after the load changed.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel was removed from `spwm`.
+    pub fn restart<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+    ) -> Result<(), SpwmError> {
+        self.best_hz = self.start_hz;
+        self.best_response = 0;
+        self.stage = Stage::Sweep;
+
+        self.apply(spwm, self.start_hz)
+    }
+
+    /// Samples the response at the applied frequency and moves on to the next frequency.
+    ///
+    /// Meant to be called once per period of the channel, e.g. after its period callback set a
+    /// flag.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channel is registered with
+    ///
+    /// # Returns
+    /// The frequency applied for the next sample in Hz.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel was removed from `spwm`.
+    pub fn poll<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+    ) -> Result<u32, SpwmError> {
+        let response = (self.feedback)();
+        let fine_step_hz = self
+            .step_hz
+            .checked_div(FINE_STEP_DIVIDER)
+            .unwrap_or(0)
+            .max(1);
+        let (next_hz, stage) = match self.stage {
+            Stage::Sweep => {
+                if response > self.best_response {
+                    self.best_hz = self.freq_hz;
+                    self.best_response = response;
+                }
+
+                match self.freq_hz.checked_add(self.step_hz) {
+                    Some(next_hz) if next_hz <= self.end_hz => (next_hz, Stage::Sweep),
+                    _ => (self.best_hz, Stage::Center { next_up: true }),
+                }
+            }
+            Stage::Center { next_up } => {
+                // refreshed on every visit, so a drifting resonance is not held by a stale peak
+                self.best_response = response;
+
+                let probe_hz = if next_up {
+                    self.best_hz.saturating_add(fine_step_hz).min(self.end_hz)
+                } else {
+                    self.best_hz.saturating_sub(fine_step_hz).max(self.start_hz)
+                };
+
+                (probe_hz, Stage::Probe { up: next_up })
+            }
+            Stage::Probe { up } => {
+                if response > self.best_response {
+                    self.best_hz = self.freq_hz;
+                    self.best_response = response;
+                }
+
+                (self.best_hz, Stage::Center { next_up: !up })
+            }
+        };
+
+        self.stage = stage;
+        self.apply(spwm, next_hz)?;
+
+        Ok(next_hz)
+    }
+
+    /// Applies `freq_hz` to the channel, keeping its duty cycle.
+    fn apply<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        freq_hz: u32,
+    ) -> Result<(), SpwmError> {
+        let channel = spwm
+            .get_channel(self.channel)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        channel.update_frequency(freq_hz, spwm.freq_hz)?;
+        channel.sync_on_ticks();
+        self.freq_hz = freq_hz;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Tick-aligned compare events.
+//!
+//! `TickScheduler` is the compare logic behind the software alarms of `Spwm`: each event holds
+//! a compare value on a wrapping 32-bit tick counter and fires once the counter reaches it,
+//! optionally re-arming itself with a period. It can also be used on its own, driven from the
+//! same interrupt as `Spwm::irq_handler()`, to schedule arbitrary tick-aligned events next to
+//! the PWM channels.
+
+use crate::SpwmError;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Callback invoked from `TickScheduler::process()` when a compare event fires.
+pub type CompareCallback = fn();
+
+/// Unique identifier for a compare event.
+pub type CompareId = usize;
+
+/// Compare values are matched on a wrapping 32-bit tick counter, so events may be at most half
+/// of the counter range in the future.
+pub(crate) const MAX_COMPARE_TICKS: u32 = 1 << 31;
+
+/// A slot holding a compare event.
+///
+/// # Fields
+/// - `callback`: Callback invoked when the event fires
+/// - `period`: Ticks between firings of a periodic event, 0 for a one-shot event
+/// - `compare`: Tick at which the event fires next
+/// - `active`: Whether the event is pending (not yet fired for one-shot events, not removed)
+struct CompareSlot {
+    callback: Option<CompareCallback>,
+    period: u32,
+    compare: AtomicU32,
+    active: AtomicBool,
+}
+
+impl CompareSlot {
+    const fn new() -> Self {
+        Self {
+            callback: None,
+            period: 0,
+            compare: AtomicU32::new(0),
+            active: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Fixed-capacity set of compare events on a wrapping 32-bit tick counter.
+///
+/// # Type Parameters
+/// - `E`: The number of compare event slots
+///
+/// # Example
+///
+/// ```
+/// # use spwm::TickScheduler;
+/// let mut scheduler = TickScheduler::<2>::new();
+/// let id = scheduler.add_compare(100, 0, || {}).unwrap();
+///
+/// assert_eq!(scheduler.process(40), 59);
+/// assert_eq!(scheduler.process(100), u32::MAX);
+/// assert!(!scheduler.is_pending(id));
+/// ```
+pub struct TickScheduler<const E: usize> {
+    slots: [CompareSlot; E],
+}
+
+impl<const E: usize> TickScheduler<E> {
+    /// Creates a scheduler without pending events.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { CompareSlot::new() }; E],
+        }
+    }
+
+    /// Adds a compare event firing at `compare_tick`.
+    ///
+    /// The event fires from the first `process()` call whose tick is at or after
+    /// `compare_tick`, i.e. less than 2^31 ticks past it on the wrapping counter. A periodic
+    /// event then re-arms itself `period_ticks` after its compare value and stays pending until
+    /// removed.
+    ///
+    /// # Parameters
+    /// - `compare_tick`: Tick at which the event fires
+    /// - `period_ticks`: Ticks between firings, 0 for a one-shot event
+    /// - `callback`: Callback invoked when the event fires
+    ///
+    /// # Returns
+    /// The identifier of the event.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidAlarm` if `period_ticks` is not less than 2^31
+    /// - `SpwmError::NoAlarmSlotAvailable` if all `E` slots are in use
+    pub fn add_compare(
+        &mut self,
+        compare_tick: u32,
+        period_ticks: u32,
+        callback: CompareCallback,
+    ) -> Result<CompareId, SpwmError> {
+        if period_ticks >= MAX_COMPARE_TICKS {
+            return Err(SpwmError::InvalidAlarm);
+        }
+
+        let (id, slot) = self
+            .slots
+            .iter_mut()
+            .enumerate()
+            .find(|(_, slot)| !slot.active.load(Ordering::Relaxed))
+            .ok_or(SpwmError::NoAlarmSlotAvailable)?;
+
+        slot.callback = Some(callback);
+        slot.period = period_ticks;
+        slot.compare.store(compare_tick, Ordering::Relaxed);
+        slot.active.store(true, Ordering::Relaxed);
+
+        Ok(id)
+    }
+
+    /// Removes a pending compare event.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the event
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidAlarm` if the event is not pending (never added, already
+    /// fired or removed).
+    pub fn remove_compare(&mut self, id: CompareId) -> Result<(), SpwmError> {
+        match self.slots.get(id) {
+            Some(slot) if slot.active.swap(false, Ordering::Relaxed) => Ok(()),
+            _ => Err(SpwmError::InvalidAlarm),
+        }
+    }
+
+    /// Returns whether a compare event is pending, i.e. added and neither fired nor removed.
+    ///
+    /// Periodic events stay pending until removed.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the event
+    #[must_use]
+    pub fn is_pending(&self, id: CompareId) -> bool {
+        self.slots
+            .get(id)
+            .is_some_and(|slot| slot.active.load(Ordering::Relaxed))
+    }
+
+    /// Invokes the callbacks of the events due at `tick`.
+    ///
+    /// If several periods of a periodic event elapsed since the last call, its callback is
+    /// invoked once.
+    ///
+    /// # Parameters
+    /// - `tick`: Current value of the wrapping tick counter
+    ///
+    /// # Returns
+    /// Ticks after `tick` before the next event fires, or `u32::MAX` if no event is pending.
+    pub fn process(&self, tick: u32) -> u32 {
+        let mut idle_ticks = u32::MAX;
+
+        for slot in &self.slots {
+            if !slot.active.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let compare = slot.compare.load(Ordering::Relaxed);
+            let overdue = tick.wrapping_sub(compare);
+
+            if overdue >= MAX_COMPARE_TICKS {
+                idle_ticks = idle_ticks.min(compare.wrapping_sub(tick).saturating_sub(1));
+                continue;
+            }
+
+            if let Some(missed) = overdue.checked_div(slot.period) {
+                let next = compare.wrapping_add(missed.wrapping_add(1).wrapping_mul(slot.period));
+
+                slot.compare.store(next, Ordering::Relaxed);
+                idle_ticks = idle_ticks.min(next.wrapping_sub(tick).saturating_sub(1));
+            } else {
+                slot.active.store(false, Ordering::Relaxed);
+            }
+
+            if let Some(callback) = slot.callback {
+                callback();
+            }
+        }
+
+        idle_ticks
+    }
+}
+
+impl<const E: usize> Default for TickScheduler<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,129 @@
+//! Power-on self-test of channel outputs.
+//!
+//! The self-test runs a single channel for a number of periods outside of the timer interrupt
+//! and compares the level read back from the output (e.g. through an input pin wired to the
+//! output pin) with the level the channel drives. A stuck, shorted or open output, as well as
+//! wrong timing, shows up as mismatching ticks.
+
+use crate::{ChannelId, Spwm, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Callback reading back the actual output level of a channel under test.
+///
+/// # Returns
+/// `true` if the output is high.
+pub type ReadbackCallback = fn() -> bool;
+
+/// Result of `Spwm::self_test()`.
+///
+/// # Fields
+/// - `periods`: Number of periods completed
+/// - `period_ticks`: Configured period length in ticks
+/// - `expected_on_ticks`: Configured on-time of every period in ticks
+/// - `observed_on_ticks`: Number of ticks the readback level was high
+/// - `mismatched_ticks`: Number of ticks the readback level differed from the driven level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfTestReport {
+    pub periods: u32,
+    pub period_ticks: u32,
+    pub expected_on_ticks: u32,
+    pub observed_on_ticks: u64,
+    pub mismatched_ticks: u64,
+}
+
+impl SelfTestReport {
+    /// Returns whether the readback level matched the driven level on every tick and the
+    /// observed on-time matches the configured on-time of the completed periods.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.mismatched_ticks == 0
+            && self.observed_on_ticks
+                == u64::from(self.expected_on_ticks).saturating_mul(u64::from(self.periods))
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Runs a disabled channel for `periods` periods and checks its output through `readback`.
+    ///
+    /// Meant as a power-on self-test before the tick timer is started: the channel is enabled,
+    /// advanced tick by tick by this function (invoking its callbacks as `irq_handler()` would)
+    /// and disabled again, while the other channels are left alone. Before every tick the
+    /// readback level is sampled and compared with the level the channel drove since the
+    /// previous tick, so the output has one tick worth of time to settle.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the channel to test
+    /// - `periods`: Number of periods to run
+    /// - `readback`: Callback reading back the output level
+    ///
+    /// # Returns
+    /// The observed timing, see `SelfTestReport::passed()`.
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::AlreadyEnabled` if the channel is enabled
+    /// - `SpwmError::InvalidMode` if the channel is chained or mirrored, as it does not
+    ///   run on its own
+    pub fn self_test(
+        &mut self,
+        channel_id: ChannelId,
+        periods: u32,
+        readback: ReadbackCallback,
+    ) -> Result<SelfTestReport, SpwmError> {
+        let channel = self
+            .get_channel(channel_id)
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if channel.is_enabled() {
+            return Err(SpwmError::AlreadyEnabled);
+        }
+
+        if self.is_mirrored(channel_id)
+            || self
+                .channel_slots
+                .get(channel_id)
+                .is_some_and(|slot| slot.chain.is_some())
+        {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        let period_ticks = channel.period_ticks.load(Ordering::Relaxed);
+        let mut report = SelfTestReport {
+            periods: 0,
+            period_ticks,
+            expected_on_ticks: channel.on_ticks.load(Ordering::Relaxed),
+            observed_on_ticks: 0,
+            mismatched_ticks: 0,
+        };
+        // Bounds the test if the channel never completes a period (e.g. a missing period end)
+        let max_ticks = u64::from(periods)
+            .saturating_add(1)
+            .saturating_mul(u64::from(period_ticks))
+            .saturating_add(u64::from(channel.start_delay_ticks.load(Ordering::Relaxed)));
+        let mut ticks = 0u64;
+
+        channel.enable()?;
+
+        while report.periods < periods && ticks < max_ticks {
+            let level = readback();
+
+            if level {
+                report.observed_on_ticks = report.observed_on_ticks.saturating_add(1);
+            }
+
+            if level != channel.output_on.load(Ordering::Relaxed) {
+                report.mismatched_ticks = report.mismatched_ticks.saturating_add(1);
+            }
+
+            if channel.process_tick() {
+                report.periods = report.periods.saturating_add(1);
+            }
+
+            ticks = ticks.saturating_add(1);
+        }
+
+        channel.disable()?;
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,215 @@
+//! Text command interface for embedded CLIs.
+//!
+//! `Spwm::shell_command()` executes a single command line and writes a human readable response
+//! into a caller-provided buffer, so a CLI crate only has to forward the `pwm` command line:
+//!
+//! - `pwm set <channel> <duty>`: set the duty cycle percentage
+//! - `pwm freq <channel> <hz>`: set the channel frequency
+//! - `pwm on <channel>` / `pwm off <channel>`: enable or disable a channel
+//! - `pwm status`: list the registered channels
+
+use crate::{ChannelId, Spwm, SpwmError};
+use core::fmt::{self, Write};
+use core::str::SplitAsciiWhitespace;
+
+/// `fmt::Write` adapter filling a byte buffer and truncating the output that does not fit.
+struct ResponseWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Write for ResponseWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let free = self.buf.get_mut(self.len..).unwrap_or_default();
+        let count = s.len().min(free.len());
+
+        if let (Some(dst), Some(src)) = (free.get_mut(..count), s.as_bytes().get(..count)) {
+            dst.copy_from_slice(src);
+        }
+
+        self.len = self.len.saturating_add(count);
+
+        if count < s.len() {
+            return Err(fmt::Error);
+        }
+
+        Ok(())
+    }
+}
+
+/// Reasons a shell command fails.
+enum ShellError {
+    Usage,
+    Spwm(SpwmError),
+}
+
+impl From<SpwmError> for ShellError {
+    fn from(error: SpwmError) -> Self {
+        Self::Spwm(error)
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Executes a text command and writes the response into `response`.
+    ///
+    /// Successful commands respond with a line starting with `ok`, failed ones with a line
+    /// starting with `error:`. A response that does not fit into the buffer is truncated.
+    ///
+    /// # Parameters
+    /// - `command`: Command line, e.g. `pwm set 2 33`
+    /// - `response`: Buffer receiving the response text
+    ///
+    /// # Returns
+    /// The number of bytes written to `response`.
+    ///
+    /// # Example
+    /// ```
+    /// # use spwm::Spwm;
+    /// # fn main() -> Result<(), spwm::SpwmError> {
+    /// let mut spwm = Spwm::<1>::new(100_000);
+    /// let channel = spwm.create_channel()
+    ///     .freq_hz(1_000)
+    ///     .duty_cycle(10)
+    ///     .on_off_callback(|_| {})
+    ///     .period_callback(|| {})
+    ///     .build()?;
+    /// spwm.register_channel(channel)?;
+    ///
+    /// let mut response = [0; 64];
+    /// let len = spwm.shell_command("pwm set 0 33", &mut response);
+    ///
+    /// assert_eq!(&response[..len], b"ok: channel 0 duty 33% (was 10%)\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shell_command(&mut self, command: &str, response: &mut [u8]) -> usize {
+        let mut writer = ResponseWriter {
+            buf: response,
+            len: 0,
+        };
+        let mut args = command.split_ascii_whitespace();
+        let result = match (args.next(), args.next()) {
+            (Some("pwm"), Some(subcommand)) => self.run_shell(subcommand, &mut args, &mut writer),
+            _ => Err(ShellError::Usage),
+        };
+
+        // a truncated response is still returned
+        let _ = match result {
+            Ok(()) => Ok(()),
+            Err(ShellError::Usage) => writeln!(
+                writer,
+                "error: usage: pwm set|freq|on|off <channel> [value] | pwm status"
+            ),
+            Err(ShellError::Spwm(error)) => writeln!(writer, "error: {}", describe(&error)),
+        };
+
+        writer.len
+    }
+
+    /// Dispatches a `pwm` subcommand.
+    fn run_shell(
+        &mut self,
+        subcommand: &str,
+        args: &mut SplitAsciiWhitespace,
+        writer: &mut ResponseWriter,
+    ) -> Result<(), ShellError> {
+        if subcommand == "status" {
+            if args.next().is_some() {
+                return Err(ShellError::Usage);
+            }
+
+            self.shell_status(writer);
+
+            return Ok(());
+        }
+
+        let id: ChannelId = parse_arg(args.next())?;
+        let channel = self.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+        match subcommand {
+            "set" => {
+                let duty_cycle = parse_arg(args.next())?;
+                let previous = channel.update_duty_cycle(duty_cycle)?;
+                let _ = writeln!(
+                    writer,
+                    "ok: channel {id} duty {duty_cycle}% (was {previous}%)"
+                );
+            }
+            "freq" => {
+                let freq_hz = parse_arg(args.next())?;
+
+                channel.update_frequency(freq_hz, self.freq_hz)?;
+                channel.sync_on_ticks();
+                let _ = writeln!(writer, "ok: channel {id} frequency {freq_hz} Hz");
+            }
+            "on" => {
+                channel.enable()?;
+                let _ = writeln!(writer, "ok: channel {id} enabled");
+            }
+            "off" => {
+                channel.disable()?;
+                let _ = writeln!(writer, "ok: channel {id} disabled");
+            }
+            _ => return Err(ShellError::Usage),
+        }
+
+        if args.next().is_some() {
+            return Err(ShellError::Usage);
+        }
+
+        Ok(())
+    }
+
+    /// Writes one status line per registered channel.
+    fn shell_status(&self, writer: &mut ResponseWriter) {
+        let _ = writeln!(writer, "ok");
+
+        for id in 0..N {
+            if let Some(channel) = self.get_channel(id) {
+                let report = channel.validate();
+                let freq_hz = self.freq_hz.checked_div(report.period_ticks).unwrap_or(0);
+                let state = if channel.is_enabled() { "on" } else { "off" };
+                let _ = writeln!(
+                    writer,
+                    "{id}: {state} {freq_hz} Hz duty {}%",
+                    channel.duty_cycle()
+                );
+            }
+        }
+    }
+}
+
+/// Parses a numeric command argument.
+fn parse_arg<T: core::str::FromStr>(arg: Option<&str>) -> Result<T, ShellError> {
+    arg.and_then(|arg| arg.parse().ok())
+        .ok_or(ShellError::Usage)
+}
+
+/// Returns a short description of an error for command responses.
+fn describe(error: &SpwmError) -> &'static str {
+    match error {
+        SpwmError::InvalidHardwareFrequency => "invalid hardware frequency",
+        SpwmError::InvalidChannel => "invalid channel",
+        SpwmError::InvalidFrequency => "invalid frequency",
+        SpwmError::InvalidDutyCycle => "invalid duty cycle",
+        SpwmError::CallbackSetError => "callback error",
+        SpwmError::AlreadyEnabled => "already enabled",
+        SpwmError::EnableFailed => "enable failed",
+        SpwmError::AlreadyDisabled => "already disabled",
+        SpwmError::DisableFailed => "disable failed",
+        SpwmError::InvalidPulseWidth => "invalid pulse width",
+        SpwmError::NoChannelSlotAvailable => "no channel slot available",
+        SpwmError::InvalidChainMode => "invalid chain mode",
+        SpwmError::Busy => "busy",
+        SpwmError::TimingToleranceExceeded => "timing tolerance exceeded",
+        SpwmError::AlreadyPaused => "already paused",
+        SpwmError::NotPaused => "not paused",
+        SpwmError::InvalidTickDivider => "invalid tick divider",
+        SpwmError::AlreadySuspended => "already suspended",
+        SpwmError::NotSuspended => "not suspended",
+        SpwmError::NoAlarmSlotAvailable => "no alarm slot available",
+        SpwmError::InvalidAlarm => "invalid alarm",
+        SpwmError::InvalidConfiguration => "invalid configuration",
+        SpwmError::InvalidMode => "invalid mode",
+    }
+}
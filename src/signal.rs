@@ -0,0 +1,291 @@
+//! Test-signal generator channels.
+//!
+//! A signal generator channel modulates its duty cycle period by period along a sine, triangle
+//! or square waveform, so its low-pass filtered output is a low-frequency analog signal for
+//! exercising analog filters or as a control reference. The waveform phase advances by the
+//! ticks of every carrier period, so the signal frequency does not depend on the carrier
+//! frequency, and the level is computed in hundredths of a percent for a finer on-time than the
+//! 1% duty cycle steps. Noise waveforms draw a new random level at the signal frequency
+//! instead.
+
+use crate::{SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Waveform of a signal generator channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignalWaveform {
+    /// Sine wave
+    Sine,
+    /// Triangle wave starting at the offset and rising like the sine
+    Triangle,
+    /// Square wave, high during the first half-period
+    Square,
+    /// White noise with a new level at every signal period
+    WhiteNoise,
+    /// Pink (1/f) noise with a new level at every signal period
+    PinkNoise,
+}
+
+impl SignalWaveform {
+    /// Returns the value stored in the channel.
+    fn to_raw(self) -> u8 {
+        match self {
+            SignalWaveform::Sine => 1,
+            SignalWaveform::Triangle => 2,
+            SignalWaveform::Square => 3,
+            SignalWaveform::WhiteNoise => 4,
+            SignalWaveform::PinkNoise => 5,
+        }
+    }
+
+    /// Returns the waveform of a stored value, or `None` for a channel that is not a signal
+    /// generator.
+    fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            1 => Some(SignalWaveform::Sine),
+            2 => Some(SignalWaveform::Triangle),
+            3 => Some(SignalWaveform::Square),
+            4 => Some(SignalWaveform::WhiteNoise),
+            5 => Some(SignalWaveform::PinkNoise),
+            _ => None,
+        }
+    }
+
+    /// Returns the waveform value at `phase` scaled to ±`SIGNAL_SCALE`, or `None` for noise
+    /// waveforms, whose value does not depend on the phase.
+    fn value(self, phase: u32) -> Option<i32> {
+        match self {
+            SignalWaveform::Sine => Some(sine(phase)),
+            SignalWaveform::Triangle => Some(triangle(phase)),
+            SignalWaveform::Square => {
+                if phase < HALF_TURN {
+                    Some(SIGNAL_SCALE)
+                } else {
+                    Some(SIGNAL_SCALE.saturating_neg())
+                }
+            }
+            SignalWaveform::WhiteNoise | SignalWaveform::PinkNoise => None,
+        }
+    }
+}
+
+/// Full scale of a waveform value and of the output level (hundredths of a percent).
+pub(crate) const SIGNAL_SCALE: i32 = 10_000;
+
+/// Phase of half a waveform period.
+const HALF_TURN: u32 = 1 << 31;
+
+/// Quarter sine period in 64 steps scaled to `SIGNAL_SCALE`.
+const QUARTER_SINE: [u16; 65] = [
+    0, 245, 491, 736, 980, 1224, 1467, 1710, 1951, 2191, 2430, 2667, 2903, 3137, 3369, 3599, 3827,
+    4052, 4276, 4496, 4714, 4929, 5141, 5350, 5556, 5758, 5957, 6152, 6344, 6532, 6716, 6895, 7071,
+    7242, 7410, 7572, 7730, 7883, 8032, 8176, 8315, 8449, 8577, 8701, 8819, 8932, 9040, 9142, 9239,
+    9330, 9415, 9495, 9569, 9638, 9700, 9757, 9808, 9853, 9892, 9925, 9952, 9973, 9988, 9997,
+    10000,
+];
+
+/// Returns the sine of `phase` scaled to ±`SIGNAL_SCALE` in 256 steps per period.
+fn sine(phase: u32) -> i32 {
+    let step = phase >> 24;
+    let index = step & 0x3F;
+    let index = if step & 0x40 == 0 {
+        index
+    } else {
+        0x40_u32.saturating_sub(index)
+    };
+    let value = usize::try_from(index)
+        .ok()
+        .and_then(|index| QUARTER_SINE.get(index))
+        .map_or(0, |&value| i32::from(value));
+
+    if phase < HALF_TURN {
+        value
+    } else {
+        value.saturating_neg()
+    }
+}
+
+/// Returns the triangle of `phase` scaled to ±`SIGNAL_SCALE`.
+fn triangle(phase: u32) -> i32 {
+    // phase in 1/65536 periods, a quarter period rises from 0 to full scale
+    let phase = i32::try_from(phase >> 16).unwrap_or(0);
+    let rise = match phase {
+        0..16_384 => phase,
+        16_384..49_152 => 32_768_i32.saturating_sub(phase),
+        _ => phase.saturating_sub(65_536),
+    };
+
+    rise.saturating_mul(SIGNAL_SCALE) >> 14
+}
+
+/// Converts a signal frequency into the phase advance per hardware timer tick.
+///
+/// # Errors
+/// Returns `SpwmError::InvalidFrequency` if the frequency is 0 or too low for the phase
+/// resolution.
+fn phase_step(signal_freq_mhz: u32, hardware_freq_hz: u32) -> Result<u32, SpwmError> {
+    let ticks_per_sec_mhz = u64::from(hardware_freq_hz).saturating_mul(1_000);
+
+    (u64::from(signal_freq_mhz) << 32)
+        .checked_add(ticks_per_sec_mhz / 2)
+        .and_then(|turn| turn.checked_div(ticks_per_sec_mhz))
+        .and_then(|step| u32::try_from(step).ok())
+        .filter(|&step| step != 0)
+        .ok_or(SpwmError::InvalidFrequency)
+}
+
+impl SpwmChannel {
+    /// Configures the channel as a signal generator with a full-scale swing around 50%, and
+    /// sets the on-time of the first period.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the signal frequency is invalid.
+    pub(crate) fn set_signal_generator(
+        &self,
+        waveform: SignalWaveform,
+        signal_freq_mhz: u32,
+        hardware_freq_hz: u32,
+    ) -> Result<(), SpwmError> {
+        self.signal_waveform
+            .store(waveform.to_raw(), Ordering::SeqCst);
+        self.signal_amplitude.store(50, Ordering::Relaxed);
+        self.signal_offset.store(50, Ordering::Relaxed);
+
+        self.set_signal_frequency(signal_freq_mhz, hardware_freq_hz)?;
+        self.draw_noise(waveform);
+
+        if let Some(on_ticks) = self.signal_on_ticks() {
+            self.update_on_ticks(on_ticks);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the on-time of the next period of a signal generator channel and advances the
+    /// waveform phase by the period, or `None` for other channels.
+    pub(crate) fn signal_on_ticks(&self) -> Option<u32> {
+        let waveform = self.signal_waveform()?;
+        let period_ticks = self.effective_period_ticks();
+        let phase = self.signal_phase.load(Ordering::Relaxed);
+        let amplitude = i32::from(self.signal_amplitude.load(Ordering::Relaxed));
+        let offset = i32::from(self.signal_offset.load(Ordering::Relaxed));
+        let level = waveform
+            .value(phase)
+            .unwrap_or_else(|| self.noise_sample.load(Ordering::Relaxed))
+            .saturating_mul(amplitude)
+            .checked_div(100)
+            .unwrap_or(0)
+            .saturating_add(offset.saturating_mul(100))
+            .clamp(0, SIGNAL_SCALE);
+
+        let (next_phase, wrapped) = phase.overflowing_add(
+            self.signal_phase_step
+                .load(Ordering::Relaxed)
+                .wrapping_mul(period_ticks),
+        );
+
+        self.signal_phase.store(next_phase, Ordering::Relaxed);
+
+        if wrapped {
+            self.draw_noise(waveform);
+        }
+
+        u64::from(period_ticks)
+            .saturating_mul(u64::from(level.unsigned_abs()))
+            .checked_div(SIGNAL_SCALE.unsigned_abs().into())
+            .and_then(|ticks| u32::try_from(ticks).ok())
+    }
+
+    /// Returns the waveform of a signal generator channel, or `None` for other channels.
+    pub fn signal_waveform(&self) -> Option<SignalWaveform> {
+        SignalWaveform::from_raw(self.signal_waveform.load(Ordering::Relaxed))
+    }
+
+    /// Changes the waveform of a signal generator channel, keeping its phase.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidMode` if the channel is not a signal generator.
+    pub fn set_signal_waveform(&self, waveform: SignalWaveform) -> Result<(), SpwmError> {
+        self.signal_waveform().ok_or(SpwmError::InvalidMode)?;
+        self.signal_waveform
+            .store(waveform.to_raw(), Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Changes the signal frequency of a signal generator channel from the next period on.
+    ///
+    /// For noise waveforms the signal frequency is the rate of new noise levels.
+    ///
+    /// # Parameters
+    /// - `signal_freq_mhz`: Signal frequency in mHz
+    /// - `hardware_freq_hz`: Hardware timer frequency in Hz
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidMode` if the channel is not a signal generator
+    /// - `SpwmError::InvalidFrequency` if the frequency is 0, too low for the phase resolution
+    ///   or the signal period is shorter than two carrier periods
+    pub fn set_signal_frequency(
+        &self,
+        signal_freq_mhz: u32,
+        hardware_freq_hz: u32,
+    ) -> Result<(), SpwmError> {
+        self.signal_waveform().ok_or(SpwmError::InvalidMode)?;
+
+        let step = phase_step(signal_freq_mhz, hardware_freq_hz)?;
+
+        if u64::from(step).saturating_mul(u64::from(self.effective_period_ticks()))
+            > u64::from(HALF_TURN)
+        {
+            return Err(SpwmError::InvalidFrequency);
+        }
+
+        self.signal_phase_step.store(step, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Changes the amplitude of a signal generator channel from the next period on.
+    ///
+    /// The output swings by `amplitude` around the offset and is clipped at 0% and 100%, so
+    /// noise stays within the bounds `offset - amplitude` and `offset + amplitude`.
+    ///
+    /// # Parameters
+    /// - `amplitude`: Peak amplitude in percent (0-100)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidMode` if the channel is not a signal generator
+    /// - `SpwmError::InvalidDutyCycle` if the amplitude is greater than 100
+    pub fn set_signal_amplitude(&self, amplitude: u8) -> Result<(), SpwmError> {
+        self.signal_waveform().ok_or(SpwmError::InvalidMode)?;
+
+        if amplitude > 100 {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        self.signal_amplitude.store(amplitude, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Changes the offset (the duty cycle at the zero crossings) of a signal generator channel
+    /// from the next period on.
+    ///
+    /// # Parameters
+    /// - `offset`: Offset in percent (0-100)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidMode` if the channel is not a signal generator
+    /// - `SpwmError::InvalidDutyCycle` if the offset is greater than 100
+    pub fn set_signal_offset(&self, offset: u8) -> Result<(), SpwmError> {
+        self.signal_waveform().ok_or(SpwmError::InvalidMode)?;
+
+        if offset > 100 {
+            return Err(SpwmError::InvalidDutyCycle);
+        }
+
+        self.signal_offset.store(offset, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
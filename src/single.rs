@@ -0,0 +1,71 @@
+//! Single-channel SPWM manager for size- and latency-constrained targets.
+//!
+//! `SpwmSingle` owns exactly one channel, so its interrupt handler drives the channel directly
+//! without iterating over channel slots, checking for empty slots or resolving channel
+//! identifiers. Features that relate several channels (chaining, ratio locking, priorities) are
+//! only available through `Spwm`.
+
+use crate::SpwmChannel;
+
+/// A software PWM manager driving a single channel.
+///
+/// # Example
+/// ```
+/// # use spwm::{SpwmChannelBuilder, SpwmSingle};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let channel = SpwmChannelBuilder::new(100_000)
+///     .freq_hz(1_000)
+///     .duty_cycle(25)
+///     .on_off_callback(|_| {})
+///     .period_callback(|| {})
+///     .build()?;
+/// let spwm = SpwmSingle::new(channel);
+///
+/// spwm.channel().enable()?;
+/// // in the timer interrupt
+/// spwm.irq_handler();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SpwmSingle {
+    channel: SpwmChannel,
+}
+
+impl SpwmSingle {
+    /// Creates a single-channel manager that owns `channel`.
+    ///
+    /// # Parameters
+    /// - `channel`: The PWM channel to drive
+    #[must_use]
+    pub fn new(channel: SpwmChannel) -> Self {
+        Self { channel }
+    }
+
+    /// Returns the driven channel.
+    #[must_use]
+    pub fn channel(&self) -> &SpwmChannel {
+        &self.channel
+    }
+
+    /// Releases the driven channel, e.g. to register it with an `Spwm` manager.
+    #[must_use]
+    pub fn into_channel(self) -> SpwmChannel {
+        self.channel
+    }
+
+    /// Handles the timer interrupt by advancing the channel by one tick.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[interrupt]
+    /// fn TIMER_IRQ() {
+    ///     spwm.irq_handler();
+    /// }
+    /// ```
+    #[inline]
+    pub fn irq_handler(&self) {
+        self.channel.process_tick();
+    }
+}
@@ -0,0 +1,70 @@
+//! Tick processing shared by the channel managers.
+//!
+//! `Spwm` and `SpwmDyn` keep their channels in slot storage of different kinds (a fixed array
+//! and a `Vec`) but process them the same way on an event tick: every channel catches up on
+//! the skipped ticks, the channels are processed in descending priority order and the next
+//! event bounds the ticks the following calls may skip. The helpers work on slot slices, so
+//! both managers run the same code.
+
+use crate::{ChannelId, ChannelSlot, SpwmChannel};
+
+/// Returns an iterator over the channels stored in `slots`.
+pub(crate) fn channels(slots: &[ChannelSlot]) -> impl Iterator<Item = &SpwmChannel> {
+    slots.iter().filter_map(|slot| slot.channel.as_ref())
+}
+
+/// Sorts the processing order by descending channel priority, keeping slot order for channels
+/// with equal priority.
+pub(crate) fn sort_order(order: &mut [ChannelId], slots: &[ChannelSlot]) {
+    order.sort_unstable_by_key(|&i| {
+        let priority = slots
+            .get(i)
+            .and_then(|slot| slot.channel.as_ref())
+            .map_or(0, SpwmChannel::priority);
+
+        (core::cmp::Reverse(priority), i)
+    });
+}
+
+/// Processes one event tick of the channels in `slots`.
+///
+/// Every channel first catches up on `skipped_ticks` ticks without events (`restart_ticks` for
+/// channels enabled in between), then `process` is called for each channel in `order`.
+/// Mirrored channels are left out, as they follow their primary instead of running on their
+/// own.
+pub(crate) fn process_tick(
+    slots: &[ChannelSlot],
+    order: &[ChannelId],
+    skipped_ticks: u32,
+    restart_ticks: u32,
+    mut process: impl FnMut(ChannelId, &SpwmChannel),
+) {
+    for channel in channels(slots) {
+        channel.catch_up(skipped_ticks, restart_ticks);
+    }
+
+    for &i in order {
+        let Some(slot) = slots.get(i) else {
+            continue;
+        };
+
+        if let Some(channel) = slot.channel.as_ref()
+            && slot.mirror.is_none()
+        {
+            process(i, channel);
+        }
+    }
+}
+
+/// Returns the number of upcoming ticks without an event of any channel in `slots`.
+///
+/// `u32::MAX` means no channel has a scheduled event.
+pub(crate) fn idle_ticks(slots: &[ChannelSlot]) -> u32 {
+    slots
+        .iter()
+        .filter(|slot| slot.mirror.is_none())
+        .filter_map(|slot| slot.channel.as_ref())
+        .map(SpwmChannel::idle_ticks)
+        .min()
+        .unwrap_or(u32::MAX)
+}
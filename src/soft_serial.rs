@@ -0,0 +1,164 @@
+//! Transmit-only software serial port (UART TX) on top of the SPWM tick engine.
+//!
+//! Every channel period carries one bit of an 8N1 frame (start bit, 8 data bits LSB first,
+//! stop bit), so the channel frequency has to equal the baud rate. The serial port acts as the
+//! channel's level source and keeps the line high while its FIFO is empty.
+//!
+//! The baud rate accuracy depends on how evenly the hardware timer frequency divides by the baud
+//! rate: e.g. a 960 kHz tick gives an exact 9600 baud bit time, while 1 MHz gives 104 ticks per
+//! bit (0.16% error).
+
+use core::sync::atomic::{AtomicU8, AtomicU16, AtomicUsize, Ordering};
+
+/// Number of bits in an 8N1 frame.
+const FRAME_BITS: u8 = 10;
+
+/// Transmit-only software serial port with a byte FIFO of `F - 1` bytes.
+///
+/// The FIFO is a lock-free single-producer single-consumer queue: `write()` must only be called
+/// from one context, while `next_level()` is called from the channel level source in the timer
+/// interrupt.
+///
+/// # Example
+///
+/// ```
+/// use spwm::{SoftSerial, Spwm};
+///
+/// static SERIAL: SoftSerial<32> = SoftSerial::new();
+///
+/// fn serial_level() -> Option<bool> {
+///     SERIAL.next_level()
+/// }
+///
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<1>::new(960_000);
+/// let channel = spwm.create_channel()
+///     .freq_hz(9_600)
+///     .duty_cycle(100)
+///     .on_off_callback(|_| {
+///         // drive the TX pin
+///     })
+///     .period_callback(|| {})
+///     .level_source(serial_level)
+///     .build()?;
+/// let id = spwm.register_channel(channel)?;
+///
+/// spwm.get_channel(id).unwrap().enable()?;
+/// SERIAL.write(b"hello\r\n");
+/// # Ok(())
+/// # }
+/// ```
+pub struct SoftSerial<const F: usize> {
+    fifo: [AtomicU8; F],
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    frame: AtomicU16,
+    bits_left: AtomicU8,
+}
+
+impl<const F: usize> SoftSerial<F> {
+    /// Creates an idle serial port with an empty FIFO.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            fifo: [const { AtomicU8::new(0) }; F],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            frame: AtomicU16::new(0),
+            bits_left: AtomicU8::new(0),
+        }
+    }
+
+    /// Queues bytes for transmission.
+    ///
+    /// # Parameters
+    /// - `data`: Bytes to transmit
+    ///
+    /// # Returns
+    /// Number of bytes queued, which is less than `data.len()` if the FIFO is full.
+    pub fn write(&self, data: &[u8]) -> usize {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        let mut written: usize = 0;
+
+        for &byte in data {
+            let next = ring_next::<F>(tail);
+
+            if next == head {
+                break;
+            }
+
+            let Some(slot) = self.fifo.get(tail) else {
+                break;
+            };
+
+            slot.store(byte, Ordering::Relaxed);
+            tail = next;
+            written = written.saturating_add(1);
+        }
+
+        self.tail.store(tail, Ordering::Release);
+
+        written
+    }
+
+    /// Returns `true` if the FIFO is empty and no frame is being transmitted.
+    pub fn is_idle(&self) -> bool {
+        self.bits_left.load(Ordering::Acquire) == 0
+            && self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Acquire)
+    }
+
+    /// Produces the line level for the next bit period.
+    ///
+    /// Intended to be called from a channel level source callback.
+    ///
+    /// # Returns
+    /// The next bit level; the line idles high (`Some(true)`) when there is nothing to send.
+    pub fn next_level(&self) -> Option<bool> {
+        let mut bits_left = self.bits_left.load(Ordering::Relaxed);
+
+        if bits_left == 0 {
+            let Some(byte) = self.pop() else {
+                return Some(true);
+            };
+
+            self.frame
+                .store((1 << 9) | (u16::from(byte) << 1), Ordering::Relaxed);
+            bits_left = FRAME_BITS;
+        }
+
+        let frame = self.frame.load(Ordering::Relaxed);
+
+        self.frame.store(frame >> 1, Ordering::Relaxed);
+        self.bits_left
+            .store(bits_left.saturating_sub(1), Ordering::Release);
+
+        Some(frame & 1 != 0)
+    }
+
+    /// Takes the next byte from the FIFO.
+    fn pop(&self) -> Option<u8> {
+        let head = self.head.load(Ordering::Relaxed);
+
+        if head == self.tail.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let byte = self.fifo.get(head)?.load(Ordering::Relaxed);
+
+        self.head.store(ring_next::<F>(head), Ordering::Release);
+
+        Some(byte)
+    }
+}
+
+impl<const F: usize> Default for SoftSerial<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the FIFO index following `index` (0 for a zero-sized FIFO).
+fn ring_next<const F: usize>(index: usize) -> usize {
+    index.wrapping_add(1).checked_rem(F).unwrap_or(0)
+}
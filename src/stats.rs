@@ -0,0 +1,76 @@
+//! On-time accounting of SPWM channels.
+//!
+//! Every channel accumulates the ticks it ran enabled and the ticks its output was on, so
+//! applications can derive delivered heater energy, average LED brightness or the duty history
+//! since boot without instrumenting the on/off callbacks.
+
+use crate::SpwmChannel;
+use crate::tick_count::TickCount;
+use core::sync::atomic::Ordering;
+
+/// Accumulated tick counts of a channel.
+///
+/// # Fields
+/// - `ticks`: Ticks processed while the channel was enabled
+/// - `on_ticks`: Ticks processed while the channel output was on
+#[derive(Debug, Default)]
+pub(crate) struct ChannelStats {
+    ticks: TickCount,
+    on_ticks: TickCount,
+}
+
+impl SpwmChannel {
+    /// Accounts `ticks` processed ticks at the current output level.
+    pub(crate) fn account_ticks(&self, ticks: u32) {
+        self.stats.ticks.add(ticks);
+
+        if self.output_on.load(Ordering::Relaxed) {
+            self.stats.on_ticks.add(ticks);
+        }
+    }
+
+    /// Returns the number of ticks processed while the channel was enabled.
+    ///
+    /// Ticks skipped by `Spwm::irq_handler()` between two events are accounted at the next
+    /// event.
+    pub fn total_ticks(&self) -> u64 {
+        self.stats.ticks.get()
+    }
+
+    /// Returns the number of ticks processed while the channel output was on.
+    ///
+    /// Ticks the output was held off by `pause()` or `Spwm::suspend()` are not counted.
+    pub fn total_on_ticks(&self) -> u64 {
+        self.stats.on_ticks.get()
+    }
+
+    /// Returns the ratio of on-time to total enabled time (0.0-1.0).
+    ///
+    /// # Returns
+    /// The on-time ratio, or `None` if the channel did not run yet.
+    pub fn on_time_ratio(&self) -> Option<f32> {
+        let ticks = self.total_ticks();
+
+        if ticks == 0 {
+            return None;
+        }
+
+        // Both counts are reduced to 16 bits, which keeps the ratio accurate to 1/32768
+        let shift = u64::BITS
+            .saturating_sub(ticks.leading_zeros())
+            .saturating_sub(u16::BITS);
+        let reduce =
+            |count: u64| u16::try_from(count.checked_shr(shift).unwrap_or(0)).unwrap_or(u16::MAX);
+
+        Some(f32::from(reduce(self.total_on_ticks())) / f32::from(reduce(ticks)))
+    }
+
+    /// Resets the accumulated tick counts.
+    ///
+    /// Must not be called while `Spwm::irq_handler()` may run, as the counters only support a
+    /// single writer.
+    pub fn reset_stats(&self) {
+        self.stats.ticks.reset();
+        self.stats.on_ticks.reset();
+    }
+}
@@ -0,0 +1,328 @@
+//! Open-loop microstepping of two-phase stepper motors.
+//!
+//! The coil currents follow a sine and a cosine of the electrical angle, set through the duty
+//! cycles of the coil channels. One full step is a quarter of the electrical period, divided
+//! into up to 16 microsteps. The position advances on demand or at a programmed rate.
+
+use crate::{ChannelId, Spwm, SpwmError};
+
+/// Duty cycles of the first quarter of a sine period in 16 microsteps (`sin(k * 90° / 16)`).
+const QUARTER_SINE: [u8; 17] = [
+    0, 10, 20, 29, 38, 47, 56, 63, 71, 77, 83, 88, 92, 96, 98, 100, 100,
+];
+
+/// Finest microstep resolution per full step.
+const MAX_MICROSTEPS: u8 = 16;
+
+/// Table positions per electrical period (4 full steps at the finest resolution).
+const ELECTRICAL_POSITIONS: i64 = 64;
+
+/// Rotation direction of a stepper motor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepDirection {
+    /// Coil B leads coil A by a quarter of the electrical period
+    Forward,
+    /// Coil B lags coil A by a quarter of the electrical period
+    Reverse,
+}
+
+/// Channels driving the two coils of a stepper motor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepperOutputs {
+    /// One channel per coil carrying the current amplitude; the current direction is switched
+    /// by the application according to `Stepper::polarity()`
+    Amplitude { a: ChannelId, b: ChannelId },
+    /// Two channels per coil, one per H-bridge half: the channel matching the current direction
+    /// carries the amplitude, the other one stays at 0%
+    Bridge {
+        a_pos: ChannelId,
+        a_neg: ChannelId,
+        b_pos: ChannelId,
+        b_neg: ChannelId,
+    },
+}
+
+/// Microstepping driver of a two-phase stepper motor.
+///
+/// # Example
+///
+/// ```
+/// # use spwm::{Spwm, StepDirection, Stepper, StepperOutputs};
+/// # fn main() -> Result<(), spwm::SpwmError> {
+/// let mut spwm = Spwm::<2>::new(1_000_000);
+/// let mut ids = [0; 2];
+///
+/// for id in &mut ids {
+///     let channel = spwm.create_channel()
+///         .freq_hz(10_000)
+///         .duty_cycle(0)
+///         .on_off_callback(|_| {})
+///         .period_callback(|| {})
+///         .build()?;
+///     *id = spwm.register_channel(channel)?;
+/// }
+///
+/// let outputs = StepperOutputs::Amplitude { a: ids[0], b: ids[1] };
+/// let mut stepper = Stepper::new(&spwm, outputs, 4)?;
+///
+/// stepper.step(&spwm, StepDirection::Forward)?;
+///
+/// assert_eq!(stepper.position(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Stepper {
+    outputs: StepperOutputs,
+    microsteps: u8,
+    position: i64,
+    direction: StepDirection,
+    interval_ticks: u64,
+    next_tick: u64,
+}
+
+impl Stepper {
+    /// Configures the coil channels for microstepping and enables them at position 0.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `outputs`: Channels driving the coils
+    /// - `microsteps`: Microsteps per full step (1, 2, 4, 8 or 16)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if a channel is not registered or used twice
+    /// - `SpwmError::InvalidConfiguration` if `microsteps` is not a power of two up to 16
+    pub fn new<const N: usize, const A: usize>(
+        spwm: &Spwm<N, A>,
+        outputs: StepperOutputs,
+        microsteps: u8,
+    ) -> Result<Self, SpwmError> {
+        if !microsteps.is_power_of_two() || microsteps > MAX_MICROSTEPS {
+            return Err(SpwmError::InvalidConfiguration);
+        }
+
+        let stepper = Self {
+            outputs,
+            microsteps,
+            position: 0,
+            direction: StepDirection::Forward,
+            interval_ticks: 0,
+            next_tick: 0,
+        };
+        let (ids, count) = stepper.channel_ids();
+        let ids = ids.get(..count).unwrap_or(&[]);
+
+        for (i, id) in ids.iter().enumerate() {
+            if spwm.get_channel(*id).is_none()
+                || ids
+                    .iter()
+                    .skip(i.saturating_add(1))
+                    .any(|other| other == id)
+            {
+                return Err(SpwmError::InvalidChannel);
+            }
+        }
+
+        stepper.apply(spwm)?;
+
+        for &id in ids {
+            let channel = spwm.get_channel(id).ok_or(SpwmError::InvalidChannel)?;
+
+            if !channel.is_enabled() {
+                channel.enable()?;
+            }
+        }
+
+        Ok(stepper)
+    }
+
+    /// Returns the position in microsteps (positive in forward direction).
+    #[must_use]
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns whether the currents of coil A and coil B flow in positive direction.
+    ///
+    /// Used with `StepperOutputs::Amplitude` to drive the direction inputs of the bridges.
+    #[must_use]
+    pub fn polarity(&self) -> (bool, bool) {
+        let (a, b) = self.coil_levels();
+
+        (a >= 0, b >= 0)
+    }
+
+    /// Advances the motor by one microstep.
+    ///
+    /// The new duty cycles apply at the next period of the coil channels.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `direction`: Direction of the step
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn step<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        direction: StepDirection,
+    ) -> Result<(), SpwmError> {
+        self.advance(direction, 1);
+        self.apply(spwm)
+    }
+
+    /// Sets the stepping rate used by `poll()`.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `microsteps_per_sec`: Stepping rate; the sign selects the direction and 0 stops
+    /// - `tick`: Current tick count from `Spwm::ticks()`
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidFrequency` if the rate is higher than the hardware timer
+    /// frequency.
+    pub fn set_rate<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        microsteps_per_sec: i32,
+        tick: u64,
+    ) -> Result<(), SpwmError> {
+        let interval_ticks = spwm
+            .freq_hz
+            .checked_div(microsteps_per_sec.unsigned_abs())
+            .unwrap_or(0);
+
+        if microsteps_per_sec != 0 && interval_ticks == 0 {
+            return Err(SpwmError::InvalidFrequency);
+        }
+
+        self.direction = if microsteps_per_sec < 0 {
+            StepDirection::Reverse
+        } else {
+            StepDirection::Forward
+        };
+        self.interval_ticks = u64::from(interval_ticks);
+        self.next_tick = tick.saturating_add(self.interval_ticks);
+
+        Ok(())
+    }
+
+    /// Performs the steps due at `tick` at the rate set with `set_rate()`.
+    ///
+    /// Steps missed since the last call are caught up at once, so the position stays exact
+    /// even if the call is late.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager the channels are registered with
+    /// - `tick`: Current tick count from `Spwm::ticks()`
+    ///
+    /// # Returns
+    /// The number of microsteps performed.
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if a channel was removed from `spwm`.
+    pub fn poll<const N: usize, const A: usize>(
+        &mut self,
+        spwm: &Spwm<N, A>,
+        tick: u64,
+    ) -> Result<u64, SpwmError> {
+        let Some(steps) = tick
+            .checked_sub(self.next_tick)
+            .and_then(|late| late.checked_div(self.interval_ticks))
+            .map(|missed| missed.saturating_add(1))
+        else {
+            return Ok(0);
+        };
+
+        self.next_tick = self
+            .next_tick
+            .saturating_add(steps.saturating_mul(self.interval_ticks));
+        self.advance(self.direction, i64::try_from(steps).unwrap_or(i64::MAX));
+        self.apply(spwm)?;
+
+        Ok(steps)
+    }
+
+    /// Moves the position by `steps` microsteps in `direction`.
+    fn advance(&mut self, direction: StepDirection, steps: i64) {
+        self.position = match direction {
+            StepDirection::Forward => self.position.saturating_add(steps),
+            StepDirection::Reverse => self.position.saturating_sub(steps),
+        };
+    }
+
+    /// Returns the signed coil levels in percent for the current position.
+    fn coil_levels(&self) -> (i16, i16) {
+        let table_step = i64::from(MAX_MICROSTEPS.checked_div(self.microsteps).unwrap_or(1));
+        let angle = self
+            .position
+            .wrapping_mul(table_step)
+            .rem_euclid(ELECTRICAL_POSITIONS);
+        let quarter = i64::from(MAX_MICROSTEPS);
+
+        (sine(angle), sine(angle.wrapping_add(quarter)))
+    }
+
+    /// Sets the coil channel duty cycles for the current position.
+    fn apply<const N: usize, const A: usize>(&self, spwm: &Spwm<N, A>) -> Result<(), SpwmError> {
+        let (a, b) = self.coil_levels();
+        let duty_cycles = match self.outputs {
+            StepperOutputs::Amplitude { .. } => [magnitude(a), magnitude(b), 0, 0],
+            StepperOutputs::Bridge { .. } => [
+                magnitude(a.max(0)),
+                magnitude(a.min(0)),
+                magnitude(b.max(0)),
+                magnitude(b.min(0)),
+            ],
+        };
+
+        let (ids, count) = self.channel_ids();
+
+        for (id, duty_cycle) in ids.into_iter().zip(duty_cycles).take(count) {
+            spwm.get_channel(id)
+                .ok_or(SpwmError::InvalidChannel)?
+                .update_duty_cycle(duty_cycle)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the coil channel identifiers and their number.
+    fn channel_ids(&self) -> ([ChannelId; 4], usize) {
+        match self.outputs {
+            StepperOutputs::Amplitude { a, b } => ([a, b, a, b], 2),
+            StepperOutputs::Bridge {
+                a_pos,
+                a_neg,
+                b_pos,
+                b_neg,
+            } => ([a_pos, a_neg, b_pos, b_neg], 4),
+        }
+    }
+}
+
+/// Returns `sin(angle * 90° / 16)` in percent for an angle in table positions.
+fn sine(angle: i64) -> i16 {
+    let angle = angle.rem_euclid(ELECTRICAL_POSITIONS);
+    let quarter = i64::from(MAX_MICROSTEPS);
+    let (index, negative) = match angle.checked_div(quarter).unwrap_or(0) {
+        0 => (angle, false),
+        1 => (quarter.saturating_mul(2).saturating_sub(angle), false),
+        2 => (angle.saturating_sub(quarter.saturating_mul(2)), true),
+        _ => (ELECTRICAL_POSITIONS.saturating_sub(angle), true),
+    };
+    let level = usize::try_from(index)
+        .ok()
+        .and_then(|index| QUARTER_SINE.get(index))
+        .map_or(0, |&level| i16::from(level));
+
+    if negative {
+        level.saturating_neg()
+    } else {
+        level
+    }
+}
+
+/// Returns the magnitude of a coil level as duty cycle.
+fn magnitude(level: i16) -> u8 {
+    u8::try_from(level.unsigned_abs()).unwrap_or(100)
+}
@@ -0,0 +1,83 @@
+//! Telemetry of channel events.
+//!
+//! `irq_handler()` mirrors every output edge and period end into compact records passed to a
+//! user-provided sink, which may print them, push them into a ring buffer or store them for
+//! post-mortem analysis of the output behavior in the field.
+
+use crate::{ChannelId, Spwm};
+use core::sync::atomic::Ordering;
+
+/// Channel event captured by telemetry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TelemetryEvent {
+    /// The output switched on
+    On,
+    /// The output switched off
+    Off,
+    /// The channel completed a period
+    PeriodEnd,
+}
+
+/// A single telemetry record.
+///
+/// # Fields
+/// - `channel`: Identifier of the channel that produced the event
+/// - `tick`: Hardware timer tick processed by `irq_handler()` that produced the event (wrapping)
+/// - `event`: The captured event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelemetryRecord {
+    pub channel: ChannelId,
+    pub tick: u32,
+    pub event: TelemetryEvent,
+}
+
+/// Sink receiving telemetry records from `irq_handler()`.
+///
+/// # Parameters
+/// - `record`: The captured event record
+pub type TelemetryCallback = fn(&TelemetryRecord);
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Sets the sink receiving telemetry records, or removes it with `None`.
+    ///
+    /// The sink is called from `irq_handler()`, so it must be short, e.g. push the record into
+    /// a lock-free ring buffer drained by the application. A period end is recorded before the
+    /// output edge starting the next period. Output changes made outside of `irq_handler()`
+    /// (e.g. by `enable()` or `pause()`) are not recorded.
+    ///
+    /// # Parameters
+    /// - `telemetry`: Telemetry sink
+    pub fn set_telemetry(&mut self, telemetry: Option<TelemetryCallback>) {
+        self.telemetry = telemetry;
+    }
+
+    /// Records the events a channel produced on `tick`.
+    pub(crate) fn record_telemetry(
+        &self,
+        id: ChannelId,
+        tick: u32,
+        was_on: bool,
+        period_end: bool,
+    ) {
+        let (Some(telemetry), Some(channel)) = (self.telemetry, self.get_channel(id)) else {
+            return;
+        };
+        let record = |event| {
+            telemetry(&TelemetryRecord {
+                channel: id,
+                tick,
+                event,
+            });
+        };
+
+        if period_end {
+            record(TelemetryEvent::PeriodEnd);
+        }
+
+        match (was_on, channel.output_on.load(Ordering::Relaxed)) {
+            (false, true) => record(TelemetryEvent::On),
+            (true, false) => record(TelemetryEvent::Off),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Waveform assertions for black-box tests of a PWM configuration.
+//!
+//! A [`Trace`] records the output level of every registered channel after each
+//! `irq_handler()` call. The assertion helpers measure the recorded waveforms and panic with a
+//! descriptive message when a measurement is out of the expected range.
+//!
+//! [`Trace::capture_with_faults()`] additionally injects interrupt latency and missed ticks
+//! described by [`TickFaults`], to evaluate how a chosen tick frequency behaves under realistic
+//! interrupt timing and verify worst-case duty cycle error bounds.
+//!
+//! ```
+//! # use spwm::Spwm;
+//! # use spwm::test_util::{Trace, assert_duty_within};
+//! let mut spwm = Spwm::<1>::new(100_000);
+//! let channel = spwm.create_channel()
+//!     .freq_hz(1_000)
+//!     .duty_cycle(50)
+//!     .on_off_callback(|_| {})
+//!     .period_callback(|| {})
+//!     .build()
+//!     .unwrap();
+//! let id = spwm.register_channel(channel).unwrap();
+//! spwm.get_channel(id).unwrap().enable().unwrap();
+//!
+//! let trace = Trace::capture(&spwm, 1_000);
+//!
+//! assert_duty_within(&trace, id, 49.0..=51.0);
+//! ```
+
+#[cfg(feature = "proptest")]
+pub mod strategies;
+
+use crate::{ChannelId, Spwm, SpwmError};
+use core::ops::RangeInclusive;
+use core::sync::atomic::Ordering;
+use std::vec::Vec;
+
+/// Parameters of a single PWM channel setup.
+///
+/// # Fields
+/// - `hardware_freq_hz`: Hardware timer frequency in Hz
+/// - `freq_hz`: Channel frequency in Hz
+/// - `duty_cycle`: Duty cycle percentage (0-100)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PwmParams {
+    pub hardware_freq_hz: u32,
+    pub freq_hz: u32,
+    pub duty_cycle: u8,
+}
+
+/// Reference model of the waveform a channel should produce.
+///
+/// The model applies the documented quantization (the period is `hardware_freq_hz / freq_hz`
+/// ticks, the on-time is the duty cycle share of the period rounded down) and keeps the output
+/// high for exactly `on_ticks` ticks at the start of every period.
+///
+/// # Fields
+/// - `period_ticks`: Period length in ticks
+/// - `on_ticks`: On-time in ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceModel {
+    pub period_ticks: u32,
+    pub on_ticks: u32,
+}
+
+impl ReferenceModel {
+    /// Creates the reference model for `params`.
+    #[must_use]
+    pub fn new(params: &PwmParams) -> Self {
+        let period_ticks = params
+            .hardware_freq_hz
+            .checked_div(params.freq_hz)
+            .unwrap_or(0)
+            .max(2);
+        let on_ticks = u64::from(period_ticks)
+            .saturating_mul(u64::from(params.duty_cycle.min(100)))
+            .div_euclid(100);
+        let on_ticks = u32::try_from(on_ticks).unwrap_or(period_ticks);
+
+        Self {
+            period_ticks,
+            on_ticks,
+        }
+    }
+
+    /// Returns the expected output level after `tick` ticks since the channel was enabled.
+    #[must_use]
+    pub fn level(&self, tick: u32) -> bool {
+        tick.checked_rem(self.period_ticks)
+            .is_some_and(|elapsed| elapsed < self.on_ticks)
+    }
+}
+
+/// Difference between the output of `irq_handler()` and the reference model.
+#[derive(Debug, PartialEq)]
+pub enum ModelMismatch {
+    /// The channel could not be created from the parameters
+    Rejected(SpwmError),
+    /// The output level differs from the model after `tick` ticks
+    Level { tick: u32, expected: bool },
+}
+
+/// Runs a channel created from `params` for `periods` periods and compares its output with the
+/// [`ReferenceModel`] after every tick.
+///
+/// # Errors
+/// Returns the first difference between the channel output and the model.
+pub fn check_against_model(params: &PwmParams, periods: u32) -> Result<(), ModelMismatch> {
+    let model = ReferenceModel::new(params);
+    let mut spwm = Spwm::<1>::new(params.hardware_freq_hz);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(params.freq_hz)
+        .duty_cycle(params.duty_cycle)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .map_err(ModelMismatch::Rejected)?;
+    let id = spwm
+        .register_channel(channel)
+        .map_err(ModelMismatch::Rejected)?;
+    spwm.get_channel(id)
+        .ok_or(ModelMismatch::Rejected(SpwmError::InvalidChannel))?
+        .enable()
+        .map_err(ModelMismatch::Rejected)?;
+
+    let trace = Trace::capture(&spwm, model.period_ticks.saturating_mul(periods));
+
+    (1..)
+        .zip(trace.levels(id).unwrap_or_default())
+        .find(|&(tick, &level)| level != model.level(tick))
+        .map_or(Ok(()), |(tick, &level)| {
+            Err(ModelMismatch::Level {
+                tick,
+                expected: !level,
+            })
+        })
+}
+
+/// Interrupt timing faults injected by [`Trace::capture_with_faults()`].
+///
+/// The faults are drawn from a pseudo-random generator seeded with `seed`, so a capture is
+/// reproducible.
+///
+/// # Fields
+/// - `max_jitter`: Maximum interrupt latency as a fraction of the tick period (0.0-1.0); the
+///   outputs of a tick change after a latency uniformly distributed up to this value
+/// - `drop_probability`: Probability of a tick interrupt being missed (0.0-1.0); a missed tick
+///   is lost, so the channels fall one tick behind
+/// - `seed`: Seed of the pseudo-random generator
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TickFaults {
+    pub max_jitter: f64,
+    pub drop_probability: f64,
+    pub seed: u64,
+}
+
+/// Output levels of all channels captured over a number of ticks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    levels: Vec<Option<Vec<bool>>>,
+    times: Vec<f64>,
+    dropped_ticks: u32,
+}
+
+impl Trace {
+    /// Calls `irq_handler()` `ticks` times and records the output level of every registered
+    /// channel after each call.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager to run
+    /// - `ticks`: Number of ticks to capture
+    #[must_use]
+    pub fn capture<const N: usize, const A: usize>(spwm: &Spwm<N, A>, ticks: u32) -> Self {
+        Self::capture_with_faults(spwm, ticks, &TickFaults::default())
+    }
+
+    /// Captures `ticks` ticks like [`Trace::capture()`] while injecting interrupt faults.
+    ///
+    /// # Parameters
+    /// - `spwm`: SPWM manager to run
+    /// - `ticks`: Number of ticks to capture, including missed ones
+    /// - `faults`: Faults to inject
+    #[must_use]
+    pub fn capture_with_faults<const N: usize, const A: usize>(
+        spwm: &Spwm<N, A>,
+        ticks: u32,
+        faults: &TickFaults,
+    ) -> Self {
+        let mut random = SplitMix64(faults.seed);
+        let mut levels: Vec<Option<Vec<bool>>> = (0..N)
+            .map(|id| spwm.get_channel(id).map(|_| Vec::new()))
+            .collect();
+        let mut times = Vec::new();
+        let mut dropped_ticks = 0u32;
+
+        for tick in 0..ticks {
+            if random.next_f64() < faults.drop_probability {
+                dropped_ticks = dropped_ticks.saturating_add(1);
+                times.push(f64::from(tick));
+            } else {
+                times.push(f64::from(tick) + random.next_f64() * faults.max_jitter);
+                spwm.irq_handler();
+            }
+
+            for (id, channel_levels) in levels.iter_mut().enumerate() {
+                if let (Some(channel_levels), Some(channel)) =
+                    (channel_levels, spwm.get_channel(id))
+                {
+                    channel_levels.push(channel.output_on.load(Ordering::Relaxed));
+                }
+            }
+        }
+
+        Self {
+            levels,
+            times,
+            dropped_ticks,
+        }
+    }
+
+    /// Returns the number of ticks missed during the capture.
+    #[must_use]
+    pub fn dropped_ticks(&self) -> u32 {
+        self.dropped_ticks
+    }
+
+    /// Returns the recorded output levels of a channel, one per tick.
+    ///
+    /// # Parameters
+    /// - `channel`: Channel identifier
+    #[must_use]
+    pub fn levels(&self, channel: ChannelId) -> Option<&[bool]> {
+        self.levels.get(channel)?.as_deref()
+    }
+
+    /// Returns the measured duty cycle of a channel in percent.
+    ///
+    /// The duty cycle is measured over the whole periods between the first and the last rising
+    /// edge, or over the whole trace if the channel has fewer than two rising edges.
+    ///
+    /// # Parameters
+    /// - `channel`: Channel identifier
+    #[must_use]
+    pub fn duty_percent(&self, channel: ChannelId) -> Option<f64> {
+        let levels = self.levels(channel)?;
+        let edges: Vec<usize> = rising_edges(levels).collect();
+
+        match edges.as_slice() {
+            [first, .., last] => self.window_duty_percent(levels, *first, *last),
+            _ => self.window_duty_percent(levels, 0, levels.len()),
+        }
+    }
+
+    /// Returns the measured duty cycle of every whole period of a channel in percent.
+    ///
+    /// A period spans from one rising edge to the next.
+    ///
+    /// # Parameters
+    /// - `channel`: Channel identifier
+    #[must_use]
+    pub fn period_duty_percent(&self, channel: ChannelId) -> Option<Vec<f64>> {
+        let levels = self.levels(channel)?;
+        let edges: Vec<usize> = rising_edges(levels).collect();
+
+        edges
+            .windows(2)
+            .map(|pair| match pair {
+                [start, end] => self.window_duty_percent(levels, *start, *end),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the phase offset of a channel relative to a reference channel in degrees of the
+    /// reference period.
+    ///
+    /// The offset is measured from the first rising edge of `reference` to the next rising edge
+    /// of `channel`; the reference period is the distance between its first two rising edges.
+    ///
+    /// # Parameters
+    /// - `reference`: Reference channel identifier
+    /// - `channel`: Channel identifier
+    #[must_use]
+    pub fn phase_offset_degrees(&self, reference: ChannelId, channel: ChannelId) -> Option<f64> {
+        let mut reference_edges = rising_edges(self.levels(reference)?);
+        let start = reference_edges.next()?;
+        let end = reference_edges.next()?;
+        let edge = rising_edges(self.levels(channel)?).find(|&edge| edge >= start)?;
+        let period = self.time(end) - self.time(start);
+        let offset = (self.time(edge) - self.time(start)) % period;
+
+        Some(offset * 360.0 / period)
+    }
+
+    /// Returns the share of time the output spent high between the samples `start` and `end`.
+    fn window_duty_percent(&self, levels: &[bool], start: usize, end: usize) -> Option<f64> {
+        let duration = self.time(end) - self.time(start);
+
+        if duration <= 0.0 {
+            return None;
+        }
+
+        let high: f64 = (start..end)
+            .zip(levels.get(start..end)?)
+            .filter(|&(_, &level)| level)
+            .map(|(sample, _)| self.time(sample.saturating_add(1)) - self.time(sample))
+            .sum();
+
+        Some(high * 100.0 / duration)
+    }
+
+    /// Returns the time in ticks at which the outputs took the levels of `sample`.
+    fn time(&self, sample: usize) -> f64 {
+        self.times
+            .get(sample)
+            .copied()
+            .unwrap_or_else(|| to_f64(sample))
+    }
+}
+
+/// Asserts that the measured duty cycle of a channel is within `range` percent.
+///
+/// # Panics
+/// Panics if the channel is not in the trace or its duty cycle is out of `range`.
+pub fn assert_duty_within(trace: &Trace, channel: ChannelId, range: RangeInclusive<f64>) {
+    let duty = trace.duty_percent(channel);
+
+    assert!(
+        duty.is_some_and(|duty| range.contains(&duty)),
+        "channel {channel} duty cycle {duty:?}% is not within {range:?}%"
+    );
+}
+
+/// Asserts that the measured duty cycle of every whole period of a channel is within `range`
+/// percent, e.g. to verify the worst-case duty cycle error of a trace captured with faults.
+///
+/// # Panics
+/// Panics if the channel is not in the trace, has no whole period or the duty cycle of a
+/// period is out of `range`.
+pub fn assert_period_duty_within(trace: &Trace, channel: ChannelId, range: RangeInclusive<f64>) {
+    let duty = trace.period_duty_percent(channel).unwrap_or_default();
+    let outlier = duty.iter().find(|duty| !range.contains(duty));
+
+    assert!(
+        !duty.is_empty() && outlier.is_none(),
+        "channel {channel} period duty cycle {outlier:?}% is not within {range:?}% ({} periods)",
+        duty.len()
+    );
+}
+
+/// Asserts that the phase offset of a channel relative to a reference channel is within
+/// `range` degrees.
+///
+/// # Panics
+/// Panics if a channel is not in the trace, has too few rising edges to measure the offset, or
+/// the offset is out of `range`.
+pub fn assert_phase_offset(
+    trace: &Trace,
+    reference: ChannelId,
+    channel: ChannelId,
+    range: RangeInclusive<f64>,
+) {
+    let offset = trace.phase_offset_degrees(reference, channel);
+
+    assert!(
+        offset.is_some_and(|offset| range.contains(&offset)),
+        "channel {channel} phase offset {offset:?}° to channel {reference} is not within {range:?}°"
+    );
+}
+
+/// Returns the ticks of the low-to-high transitions in `levels`.
+fn rising_edges(levels: &[bool]) -> impl Iterator<Item = usize> + '_ {
+    (1..)
+        .zip(levels.windows(2))
+        .filter_map(|(tick, pair)| matches!(pair, [false, true]).then_some(tick))
+}
+
+/// Converts a tick count to `f64` for ratio computations.
+fn to_f64(ticks: usize) -> f64 {
+    u32::try_from(ticks).map_or(f64::from(u32::MAX), f64::from)
+}
+
+/// `SplitMix64` pseudo-random generator used to draw the injected faults.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Returns a uniformly distributed value in `0.0..1.0`.
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        f64::from(u32::try_from(z >> 32).unwrap_or(u32::MAX)) / 4_294_967_296.0
+    }
+}
@@ -0,0 +1,35 @@
+//! `proptest` strategies generating valid channel configurations.
+
+use super::PwmParams;
+use proptest::prelude::*;
+
+/// Generates valid `PwmParams`: a hardware timer frequency between 1 kHz and 10 MHz, a channel
+/// frequency at least 100 times lower with a period of at most 100 000 ticks, and a duty cycle
+/// between 0% and 100%.
+pub fn valid_params() -> impl Strategy<Value = PwmParams> {
+    (1_000..=10_000_000u32, 0..=100u8).prop_flat_map(|(hardware_freq_hz, duty_cycle)| {
+        let max_freq_hz = hardware_freq_hz / 100;
+        let min_freq_hz = (hardware_freq_hz / 100_000).max(1);
+
+        (min_freq_hz..=max_freq_hz).prop_map(move |freq_hz| PwmParams {
+            hardware_freq_hz,
+            freq_hz,
+            duty_cycle,
+        })
+    })
+}
+
+/// Generates `PwmParams` on the edges of the parameter space: the shortest (100 ticks) and
+/// near-shortest periods combined with 0%, 1%, 99% and 100% duty cycles.
+pub fn boundary_params() -> impl Strategy<Value = PwmParams> {
+    (
+        1_000..=10_000_000u32,
+        prop::sample::select(&[0u8, 1, 99, 100][..]),
+        100..=101u32,
+    )
+        .prop_map(|(freq_hz, duty_cycle, ratio)| PwmParams {
+            hardware_freq_hz: freq_hz.saturating_mul(ratio),
+            freq_hz,
+            duty_cycle,
+        })
+}
@@ -0,0 +1,144 @@
+//! 64-bit tick counter built from 32-bit atomics.
+//!
+//! Many microcontrollers (e.g. Cortex-M3) have no 64-bit atomics, so the counter is kept in two
+//! words guarded by a sequence number. `irq_handler()` is the only writer; readers in thread
+//! context retry when the interrupt updated the counter while they were reading it.
+
+use core::sync::atomic::{AtomicU32, Ordering, fence};
+use core::time::Duration;
+
+/// Nanoseconds per second.
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Monotonic 64-bit tick counter with a single writer.
+#[derive(Debug, Default)]
+pub(crate) struct TickCount {
+    sequence: AtomicU32,
+    low: AtomicU32,
+    high: AtomicU32,
+}
+
+impl TickCount {
+    /// Adds `ticks` to the counter (writer side).
+    ///
+    /// # Returns
+    /// The low 32 bits of the new counter value.
+    pub(crate) fn add(&self, ticks: u32) -> u32 {
+        let (low, carry) = self.low.load(Ordering::Relaxed).overflowing_add(ticks);
+
+        self.write(|| {
+            self.low.store(low, Ordering::Relaxed);
+
+            if carry {
+                let high = self.high.load(Ordering::Relaxed).wrapping_add(1);
+                self.high.store(high, Ordering::Relaxed);
+            }
+        });
+
+        low
+    }
+
+    /// Resets the counter to 0 (writer side).
+    pub(crate) fn reset(&self) {
+        self.write(|| {
+            self.low.store(0, Ordering::Relaxed);
+            self.high.store(0, Ordering::Relaxed);
+        });
+    }
+
+    /// Returns the low 32 bits of the counter (writer side).
+    pub(crate) fn low(&self) -> u32 {
+        self.low.load(Ordering::Relaxed)
+    }
+
+    /// Returns the counter value.
+    pub(crate) fn get(&self) -> u64 {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+            let low = self.low.load(Ordering::Relaxed);
+            let high = self.high.load(Ordering::Relaxed);
+
+            fence(Ordering::Acquire);
+
+            if sequence & 1 == 0 && self.sequence.load(Ordering::Relaxed) == sequence {
+                return (u64::from(high) << 32) | u64::from(low);
+            }
+        }
+    }
+
+    /// Runs `update` between two sequence number increments.
+    fn write(&self, update: impl FnOnce()) {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+
+        self.sequence
+            .store(sequence.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        update();
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+    }
+}
+
+/// Converts a number of hardware timer ticks into a duration.
+///
+/// # Parameters
+/// - `ticks`: Number of ticks
+/// - `hardware_freq_hz`: Hardware timer frequency in Hz
+///
+/// # Returns
+/// The duration of `ticks` ticks, or `None` if the hardware timer frequency is 0.
+#[must_use]
+pub fn ticks_to_duration(ticks: u64, hardware_freq_hz: u32) -> Option<Duration> {
+    let freq_hz = u64::from(hardware_freq_hz);
+    let secs = ticks.checked_div(freq_hz)?;
+    let nanos = ticks
+        .checked_rem(freq_hz)?
+        .saturating_mul(NANOS_PER_SEC)
+        .checked_div(freq_hz)?;
+
+    Some(Duration::new(secs, u32::try_from(nanos).ok()?))
+}
+
+/// Converts a duration into a number of hardware timer ticks, rounded down and saturated at
+/// `u64::MAX`.
+///
+/// # Parameters
+/// - `duration`: Duration to convert
+/// - `hardware_freq_hz`: Hardware timer frequency in Hz
+#[must_use]
+pub fn duration_to_ticks(duration: Duration, hardware_freq_hz: u32) -> u64 {
+    let freq_hz = u64::from(hardware_freq_hz);
+    let sub_sec_ticks = u64::from(duration.subsec_nanos())
+        .saturating_mul(freq_hz)
+        .checked_div(NANOS_PER_SEC)
+        .unwrap_or(0);
+
+    duration
+        .as_secs()
+        .saturating_mul(freq_hz)
+        .saturating_add(sub_sec_ticks)
+}
+
+/// Highest supported hardware timer frequency in Hz.
+///
+/// The period of a 1 Hz channel must stay below half the range of the wrapping 32-bit tick
+/// arithmetic used to schedule events.
+pub const MAX_HARDWARE_FREQ_HZ: u32 = (1 << 31) - 1;
+
+/// Returns whether `hardware_freq_hz` is a usable hardware timer frequency (1 Hz up to
+/// `MAX_HARDWARE_FREQ_HZ`).
+///
+/// Being a `const fn`, it turns a bad timer configuration into a compile-time error.
+///
+/// # Example
+/// ```
+/// # use spwm::is_valid_hardware_frequency;
+/// const TIMER_HZ: u32 = 1_000_000;
+/// const _: () = assert!(is_valid_hardware_frequency(TIMER_HZ));
+///
+/// assert!(!is_valid_hardware_frequency(0));
+/// ```
+#[must_use]
+pub const fn is_valid_hardware_frequency(hardware_freq_hz: u32) -> bool {
+    hardware_freq_hz != 0 && hardware_freq_hz <= MAX_HARDWARE_FREQ_HZ
+}
@@ -0,0 +1,42 @@
+//! Burst output for piezo and ultrasonic transducers.
+//!
+//! A channel in burst mode runs a burst of carrier periods, keeps the output off for a quiet
+//! (listening) window and repeats, as the transmit half of ultrasonic ranging does. The quiet
+//! window is timed by the tick engine like a start delay, so it costs no processing while it
+//! runs, and a burst-complete callback marks the start of every listening window.
+
+use crate::SpwmChannel;
+use core::sync::atomic::Ordering;
+
+impl SpwmChannel {
+    /// Counts a completed carrier period of a channel in burst mode.
+    ///
+    /// # Returns
+    /// The quiet window in ticks if the period completed a burst, otherwise 0.
+    pub(crate) fn end_carrier_period(&self) -> u32 {
+        let burst_periods = self.burst_periods.load(Ordering::Relaxed);
+
+        if burst_periods == 0 {
+            return 0;
+        }
+
+        let count = self
+            .burst_period_count
+            .load(Ordering::Relaxed)
+            .saturating_add(1);
+
+        if count < burst_periods {
+            self.burst_period_count.store(count, Ordering::Relaxed);
+
+            return 0;
+        }
+
+        self.burst_period_count.store(0, Ordering::Relaxed);
+
+        if let Some(callback) = self.burst_complete_callback.get() {
+            callback();
+        }
+
+        self.quiet_ticks.load(Ordering::Relaxed)
+    }
+}
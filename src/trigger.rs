@@ -0,0 +1,157 @@
+//! Camera trigger and strobe outputs.
+//!
+//! A trigger channel is configured with a frame rate in mHz and a pulse width in µs instead of
+//! a frequency and a duty cycle percentage. The pulse width is kept in ticks across frame rate
+//! changes and always stays shorter than the period, so every period produces exactly one
+//! pulse.
+
+use crate::channel::MIN_PERIOD_TICKS;
+use crate::{ChannelId, Spwm, SpwmChannel, SpwmError};
+use core::sync::atomic::Ordering;
+
+/// Millihertz per hertz.
+const MILLIHERTZ_PER_HZ: u64 = 1_000;
+
+/// Microseconds per second.
+const MICROS_PER_SEC: u64 = 1_000_000;
+
+/// Divides `value` by `divisor`, rounded to the nearest integer.
+fn div_round(value: u64, divisor: u64) -> Option<u64> {
+    value.checked_add(divisor / 2)?.checked_div(divisor)
+}
+
+/// Converts a frame rate into period ticks.
+///
+/// # Errors
+/// Returns `SpwmError::InvalidFrequency` if the frame rate is 0 or the period is shorter than
+/// 2 ticks or does not fit into 32 bits.
+pub(crate) fn frame_period_ticks(
+    frame_rate_mhz: u32,
+    hardware_freq_hz: u32,
+) -> Result<u32, SpwmError> {
+    div_round(
+        u64::from(hardware_freq_hz).saturating_mul(MILLIHERTZ_PER_HZ),
+        u64::from(frame_rate_mhz),
+    )
+    .and_then(|ticks| u32::try_from(ticks).ok())
+    .filter(|&ticks| ticks >= MIN_PERIOD_TICKS)
+    .ok_or(SpwmError::InvalidFrequency)
+}
+
+/// Converts a pulse width into ticks.
+///
+/// # Errors
+/// Returns `SpwmError::InvalidPulseWidth` if the pulse is shorter than half a tick or not
+/// shorter than `period_ticks`.
+pub(crate) fn pulse_width_ticks(
+    pulse_width_us: u32,
+    hardware_freq_hz: u32,
+    period_ticks: u32,
+) -> Result<u32, SpwmError> {
+    div_round(
+        u64::from(pulse_width_us).saturating_mul(u64::from(hardware_freq_hz)),
+        MICROS_PER_SEC,
+    )
+    .and_then(|ticks| u32::try_from(ticks).ok())
+    .filter(|&ticks| ticks != 0 && ticks < period_ticks)
+    .ok_or(SpwmError::InvalidPulseWidth)
+}
+
+impl SpwmChannel {
+    /// Returns whether the channel is a trigger output.
+    pub(crate) fn is_trigger_output(&self) -> bool {
+        self.trigger_pulse_ticks.load(Ordering::Relaxed) != 0
+    }
+
+    /// Configures the channel as a trigger output with a fixed pulse of `pulse_ticks` ticks.
+    pub(crate) fn set_trigger_output(&self, period_ticks: u32, pulse_ticks: u32) {
+        self.trigger_pulse_ticks
+            .store(pulse_ticks, Ordering::SeqCst);
+        self.set_period_ticks(period_ticks);
+        self.sync_on_ticks();
+    }
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Changes the frame rate of a trigger output, keeping its pulse width.
+    ///
+    /// The new period applies to the period in progress like `SpwmChannel::trim_period()`; the
+    /// pulse in progress is never cut or repeated, so each period still produces exactly one
+    /// pulse.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the trigger channel
+    /// - `frame_rate_mhz`: Frame rate in mHz (e.g. `30_000` for 30 fps)
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidMode` if the channel is not a trigger output
+    /// - `SpwmError::InvalidFrequency` if the period is shorter than 2 ticks
+    /// - `SpwmError::InvalidPulseWidth` if the pulse width is not shorter than the new period
+    pub fn set_frame_rate(
+        &self,
+        channel_id: ChannelId,
+        frame_rate_mhz: u32,
+    ) -> Result<(), SpwmError> {
+        let channel = self.trigger_channel(channel_id)?;
+        let period_ticks = frame_period_ticks(frame_rate_mhz, self.freq_hz)?;
+        let pulse_ticks = channel
+            .trigger_pulse_ticks
+            .load(Ordering::Relaxed)
+            .max(channel.on_ticks.load(Ordering::Relaxed));
+
+        if pulse_ticks >= period_ticks {
+            return Err(SpwmError::InvalidPulseWidth);
+        }
+
+        channel.set_period_ticks(period_ticks);
+
+        Ok(())
+    }
+
+    /// Changes the pulse width of a trigger output from the next period on.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the trigger channel
+    /// - `pulse_width_us`: Pulse width in µs
+    ///
+    /// # Errors
+    /// - `SpwmError::InvalidChannel` if the channel is not registered
+    /// - `SpwmError::InvalidMode` if the channel is not a trigger output
+    /// - `SpwmError::InvalidPulseWidth` if the pulse is shorter than half a tick or not shorter
+    ///   than the period
+    pub fn set_pulse_width(
+        &self,
+        channel_id: ChannelId,
+        pulse_width_us: u32,
+    ) -> Result<(), SpwmError> {
+        let channel = self.trigger_channel(channel_id)?;
+        let pulse_ticks = pulse_width_ticks(
+            pulse_width_us,
+            self.freq_hz,
+            channel.period_ticks.load(Ordering::Relaxed),
+        )?;
+
+        channel
+            .trigger_pulse_ticks
+            .store(pulse_ticks, Ordering::SeqCst);
+        channel.sync_on_ticks();
+
+        Ok(())
+    }
+
+    /// Returns a registered trigger output channel.
+    fn trigger_channel(&self, channel_id: ChannelId) -> Result<&SpwmChannel, SpwmError> {
+        let channel = self
+            .channel_slots
+            .get(channel_id)
+            .and_then(|slot| slot.channel.as_ref())
+            .ok_or(SpwmError::InvalidChannel)?;
+
+        if !channel.is_trigger_output() {
+            return Err(SpwmError::InvalidMode);
+        }
+
+        Ok(channel)
+    }
+}
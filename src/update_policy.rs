@@ -0,0 +1,112 @@
+//! When on-time updates latch.
+//!
+//! By default an on-time update of a running channel waits for the next period boundary while
+//! a disabled channel takes it at once. The update policy changes that per channel: updates can
+//! cut into the period in progress, or be staged and only latched when the channel is enabled,
+//! e.g. to prepare the next profile and start it at an exact moment.
+
+use crate::SpwmChannel;
+use crate::channel::SCHEDULE_CHANGED;
+use core::sync::atomic::Ordering;
+
+/// When duty cycle and on-time updates of a channel latch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum UpdatePolicy {
+    /// Updates apply at once, including the period in progress (see
+    /// `SpwmChannel::update_duty_cycle_immediate()`)
+    Immediate,
+    /// Updates of a running channel apply at the next period boundary, updates of a disabled
+    /// channel at once
+    #[default]
+    NextPeriod,
+    /// Updates are staged and latched when the channel is next enabled; a running channel keeps
+    /// its on-time until it is disabled and enabled again
+    OnEnable,
+}
+
+impl UpdatePolicy {
+    /// Returns the value stored in the channel.
+    fn to_raw(self) -> u8 {
+        match self {
+            UpdatePolicy::NextPeriod => 0,
+            UpdatePolicy::Immediate => 1,
+            UpdatePolicy::OnEnable => 2,
+        }
+    }
+
+    /// Returns the policy of a stored value.
+    fn from_raw(raw: u8) -> Self {
+        match raw {
+            1 => UpdatePolicy::Immediate,
+            2 => UpdatePolicy::OnEnable,
+            _ => UpdatePolicy::NextPeriod,
+        }
+    }
+}
+
+impl SpwmChannel {
+    /// Sets when duty cycle and on-time updates of the channel latch.
+    ///
+    /// Switching away from `UpdatePolicy::OnEnable` applies a staged update like any other
+    /// update under the new policy.
+    ///
+    /// # Parameters
+    /// - `policy`: Update policy
+    pub fn set_update_policy(&self, policy: UpdatePolicy) {
+        self.update_policy.store(policy.to_raw(), Ordering::SeqCst);
+
+        if policy != UpdatePolicy::OnEnable && self.staged_pending.swap(false, Ordering::SeqCst) {
+            self.update_on_ticks(self.staged_on_ticks.load(Ordering::SeqCst));
+        }
+    }
+
+    /// Returns when duty cycle and on-time updates of the channel latch.
+    pub fn update_policy(&self) -> UpdatePolicy {
+        UpdatePolicy::from_raw(self.update_policy.load(Ordering::Relaxed))
+    }
+
+    /// Returns whether an update is staged until the channel is enabled.
+    pub fn has_staged_update(&self) -> bool {
+        self.staged_pending.load(Ordering::Relaxed)
+    }
+
+    /// Latches an on-time update according to the update policy.
+    pub(crate) fn latch_with_policy(&self, on_ticks: u32) {
+        let enabled = self.enabled.load(Ordering::Relaxed);
+
+        match self.update_policy() {
+            UpdatePolicy::OnEnable => {
+                self.staged_on_ticks.store(on_ticks, Ordering::SeqCst);
+                self.staged_pending.store(true, Ordering::SeqCst);
+            }
+            UpdatePolicy::Immediate if enabled => {
+                self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+                self.update_pending.store(false, Ordering::SeqCst);
+                self.set_on_ticks(self.limit_step(self.on_ticks.load(Ordering::SeqCst), on_ticks));
+                self.reschedule(SCHEDULE_CHANGED);
+            }
+            UpdatePolicy::NextPeriod if enabled => {
+                self.note_latched_on_ticks();
+                self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+                self.update_pending.store(true, Ordering::SeqCst);
+            }
+            UpdatePolicy::Immediate | UpdatePolicy::NextPeriod => {
+                self.set_on_ticks(on_ticks);
+                self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+                self.update_pending.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Latches an update staged with `UpdatePolicy::OnEnable` into the period that starts when
+    /// the channel is enabled.
+    pub(crate) fn latch_staged(&self) {
+        if self.staged_pending.swap(false, Ordering::SeqCst) {
+            let on_ticks = self.staged_on_ticks.load(Ordering::SeqCst);
+
+            self.set_on_ticks(on_ticks);
+            self.update_on_ticks.store(on_ticks, Ordering::SeqCst);
+            self.update_pending.store(false, Ordering::SeqCst);
+        }
+    }
+}
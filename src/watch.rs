@@ -0,0 +1,107 @@
+//! Watch window recording the output edges of a single channel.
+//!
+//! Once armed, `irq_handler()` stores the tick of each of the next output edges of the watched
+//! channel into a caller-provided buffer and disarms when the buffer is full. The buffer can
+//! then be dumped over RTT or a serial port to verify the timing on the real device without an
+//! oscilloscope.
+
+use crate::{ChannelId, Spwm, SpwmError};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+/// Armed watch window.
+pub(crate) struct WatchState {
+    /// Watched channel
+    channel: ChannelId,
+    /// Edge ticks recorded so far
+    buffer: &'static [AtomicU32],
+    /// Number of recorded edges
+    recorded: AtomicUsize,
+    /// Whether edges are still recorded
+    armed: AtomicBool,
+}
+
+impl<const N: usize, const A: usize> Spwm<N, A> {
+    /// Arms the watch window for a channel.
+    ///
+    /// Each following output edge of the channel produced by `irq_handler()` stores its tick
+    /// (the low 32 bits of `ticks()`) into the next `buffer` entry until the buffer is full,
+    /// then the window disarms. Edges alternate between on and off, starting with the opposite of the
+    /// output state at the time of arming. Arming again restarts recording from the first entry.
+    ///
+    /// # Parameters
+    /// - `channel_id`: The identifier of the watched channel
+    /// - `buffer`: Buffer receiving the edge ticks, e.g. a `static` array
+    ///
+    /// # Errors
+    /// Returns `SpwmError::InvalidChannel` if the channel is not registered.
+    pub fn arm_watch(
+        &mut self,
+        channel_id: ChannelId,
+        buffer: &'static [AtomicU32],
+    ) -> Result<(), SpwmError> {
+        if self.get_channel(channel_id).is_none() {
+            return Err(SpwmError::InvalidChannel);
+        }
+
+        self.watch = Some(WatchState {
+            channel: channel_id,
+            buffer,
+            recorded: AtomicUsize::new(0),
+            armed: AtomicBool::new(!buffer.is_empty()),
+        });
+
+        Ok(())
+    }
+
+    /// Stops recording; the edges recorded so far stay in the buffer.
+    pub fn disarm_watch(&mut self) {
+        if let Some(watch) = &self.watch {
+            watch.armed.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns whether the watch window still records edges.
+    #[must_use]
+    pub fn is_watch_armed(&self) -> bool {
+        self.watch
+            .as_ref()
+            .is_some_and(|watch| watch.armed.load(Ordering::SeqCst))
+    }
+
+    /// Returns the number of edges recorded into the buffer since the window was armed.
+    #[must_use]
+    pub fn watch_count(&self) -> usize {
+        self.watch
+            .as_ref()
+            .map_or(0, |watch| watch.recorded.load(Ordering::SeqCst))
+    }
+
+    /// Records an output edge a channel produced on `tick`.
+    pub(crate) fn record_watch(&self, id: ChannelId, tick: u32, was_on: bool) {
+        let Some(watch) = &self.watch else {
+            return;
+        };
+
+        if watch.channel != id
+            || !watch.armed.load(Ordering::SeqCst)
+            || self
+                .get_channel(id)
+                .is_none_or(|channel| channel.output_on.load(Ordering::Relaxed) == was_on)
+        {
+            return;
+        }
+
+        let recorded = watch.recorded.load(Ordering::SeqCst);
+
+        if let Some(entry) = watch.buffer.get(recorded) {
+            entry.store(tick, Ordering::Relaxed);
+            watch
+                .recorded
+                .store(recorded.saturating_add(1), Ordering::SeqCst);
+        }
+
+        if recorded.saturating_add(1) >= watch.buffer.len() {
+            watch.armed.store(false, Ordering::SeqCst);
+        }
+    }
+}
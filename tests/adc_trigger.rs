@@ -0,0 +1,107 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use spwm::{AdcTriggerPoint, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static EDGES: Mutex<Vec<(u8, bool, u32)>> = Mutex::new(Vec::new());
+static TICK: AtomicU32 = AtomicU32::new(0);
+
+fn record_edge(channel: u8, state: &SpwmState) {
+    EDGES.lock().unwrap().push((
+        channel,
+        matches!(state, SpwmState::On),
+        TICK.load(Ordering::Relaxed),
+    ));
+}
+
+fn pwm_callback(state: &SpwmState) {
+    record_edge(0, state);
+}
+
+fn trigger_callback(state: &SpwmState) {
+    record_edge(1, state);
+}
+
+fn run(spwm: &Spwm<2>, ticks: u32) -> Vec<(u8, bool, u32)> {
+    EDGES.lock().unwrap().clear();
+
+    for _ in 0..ticks {
+        spwm.irq_handler();
+        TICK.fetch_add(1, Ordering::Relaxed);
+    }
+
+    EDGES.lock().unwrap().clone()
+}
+
+#[test]
+fn adc_trigger_follows_pwm_duty_cycle() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let mut ids = [0; 2];
+
+    for (id, callback) in ids.iter_mut().zip([pwm_callback, trigger_callback]) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    let [pwm_id, trigger_id] = ids;
+
+    assert_eq!(
+        spwm.align_adc_trigger(pwm_id, pwm_id, AdcTriggerPoint::MidOff, 2),
+        Err(SpwmError::InvalidChannel)
+    );
+    assert_eq!(
+        spwm.align_adc_trigger(pwm_id, trigger_id, AdcTriggerPoint::MidOff, 100),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert_eq!(
+        spwm.align_adc_trigger(pwm_id, trigger_id, AdcTriggerPoint::Ticks(100), 2),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert!(
+        spwm.align_adc_trigger(pwm_id, trigger_id, AdcTriggerPoint::MidOff, 2)
+            .is_ok()
+    );
+    assert_eq!(
+        spwm.get_channel(trigger_id).unwrap().update_duty_cycle(10),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+
+    for id in ids {
+        spwm.get_channel(id).unwrap().enable().unwrap();
+    }
+
+    // trigger pulse edges relative to the PWM rising edge of the recorded period
+    let trigger_pulse = |edges: Vec<(u8, bool, u32)>| {
+        let start = edges.iter().find(|edge| edge.0 == 0 && edge.1).unwrap().2;
+        let mut pulse = edges
+            .iter()
+            .filter(|edge| edge.0 == 1 && edge.2 >= start)
+            .map(|edge| (edge.1, edge.2 - start));
+
+        [pulse.next().unwrap(), pulse.next().unwrap()]
+    };
+
+    // let the lock settle for one PWM period
+    run(&spwm, 100);
+    assert_eq!(trigger_pulse(run(&spwm, 200)), [(true, 65), (false, 67)]);
+
+    spwm.get_channel(pwm_id)
+        .unwrap()
+        .update_duty_cycle(50)
+        .unwrap();
+    run(&spwm, 100);
+    assert_eq!(trigger_pulse(run(&spwm, 200)), [(true, 75), (false, 77)]);
+
+    assert!(
+        spwm.align_adc_trigger(pwm_id, trigger_id, AdcTriggerPoint::MidOn, 2)
+            .is_ok()
+    );
+    run(&spwm, 100);
+    assert_eq!(trigger_pulse(run(&spwm, 200)), [(true, 25), (false, 27)]);
+}
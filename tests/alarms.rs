@@ -0,0 +1,148 @@
+use spwm::{Spwm, SpwmError};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[test]
+fn one_shot_alarm_fires_once() {
+    static FIRED: AtomicU32 = AtomicU32::new(0);
+    let mut spwm = Spwm::<1, 2>::new(100_000);
+    let id = spwm
+        .set_alarm(10, || {
+            FIRED.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+    for _ in 0..9 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 0);
+    assert!(spwm.is_alarm_pending(id));
+
+    spwm.irq_handler();
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 1);
+    assert!(!spwm.is_alarm_pending(id));
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 1);
+    assert_eq!(spwm.cancel_alarm(id), Err(SpwmError::InvalidAlarm));
+}
+
+#[test]
+fn periodic_alarm_fires_alongside_channels() {
+    static FIRED: AtomicU32 = AtomicU32::new(0);
+    let mut spwm = Spwm::<1, 1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+    let id = spwm
+        .set_periodic_alarm(7, || {
+            FIRED.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+    for _ in 0..700 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 100);
+    assert!(spwm.is_alarm_pending(id));
+
+    spwm.cancel_alarm(id).unwrap();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 100);
+}
+
+#[test]
+fn periodic_alarm_coalesces_periods_within_divided_tick() {
+    static FIRED: AtomicU32 = AtomicU32::new(0);
+    let mut spwm = Spwm::<1, 1>::new(100_000);
+
+    spwm.set_tick_divider(10).unwrap();
+    spwm.set_periodic_alarm(4, || {
+        FIRED.fetch_add(1, Ordering::Relaxed);
+    })
+    .unwrap();
+
+    // Expirations at ticks 4, 8 | 12, 16, 20 | 24, 28 | 32, 36, 40
+    for _ in 0..4 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn alarm_slots_are_limited_and_reused() {
+    let mut spwm = Spwm::<1, 1>::new(100_000);
+
+    let id = spwm.set_alarm(1, || {}).unwrap();
+    assert_eq!(
+        spwm.set_alarm(1, || {}),
+        Err(SpwmError::NoAlarmSlotAvailable)
+    );
+    assert_eq!(
+        spwm.set_periodic_alarm(0, || {}),
+        Err(SpwmError::InvalidAlarm)
+    );
+    assert_eq!(
+        spwm.set_alarm(u32::MAX, || {}),
+        Err(SpwmError::InvalidAlarm)
+    );
+
+    spwm.irq_handler();
+
+    assert_eq!(spwm.set_alarm(1, || {}), Ok(id));
+    spwm.cancel_alarm(id).unwrap();
+    assert_eq!(spwm.set_periodic_alarm(5, || {}), Ok(id));
+
+    let mut without_alarms = Spwm::<1>::new(100_000);
+
+    assert_eq!(
+        without_alarms.set_alarm(1, || {}),
+        Err(SpwmError::NoAlarmSlotAvailable)
+    );
+}
+
+#[test]
+fn alarms_hold_while_suspended() {
+    static FIRED: AtomicU32 = AtomicU32::new(0);
+    let mut spwm = Spwm::<1, 1>::new(100_000);
+
+    spwm.set_alarm(5, || {
+        FIRED.fetch_add(1, Ordering::Relaxed);
+    })
+    .unwrap();
+
+    for _ in 0..4 {
+        spwm.irq_handler();
+    }
+
+    spwm.suspend().unwrap();
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 0);
+
+    spwm.resume().unwrap();
+    spwm.irq_handler();
+
+    assert_eq!(FIRED.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,145 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use spwm::{LineCode, PulseTiming, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static TEST_ON_OFF: AtomicBool = AtomicBool::new(false);
+static TEST_COMPLETE: AtomicU32 = AtomicU32::new(0);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn on_off_test_callback(state: &SpwmState) {
+    TEST_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+fn transmit_complete_test_callback() {
+    TEST_COMPLETE.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Transmits `data` at 500 bit/s with a 100 kHz tick and samples the output in the middle of
+/// each half-bit.
+fn transmit_and_sample(data: &'static [u8], code: LineCode) -> Vec<u8> {
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_COMPLETE.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(10)
+        .duty_cycle(0)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .transmit_complete_callback(transmit_complete_test_callback)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.set_bit_rate(500, 100_000).is_ok());
+    assert!(channel.enable().is_ok());
+    assert!(channel.transmit(data, code).is_ok());
+    assert!(channel.is_transmitting());
+    assert_eq!(channel.transmit(data, code), Err(SpwmError::Busy));
+
+    let half_bits = data.len() * 16;
+    let mut levels = Vec::with_capacity(half_bits);
+
+    for tick in 0..(half_bits + 2) * 100 {
+        spwm.irq_handler();
+
+        if tick % 100 == 49 && tick > 100 && levels.len() < half_bits {
+            levels.push(u8::from(TEST_ON_OFF.load(Ordering::Relaxed)));
+        }
+    }
+
+    assert!(!channel.is_transmitting());
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+    assert_eq!(TEST_COMPLETE.load(Ordering::Relaxed), 1);
+
+    levels
+}
+
+#[test]
+fn manchester_transmission() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let levels = transmit_and_sample(&[0b1010_0000], LineCode::Manchester);
+
+    assert_eq!(levels, [0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0]);
+}
+
+#[test]
+fn biphase_mark_transmission() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let levels = transmit_and_sample(&[0b1010_0000], LineCode::BiphaseMark);
+
+    assert_eq!(levels, [1, 0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0]);
+}
+
+#[test]
+fn pulse_width_transmission() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_COMPLETE.store(0, Ordering::Relaxed);
+
+    let timing = PulseTiming {
+        bit_ns: 1_250,
+        t0h_ns: 400,
+        t1h_ns: 800,
+        tolerance_ns: 150,
+    };
+    let mut spwm = Spwm::<1>::new(20_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(0)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .transmit_complete_callback(transmit_complete_test_callback)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(
+        channel.transmit(&[0x00], LineCode::PulseWidth),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert_eq!(
+        channel.set_pulse_timing(&timing, 1_000_000),
+        Err(SpwmError::TimingToleranceExceeded)
+    );
+    assert_eq!(
+        channel.set_pulse_timing(
+            &PulseTiming {
+                t1h_ns: 400,
+                ..timing
+            },
+            20_000_000
+        ),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert!(channel.set_pulse_timing(&timing, 20_000_000).is_ok());
+    assert!(channel.enable().is_ok());
+    assert!(
+        channel
+            .transmit(&[0b1001_0000], LineCode::PulseWidth)
+            .is_ok()
+    );
+
+    // 25 ticks per bit, 8 ticks for `0` and 16 ticks for `1`
+    let mut high_ticks = Vec::new();
+    let mut current = 0;
+
+    for _ in 0..11 * 25 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            current += 1;
+        } else if current != 0 {
+            high_ticks.push(current);
+            current = 0;
+        }
+    }
+
+    assert_eq!(high_ticks, [16, 8, 8, 16, 8, 8, 8, 8]);
+    assert_eq!(TEST_COMPLETE.load(Ordering::Relaxed), 1);
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+}
@@ -0,0 +1,376 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use spwm::{ChainMode, OnOffCallback, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static SLAVE_ON_EDGES: AtomicU32 = AtomicU32::new(0);
+static SLAVE_PERIODS: AtomicU32 = AtomicU32::new(0);
+
+fn slave_on_off_callback(state: &SpwmState) {
+    if matches!(state, SpwmState::On) {
+        SLAVE_ON_EDGES.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn slave_period_callback() {
+    SLAVE_PERIODS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn chained_channel_runs_every_nth_master_period() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let master = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let slave = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(10)
+        .on_off_callback(slave_on_off_callback)
+        .period_callback(slave_period_callback)
+        .build()
+        .unwrap();
+    let master_id = spwm.register_channel(master).unwrap();
+    let slave_id = spwm.register_channel(slave).unwrap();
+
+    assert_eq!(
+        spwm.chain(master_id, slave_id, ChainMode::EveryNthPeriod(0)),
+        Err(SpwmError::InvalidChainMode)
+    );
+    assert_eq!(
+        spwm.chain(master_id, master_id, ChainMode::EveryPeriod),
+        Err(SpwmError::InvalidChannel)
+    );
+    assert_eq!(
+        spwm.chain(master_id, 2, ChainMode::EveryPeriod),
+        Err(SpwmError::InvalidChannel)
+    );
+    assert!(
+        spwm.chain(master_id, slave_id, ChainMode::EveryNthPeriod(4))
+            .is_ok()
+    );
+
+    spwm.get_channel(master_id).unwrap().enable().unwrap();
+    spwm.get_channel(slave_id).unwrap().enable().unwrap();
+    assert_eq!(SLAVE_ON_EDGES.load(Ordering::Relaxed), 0);
+
+    for _ in 0..850 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(SLAVE_ON_EDGES.load(Ordering::Relaxed), 2);
+    assert_eq!(SLAVE_PERIODS.load(Ordering::Relaxed), 1);
+
+    assert!(spwm.unchain(slave_id).is_ok());
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(SLAVE_PERIODS.load(Ordering::Relaxed), 11);
+}
+
+static LOCKED_PERIODS: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn chain_rejects_cycles() {
+    let mut spwm = Spwm::<3>::new(100_000);
+    let mut ids = [0; 3];
+
+    for id in &mut ids {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(50)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    let [a, b, c] = ids;
+
+    spwm.chain(a, b, ChainMode::EveryPeriod).unwrap();
+    spwm.chain(b, c, ChainMode::EveryPeriod).unwrap();
+
+    assert_eq!(
+        spwm.chain(b, a, ChainMode::EveryPeriod),
+        Err(SpwmError::InvalidMode)
+    );
+    assert_eq!(
+        spwm.chain(c, a, ChainMode::EveryNthPeriod(2)),
+        Err(SpwmError::InvalidMode)
+    );
+
+    // Re-chaining a slave to a channel that is not driven by it is still allowed
+    spwm.chain(a, c, ChainMode::EveryPeriod).unwrap();
+    spwm.chain(c, b, ChainMode::EveryPeriod).unwrap();
+}
+
+fn locked_period_callback() {
+    LOCKED_PERIODS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn ratio_locked_channel_does_not_drift() {
+    let mut spwm = Spwm::<2>::new(33_333);
+    let master = spwm
+        .create_channel()
+        .freq_hz(333)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let slave = spwm
+        .create_channel()
+        .freq_hz(33)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(locked_period_callback)
+        .build()
+        .unwrap();
+    let master_id = spwm.register_channel(master).unwrap();
+    let slave_id = spwm.register_channel(slave).unwrap();
+
+    assert_eq!(
+        spwm.lock_ratio(master_id, slave_id, 0),
+        Err(SpwmError::InvalidChainMode)
+    );
+    assert!(spwm.lock_ratio(master_id, slave_id, 10).is_ok());
+
+    spwm.get_channel(master_id).unwrap().enable().unwrap();
+    spwm.get_channel(slave_id).unwrap().enable().unwrap();
+
+    // master period is 100 ticks, while the unlocked slave period would be 1010 ticks
+    for _ in 0..100_000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(LOCKED_PERIODS.load(Ordering::Relaxed), 100);
+}
+
+static INTERLEAVE_EDGES: Mutex<Vec<(u8, u32)>> = Mutex::new(Vec::new());
+static INTERLEAVE_TICK: AtomicU32 = AtomicU32::new(0);
+
+fn record_on_edge(channel: u8, state: &SpwmState) {
+    if matches!(state, SpwmState::On) {
+        INTERLEAVE_EDGES
+            .lock()
+            .unwrap()
+            .push((channel, INTERLEAVE_TICK.load(Ordering::Relaxed)));
+    }
+}
+
+fn interleave_callback_0(state: &SpwmState) {
+    record_on_edge(0, state);
+}
+
+fn interleave_callback_1(state: &SpwmState) {
+    record_on_edge(1, state);
+}
+
+fn interleave_callback_2(state: &SpwmState) {
+    record_on_edge(2, state);
+}
+
+#[test]
+fn interleaved_channels_keep_phase_offsets() {
+    let mut spwm = Spwm::<3>::new(120_000);
+    let callbacks: [OnOffCallback; 3] = [
+        interleave_callback_0,
+        interleave_callback_1,
+        interleave_callback_2,
+    ];
+    let mut ids = [0; 3];
+
+    for (id, callback) in ids.iter_mut().zip(callbacks) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    assert_eq!(spwm.interleave(&[]), Err(SpwmError::InvalidChannel));
+    assert_eq!(
+        spwm.interleave(&[ids[0], ids[0]]),
+        Err(SpwmError::InvalidChannel)
+    );
+    assert!(spwm.interleave(&ids).is_ok());
+
+    for id in ids {
+        spwm.get_channel(id).unwrap().enable().unwrap();
+    }
+
+    let run = |ticks: u32| {
+        INTERLEAVE_EDGES.lock().unwrap().clear();
+
+        for _ in 0..ticks {
+            spwm.irq_handler();
+            INTERLEAVE_TICK.fetch_add(1, Ordering::Relaxed);
+        }
+
+        INTERLEAVE_EDGES.lock().unwrap().clone()
+    };
+
+    let phase_offsets = |edges: Vec<(u8, u32)>, period: u32| {
+        let tick = |channel| edges.iter().find(|edge| edge.0 == channel).unwrap().1;
+        let reference = tick(0);
+
+        [1, 2].map(|channel| (tick(channel) + period - reference) % period)
+    };
+
+    // let the phase lock settle for one reference period
+    run(120);
+    assert_eq!(phase_offsets(run(120), 120), [40, 80]);
+
+    spwm.get_channel(ids[0])
+        .unwrap()
+        .update_frequency(500, 120_000)
+        .unwrap();
+    run(480);
+    assert_eq!(phase_offsets(run(240), 240), [80, 160]);
+}
+
+static QUADRATURE_EDGES: Mutex<Vec<(u8, bool, u32)>> = Mutex::new(Vec::new());
+static QUADRATURE_TICK: AtomicU32 = AtomicU32::new(0);
+
+fn record_quadrature_edge(channel: u8, state: &SpwmState) {
+    QUADRATURE_EDGES.lock().unwrap().push((
+        channel,
+        matches!(state, SpwmState::On),
+        QUADRATURE_TICK.load(Ordering::Relaxed),
+    ));
+}
+
+fn quadrature_callback_a(state: &SpwmState) {
+    record_quadrature_edge(0, state);
+}
+
+fn quadrature_callback_b(state: &SpwmState) {
+    record_quadrature_edge(1, state);
+}
+
+#[test]
+fn quadrature_pair_is_shifted_by_quarter_period() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let a = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(10)
+        .on_off_callback(quadrature_callback_a)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let b = spwm
+        .create_channel()
+        .freq_hz(200)
+        .duty_cycle(90)
+        .on_off_callback(quadrature_callback_b)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let a_id = spwm.register_channel(a).unwrap();
+    let b_id = spwm.register_channel(b).unwrap();
+
+    assert_eq!(spwm.quadrature(a_id, a_id), Err(SpwmError::InvalidChannel));
+    assert!(spwm.quadrature(a_id, b_id).is_ok());
+    assert_eq!(spwm.get_channel(a_id).unwrap().duty_cycle(), 50);
+    assert_eq!(spwm.get_channel(b_id).unwrap().duty_cycle(), 50);
+
+    spwm.get_channel(a_id).unwrap().enable().unwrap();
+    spwm.get_channel(b_id).unwrap().enable().unwrap();
+
+    for _ in 0..200 {
+        spwm.irq_handler();
+        QUADRATURE_TICK.fetch_add(1, Ordering::Relaxed);
+    }
+
+    QUADRATURE_EDGES.lock().unwrap().clear();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+        QUADRATURE_TICK.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let edges = QUADRATURE_EDGES.lock().unwrap().clone();
+    let tick = |channel, on| {
+        edges
+            .iter()
+            .find(|edge| edge.0 == channel && edge.1 == on)
+            .unwrap()
+            .2
+    };
+
+    assert_eq!((tick(1, true) + 100 - tick(0, true)) % 100, 25);
+    assert_eq!((tick(1, false) + 100 - tick(0, false)) % 100, 25);
+    assert_eq!(
+        (tick(0, false) + 100 - tick(0, true)) % 100,
+        (tick(1, false) + 100 - tick(1, true)) % 100
+    );
+}
+
+static STALLED_SLAVE_ON_EDGES: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn stalled_locked_channel_survives_counter_saturation() {
+    let mut spwm = Spwm::<2>::new(102_400_000);
+    let master = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let master_id = spwm.register_channel(master).unwrap();
+    let slave = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(25)
+        .on_off_callback(|state| {
+            if matches!(state, SpwmState::On) {
+                STALLED_SLAVE_ON_EDGES.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .period_callback(|| {})
+        .pulse_offset_ticks(51_200)
+        .build()
+        .unwrap();
+    let slave_id = spwm.register_channel(slave).unwrap();
+
+    spwm.lock_ratio(master_id, slave_id, 1).unwrap();
+    spwm.get_channel(slave_id).unwrap().enable().unwrap();
+
+    // Without a running master the slave never gets its trigger and its period counter runs
+    // past 2^32 ticks
+    spwm.set_tick_divider(spwm.max_tick_divider()).unwrap();
+    assert_eq!(spwm.tick_divider(), 25_600);
+
+    for _ in 0..167_780 {
+        spwm.irq_handler();
+    }
+
+    assert!(spwm.ticks() > u64::from(u32::MAX));
+    assert_eq!(STALLED_SLAVE_ON_EDGES.load(Ordering::Relaxed), 1);
+
+    // Once the master runs, the slave resumes on its triggers
+    spwm.set_tick_divider(1).unwrap();
+    spwm.get_channel(master_id).unwrap().enable().unwrap();
+
+    for _ in 0..3 * 102_400 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(STALLED_SLAVE_ON_EDGES.load(Ordering::Relaxed), 3);
+}
@@ -0,0 +1,1471 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use spwm::{
+    AppliedUpdate, FLICKER_SAFE_MIN_HZ, MAX_HARDWARE_FREQ_HZ, OutputWaveform, PendingUpdate,
+    PeriodCallbackTiming, ProtectionProfile, ProtectionViolation, Spwm, SpwmChannel,
+    SpwmChannelBuilder, SpwmError, SpwmState, UpdatePolicy, assert_flicker_safe,
+};
+use std::sync::Mutex;
+
+static TEST_ON_OFF: AtomicBool = AtomicBool::new(false);
+static TEST_ON_EDGES: AtomicU32 = AtomicU32::new(0);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn on_off_test_callback(state: &SpwmState) {
+    let on = matches!(state, SpwmState::On);
+
+    if on && !TEST_ON_OFF.load(Ordering::Relaxed) {
+        TEST_ON_EDGES.fetch_add(1, Ordering::Relaxed);
+    }
+
+    TEST_ON_OFF.store(on, Ordering::Relaxed);
+}
+
+fn test_create_pwm_channel(
+    hardware_freq_hz: u32,
+    channel_freq_hz: u32,
+    duty_cycle: u8,
+) -> SpwmChannel {
+    Spwm::<1>::new(hardware_freq_hz)
+        .create_channel()
+        .freq_hz(channel_freq_hz)
+        .duty_cycle(duty_cycle)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn modify_duty_read_modify_write() {
+    let channel = test_create_pwm_channel(100_000, 1000, 50);
+
+    let result = channel.modify_duty(|current| current / 2);
+    assert_eq!(result, Ok(50));
+    assert_eq!(channel.duty_cycle(), 25);
+
+    let result = channel.modify_duty(|current| current + 75);
+    assert_eq!(result, Ok(25));
+    assert_eq!(channel.duty_cycle(), 100);
+}
+
+#[test]
+fn modify_duty_rejects_invalid_duty_cycle() {
+    let channel = test_create_pwm_channel(100_000, 1000, 50);
+
+    let result = channel.modify_duty(|current| current + 51);
+    assert_eq!(result, Err(SpwmError::InvalidDutyCycle));
+    assert_eq!(channel.duty_cycle(), 50);
+}
+
+#[test]
+fn increase_decrease_duty_saturate() {
+    let channel = test_create_pwm_channel(100_000, 1000, 90);
+
+    assert_eq!(channel.increase_duty(5), Ok(90));
+    assert_eq!(channel.duty_cycle(), 95);
+    assert_eq!(channel.increase_duty(10), Ok(95));
+    assert_eq!(channel.duty_cycle(), 100);
+    assert_eq!(channel.increase_duty(u8::MAX), Ok(100));
+    assert_eq!(channel.duty_cycle(), 100);
+
+    assert_eq!(channel.decrease_duty(60), Ok(100));
+    assert_eq!(channel.duty_cycle(), 40);
+    assert_eq!(channel.decrease_duty(50), Ok(40));
+    assert_eq!(channel.duty_cycle(), 0);
+}
+
+#[test]
+fn pulse_once_single_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(0)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(channel.pulse_once(0), Err(SpwmError::InvalidPulseWidth));
+    assert_eq!(channel.pulse_once(101), Err(SpwmError::InvalidPulseWidth));
+    assert!(channel.enable().is_ok());
+    assert!(channel.pulse_once(10).is_ok());
+
+    for _ in 0..300 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(TEST_ON_EDGES.load(Ordering::Relaxed), 1);
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+}
+
+#[test]
+fn toggle_enable_switches_state() {
+    let channel = test_create_pwm_channel(100_000, 1000, 50);
+
+    assert!(!channel.is_enabled());
+    assert_eq!(channel.toggle_enable(), Ok(true));
+    assert!(channel.is_enabled());
+    assert_eq!(channel.toggle_enable(), Ok(false));
+    assert!(!channel.is_enabled());
+}
+
+#[test]
+fn duty_cycle_follows_frequency_change() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.update_frequency(500, 100_000).is_ok());
+    assert!(channel.update_duty_cycle(30).is_ok());
+    assert!(channel.enable().is_ok());
+
+    let mut high_ticks = 0;
+
+    for _ in 0..200 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            high_ticks += 1;
+        }
+    }
+
+    // 60 on-time ticks of a 200-tick period: the Off edge comes on the 60th tick and the last
+    // tick carries the next On edge
+    assert_eq!(high_ticks, 60);
+}
+
+#[test]
+fn update_frequency_ratio_limit() {
+    let channel = test_create_pwm_channel(100_000, 1000, 50);
+
+    assert!(channel.update_frequency(1000, 100_000).is_ok());
+    assert_eq!(
+        channel.update_frequency(1001, 100_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        channel.update_frequency(u32::MAX, u32::MAX),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert!(channel.update_frequency(u32::MAX / 100, u32::MAX).is_ok());
+}
+
+#[test]
+fn validate_reports_extreme_duty_cycles() {
+    for (duty_cycle, on_ticks, waveform) in [
+        (0, 0, OutputWaveform::ConstantOff),
+        (1, 1, OutputWaveform::Modulated),
+        (99, 99, OutputWaveform::Modulated),
+        (100, 100, OutputWaveform::ConstantOn),
+    ] {
+        let report = test_create_pwm_channel(100_000, 1000, duty_cycle).validate();
+
+        assert_eq!(report.period_ticks, 100);
+        assert_eq!(report.on_ticks, on_ticks);
+        assert_eq!(report.waveform, waveform);
+        assert!(!report.period_clamped);
+    }
+}
+
+#[test]
+fn validate_reports_exact_on_time_for_uneven_periods() {
+    // 150 ticks per period, not a multiple of 100
+    for (duty_cycle, on_ticks, waveform) in [
+        (1, 1, OutputWaveform::Modulated),
+        (50, 75, OutputWaveform::Modulated),
+        (99, 148, OutputWaveform::Modulated),
+        (100, 150, OutputWaveform::ConstantOn),
+    ] {
+        let report = test_create_pwm_channel(100_000, 666, duty_cycle).validate();
+
+        assert_eq!(report.period_ticks, 150);
+        assert_eq!(report.on_ticks, on_ticks);
+        assert_eq!(report.waveform, waveform);
+    }
+
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(666)
+        .duty_cycle(100)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    let mut high_ticks = 0;
+
+    for _ in 0..1500 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            high_ticks += 1;
+        }
+    }
+
+    assert_eq!(high_ticks, 1500);
+}
+
+#[test]
+fn duty_conversion_is_exact_for_the_longest_period() {
+    for (duty_cycle, on_ticks) in [(1, 42_949_672), (33, 1_417_339_207), (99, 4_252_017_622)] {
+        // 3 s periods at a third of `u32::MAX` Hz
+        let channel = SpwmChannelBuilder::heater(1_431_655_765, 3)
+            .on_off_callback(|_| {})
+            .build()
+            .unwrap();
+
+        channel.update_duty_cycle(duty_cycle).unwrap();
+
+        let report = channel.validate();
+
+        assert_eq!(report.period_ticks, u32::MAX);
+        assert_eq!(report.on_ticks, on_ticks);
+    }
+}
+
+#[test]
+fn frequency_conversion_matches_division() {
+    for hardware_freq_hz in [
+        100,
+        101,
+        999_983,
+        1_000_000,
+        72_000_000,
+        MAX_HARDWARE_FREQ_HZ,
+    ] {
+        let channel = SpwmChannelBuilder::new(hardware_freq_hz)
+            .freq_hz(1)
+            .duty_cycle(0)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        let max_freq_hz = hardware_freq_hz / 100;
+
+        for freq_hz in (1..=max_freq_hz.min(10_000))
+            .chain([
+                max_freq_hz / 7,
+                max_freq_hz / 3,
+                max_freq_hz - 1,
+                max_freq_hz,
+            ])
+            .filter(|&freq_hz| freq_hz > 0)
+        {
+            channel.update_frequency(freq_hz, hardware_freq_hz).unwrap();
+
+            for duty_cycle in [0, 1, 33, 99, 100] {
+                channel.update_duty_cycle(duty_cycle).unwrap();
+
+                let report = channel.validate();
+                let period_ticks = hardware_freq_hz / freq_hz;
+
+                assert_eq!(report.period_ticks, period_ticks);
+                assert_eq!(
+                    u64::from(report.on_ticks),
+                    u64::from(period_ticks) * u64::from(duty_cycle) / 100
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn on_time_is_exact_near_full_duty() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(99)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.enable().is_ok());
+
+    let mut low_ticks = 0;
+
+    for _ in 0..500 {
+        spwm.irq_handler();
+
+        if !TEST_ON_OFF.load(Ordering::Relaxed) {
+            low_ticks += 1;
+        }
+    }
+
+    // one low tick per period
+    assert_eq!(low_ticks, 5);
+    assert_eq!(TEST_ON_EDGES.load(Ordering::Relaxed), 6);
+}
+
+#[test]
+fn pause_resume_keeps_phase() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.enable().is_ok());
+    assert_eq!(channel.resume(), Err(SpwmError::NotPaused));
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    assert!(channel.pause().is_ok());
+    assert!(channel.is_paused());
+    assert_eq!(channel.pause(), Err(SpwmError::AlreadyPaused));
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 0..230 {
+        spwm.irq_handler();
+        assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+    }
+
+    // 240 ticks into the waveform: 40 ticks into the on-time of the third period
+    assert!(channel.resume().is_ok());
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+    assert_eq!(TEST_ON_EDGES.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn start_delay_postpones_first_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .start_delay_ticks(30)
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.enable().is_ok());
+
+    for _ in 0..29 {
+        spwm.irq_handler();
+        assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+    }
+
+    spwm.irq_handler();
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+
+    let mut high_ticks = 0;
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            high_ticks += 1;
+        }
+    }
+
+    // 49 ticks before the Off edge and the next On edge on the last tick
+    assert_eq!(high_ticks, 50);
+    assert_eq!(TEST_ON_EDGES.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn duty_update_immediate_applies_to_current_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(80)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(channel.enable().is_ok());
+
+    for _ in 0..30 {
+        spwm.irq_handler();
+    }
+
+    // the counter already passed the new on-time
+    assert!(channel.update_duty_cycle_immediate(20).is_ok());
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+    spwm.irq_handler();
+    assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 31..100 {
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    // extending the on-time of the running period
+    assert!(channel.update_duty_cycle_immediate(50).is_ok());
+
+    let mut high_ticks = 10;
+
+    while TEST_ON_OFF.load(Ordering::Relaxed) {
+        spwm.irq_handler();
+        high_ticks += 1;
+    }
+
+    assert_eq!(high_ticks, 50);
+    assert_eq!(
+        channel.update_duty_cycle_immediate(101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn duty_updates_return_previous_value() {
+    let channel = test_create_pwm_channel(100_000, 1000, 25);
+
+    assert_eq!(channel.update_duty_cycle(60), Ok(25));
+    assert_eq!(channel.update_duty_cycle_immediate(10), Ok(60));
+    assert_eq!(
+        channel.update_duty_cycle(101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(channel.duty_cycle(), 10);
+}
+
+static TEST_APPLIED: Mutex<Vec<AppliedUpdate>> = Mutex::new(Vec::new());
+
+fn update_applied_test_callback(update: &AppliedUpdate) {
+    TEST_APPLIED.lock().unwrap().push(*update);
+}
+
+#[test]
+fn update_applied_callback_reports_period_boundary() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .update_applied_callback(update_applied_test_callback)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    // applied right away while disabled
+    channel.update_duty_cycle(20).unwrap();
+    channel.enable().unwrap();
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    channel.update_duty_cycle(50).unwrap();
+
+    for _ in 0..89 {
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_APPLIED.lock().unwrap().is_empty());
+
+    spwm.irq_handler();
+
+    assert_eq!(
+        *TEST_APPLIED.lock().unwrap(),
+        [AppliedUpdate {
+            old_on_ticks: 20,
+            new_on_ticks: 50,
+            period_ticks: 100,
+        }]
+    );
+
+    channel.update_duty_cycle_immediate(70).unwrap();
+
+    for _ in 0..200 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(TEST_APPLIED.lock().unwrap().len(), 1);
+}
+
+static TEST_PERIOD_TICKS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+fn period_ticks_test_callback(ticks: u64) {
+    TEST_PERIOD_TICKS.lock().unwrap().push(ticks);
+}
+
+#[test]
+fn period_ticks_callback_counts_ticks_since_enable() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(|_| {})
+        .period_ticks_callback(period_ticks_test_callback)
+        .start_delay_ticks(25)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let other = test_create_pwm_channel(100_000, 10, 50);
+    let other_id = spwm.register_channel(other).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    spwm.get_channel(other_id).unwrap().enable().unwrap();
+
+    for _ in 0..1234 {
+        spwm.irq_handler();
+    }
+
+    channel.enable().unwrap();
+
+    for _ in 0..325 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(*TEST_PERIOD_TICKS.lock().unwrap(), [125, 225, 325]);
+
+    channel.disable().unwrap();
+    channel.enable().unwrap();
+
+    for _ in 0..225 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(
+        *TEST_PERIOD_TICKS.lock().unwrap(),
+        [125, 225, 325, 125, 225]
+    );
+    assert_eq!(
+        Spwm::<1>::new(100_000)
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(|_| {})
+            .build()
+            .err(),
+        Some(SpwmError::CallbackSetError)
+    );
+}
+
+static TEST_VIOLATIONS: Mutex<Vec<ProtectionViolation>> = Mutex::new(Vec::new());
+static TEST_LIMITED_ON_TICKS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+fn violation_test_callback(violation: ProtectionViolation) {
+    TEST_VIOLATIONS.lock().unwrap().push(violation);
+}
+
+fn limited_update_test_callback(update: &AppliedUpdate) {
+    TEST_LIMITED_ON_TICKS
+        .lock()
+        .unwrap()
+        .push(update.new_on_ticks);
+}
+
+#[test]
+fn protection_limits_duty_and_step() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_VIOLATIONS.lock().unwrap().clear();
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let profile = ProtectionProfile {
+        max_duty: 80,
+        max_step: 20,
+        ..ProtectionProfile::default()
+    };
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(0)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .update_applied_callback(limited_update_test_callback)
+        .protection(profile, violation_test_callback)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    channel.enable().unwrap();
+    channel.update_duty_cycle(100).unwrap();
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(*TEST_LIMITED_ON_TICKS.lock().unwrap(), [20, 40, 60, 80]);
+    assert_eq!(
+        *TEST_VIOLATIONS.lock().unwrap(),
+        [
+            ProtectionViolation::MaxDuty,
+            ProtectionViolation::MaxStep,
+            ProtectionViolation::MaxStep,
+            ProtectionViolation::MaxStep,
+        ]
+    );
+}
+
+#[test]
+fn increase_duty_saturates_at_protection_max_duty() {
+    let profile = ProtectionProfile {
+        max_duty: 80,
+        ..ProtectionProfile::default()
+    };
+    let channel = SpwmChannelBuilder::new(100_000)
+        .freq_hz(1000)
+        .duty_cycle(70)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .protection(profile, |_| {})
+        .build()
+        .unwrap();
+
+    assert_eq!(channel.increase_duty(5), Ok(70));
+    assert_eq!(channel.duty_cycle(), 75);
+    assert_eq!(channel.increase_duty(10), Ok(75));
+    assert_eq!(channel.duty_cycle(), 80);
+    assert_eq!(channel.increase_duty(u8::MAX), Ok(80));
+    assert_eq!(channel.duty_cycle(), 80);
+}
+
+#[test]
+fn protection_forces_period_off_after_continuous_on_time() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_VIOLATIONS.lock().unwrap().clear();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let profile = ProtectionProfile {
+        max_continuous_on_periods: 2,
+        ..ProtectionProfile::default()
+    };
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(100)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .protection(profile, violation_test_callback)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    let mut levels = Vec::new();
+
+    for _ in 0..6 {
+        for _ in 0..50 {
+            spwm.irq_handler();
+        }
+
+        levels.push(TEST_ON_OFF.load(Ordering::Relaxed));
+
+        for _ in 0..50 {
+            spwm.irq_handler();
+        }
+    }
+
+    assert_eq!(levels, [true, true, false, true, true, false]);
+    assert_eq!(
+        *TEST_VIOLATIONS.lock().unwrap(),
+        [ProtectionViolation::ContinuousOn; 2]
+    );
+}
+
+#[test]
+fn protection_profile_is_validated() {
+    let profiles = [
+        (
+            50,
+            ProtectionProfile {
+                max_duty: 101,
+                ..ProtectionProfile::default()
+            },
+        ),
+        (
+            50,
+            ProtectionProfile {
+                max_step: 0,
+                ..ProtectionProfile::default()
+            },
+        ),
+        (
+            50,
+            ProtectionProfile {
+                max_duty: 40,
+                ..ProtectionProfile::default()
+            },
+        ),
+    ];
+
+    for (duty_cycle, profile) in profiles {
+        let result = Spwm::<1>::new(100_000)
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .protection(profile, |_| {})
+            .build();
+
+        assert!(matches!(result, Err(SpwmError::InvalidDutyCycle)));
+    }
+}
+
+static TEST_ENABLE_EVENTS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+#[test]
+fn enable_callback_brackets_output() {
+    let channel = Spwm::<1>::new(100_000)
+        .create_channel()
+        .on_off_callback(|state| {
+            TEST_ENABLE_EVENTS.lock().unwrap().push(match state {
+                SpwmState::On => "on",
+                SpwmState::Off => "off",
+            });
+        })
+        .period_callback(|| {})
+        .enable_callback(|enabled| {
+            TEST_ENABLE_EVENTS
+                .lock()
+                .unwrap()
+                .push(if enabled { "enabled" } else { "disabled" });
+        })
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .build()
+        .unwrap();
+
+    channel.enable().unwrap();
+    assert_eq!(channel.enable(), Err(SpwmError::AlreadyEnabled));
+    channel.disable().unwrap();
+    assert_eq!(channel.disable(), Err(SpwmError::AlreadyDisabled));
+
+    assert_eq!(
+        *TEST_ENABLE_EVENTS.lock().unwrap(),
+        ["enabled", "on", "off", "disabled"]
+    );
+}
+
+static TEST_PREPARED: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn prepare_callback_runs_lead_ticks_before_period_end() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .prepare_callback(|| TEST_PREPARED.store(true, Ordering::Relaxed), 10)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    let prepared_ticks: Vec<_> = (1..=300)
+        .filter(|_| {
+            spwm.irq_handler();
+            TEST_PREPARED.swap(false, Ordering::Relaxed)
+        })
+        .collect();
+
+    assert_eq!(prepared_ticks, [90, 190, 290]);
+}
+
+static TEST_BOUNDARY_EVENTS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+#[test]
+fn period_callback_timing_orders_callback_and_update() {
+    for (timing, expected) in [
+        (PeriodCallbackTiming::BeforeUpdate, ["period", "applied"]),
+        (PeriodCallbackTiming::AfterUpdate, ["applied", "period"]),
+    ] {
+        let mut spwm = Spwm::<1>::new(100_000);
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(|_| {})
+            .period_callback(|| TEST_BOUNDARY_EVENTS.lock().unwrap().push("period"))
+            .update_applied_callback(|_| TEST_BOUNDARY_EVENTS.lock().unwrap().push("applied"))
+            .period_callback_timing(timing)
+            .build()
+            .unwrap();
+        let channel_id = spwm.register_channel(channel).unwrap();
+        let channel = spwm.get_channel(channel_id).unwrap();
+
+        channel.enable().unwrap();
+        channel.update_duty_cycle(60).unwrap();
+        TEST_BOUNDARY_EVENTS.lock().unwrap().clear();
+
+        for _ in 0..100 {
+            spwm.irq_handler();
+        }
+
+        assert_eq!(*TEST_BOUNDARY_EVENTS.lock().unwrap(), expected);
+    }
+}
+
+#[test]
+fn trim_period_adjusts_period_ticks() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(100)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(channel.trim_period(3), Ok(1003));
+    assert_eq!(channel.trim_period(-5), Ok(998));
+    assert_eq!(channel.trim_period(-997), Err(SpwmError::InvalidFrequency));
+    assert_eq!(channel.validate().period_ticks, 998);
+
+    channel.enable().unwrap();
+
+    for _ in 0..9980 {
+        spwm.irq_handler();
+    }
+
+    // 10 periods of 998 ticks, the 11th starts on the last tick
+    assert_eq!(TEST_ON_EDGES.load(Ordering::Relaxed), 11);
+}
+
+#[test]
+fn peak_and_hold_drops_to_hold_duty_after_pull_in() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .peak_and_hold(90, 250)
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+    let on_ticks_per_period = || {
+        (0..100)
+            .filter(|_| {
+                spwm.irq_handler();
+                TEST_ON_OFF.load(Ordering::Relaxed)
+            })
+            .count()
+    };
+
+    channel.enable().unwrap();
+
+    assert!(channel.is_in_peak());
+
+    let periods: Vec<_> = (0..5).map(|_| on_ticks_per_period()).collect();
+
+    // 250 pull-in ticks take 3 whole periods
+    assert_eq!(periods, [90, 90, 90, 30, 30]);
+    assert!(!channel.is_in_peak());
+
+    channel.retrigger();
+
+    let periods: Vec<_> = (0..5).map(|_| on_ticks_per_period()).collect();
+
+    assert_eq!(periods, [30, 90, 90, 90, 30]);
+
+    channel.disable().unwrap();
+
+    assert_eq!(channel.validate().on_ticks, 30);
+    assert!(
+        Spwm::<1>::new(100_000)
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .peak_and_hold(101, 250)
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn pulse_offset_positions_on_time_within_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(20)
+        .pulse_offset_ticks(30)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+    let on_ticks_of_period = || {
+        (0..100)
+            .filter(|_| {
+                let on = TEST_ON_OFF.load(Ordering::Relaxed);
+                spwm.irq_handler();
+                on
+            })
+            .collect::<Vec<_>>()
+    };
+
+    channel.enable().unwrap();
+
+    assert_eq!(on_ticks_of_period(), (30..50).collect::<Vec<_>>());
+
+    // moved pulse applies from the next period, a pulse past the period end is cut
+    channel.set_pulse_offset(90).unwrap();
+
+    assert_eq!(channel.pulse_offset(), 90);
+    assert_eq!(on_ticks_of_period(), (30..50).collect::<Vec<_>>());
+    assert_eq!(on_ticks_of_period(), (90..100).collect::<Vec<_>>());
+    assert_eq!(
+        channel.set_pulse_offset(100),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+
+    channel.set_pulse_offset(0).unwrap();
+    on_ticks_of_period();
+
+    assert_eq!(on_ticks_of_period(), (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn current_limit_trip_ends_pulse_until_next_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert!(!channel.trip_current_limit());
+
+    channel.enable().unwrap();
+
+    let mut on_ticks = [0; 2];
+
+    for tick in 0..200 {
+        if tick == 20 {
+            assert!(channel.trip_current_limit());
+            assert!(channel.is_tripped());
+            assert!(!channel.trip_current_limit());
+        }
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks[tick / 100] += 1;
+        }
+
+        spwm.irq_handler();
+    }
+
+    assert_eq!(on_ticks, [20, 50]);
+    assert!(!channel.is_tripped());
+    assert!(channel.is_enabled());
+    assert_eq!(channel.duty_cycle(), 50);
+}
+
+#[test]
+fn current_limit_trip_is_ignored_during_blanking() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .blanking_ticks(10)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(channel.blanking_ticks(), 10);
+
+    channel.enable().unwrap();
+
+    let mut on_ticks = 0;
+
+    for tick in 0..100 {
+        match tick {
+            5 | 9 => assert!(!channel.trip_current_limit()),
+            10 => assert!(channel.trip_current_limit()),
+            _ => {}
+        }
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks += 1;
+        }
+
+        spwm.irq_handler();
+    }
+
+    assert_eq!(on_ticks, 10);
+
+    channel.set_blanking_ticks(0);
+
+    assert!(channel.trip_current_limit());
+}
+
+#[test]
+fn frequency_target_ramps_period_by_period() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+    TEST_ON_EDGES.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(500)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    channel.enable().unwrap();
+    channel.set_frequency_target(1000, 125, 100_000).unwrap();
+
+    assert_eq!(channel.frequency_target(), Some(1000));
+
+    let mut rising_edges = Vec::new();
+
+    for tick in 0..900 {
+        let edges = TEST_ON_EDGES.load(Ordering::Relaxed);
+
+        spwm.irq_handler();
+
+        if TEST_ON_EDGES.load(Ordering::Relaxed) != edges {
+            rising_edges.push(tick);
+        }
+    }
+
+    // 625 Hz, 750 Hz, 875 Hz and then 1000 Hz periods following the first 500 Hz period
+    let periods: Vec<_> = rising_edges.windows(2).map(|w| w[1] - w[0]).collect();
+
+    assert_eq!(rising_edges[0], 199);
+    assert_eq!(periods, [160, 133, 114, 100, 100]);
+    assert_eq!(channel.frequency_target(), None);
+    assert_eq!(channel.validate().on_ticks, 50);
+
+    channel.set_frequency_target(500, 0, 100_000).unwrap();
+    channel.update_frequency(800, 100_000).unwrap();
+
+    assert_eq!(channel.frequency_target(), None);
+    assert_eq!(
+        channel.set_frequency_target(0, 10, 100_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+}
+
+static WINDOW_EVENTS: Mutex<Vec<(bool, u32)>> = Mutex::new(Vec::new());
+static WINDOW_TICK: AtomicU32 = AtomicU32::new(0);
+
+fn window_callback(active: bool) {
+    WINDOW_EVENTS
+        .lock()
+        .unwrap()
+        .push((active, WINDOW_TICK.load(Ordering::Relaxed)));
+}
+
+#[test]
+fn measurement_window_brackets_quiet_off_time() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    WINDOW_EVENTS.lock().unwrap().clear();
+    WINDOW_TICK.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .measurement_window(window_callback, 50, 80)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(channel.measurement_window(), (50, 80));
+
+    channel.enable().unwrap();
+
+    for tick in 1..=200 {
+        WINDOW_TICK.store(tick, Ordering::Relaxed);
+        spwm.irq_handler();
+    }
+
+    assert_eq!(
+        *WINDOW_EVENTS.lock().unwrap(),
+        [(true, 50), (false, 80), (true, 150), (false, 180)]
+    );
+    assert_eq!(
+        channel.set_measurement_window(60, 60),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert!(
+        Spwm::<1>::new(100_000)
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .measurement_window(window_callback, 80, 50)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .is_err()
+    );
+}
+
+static CALIBRATION: [(u8, u8); 3] = [(10, 20), (50, 40), (100, 100)];
+static UNSORTED: [(u8, u8); 2] = [(50, 20), (50, 40)];
+
+#[test]
+fn calibration_maps_output_values_to_duty_cycles() {
+    let channel = Spwm::<1>::new(1_000_000)
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .calibration(&CALIBRATION)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+
+    // halfway between 20% and 40%
+    assert_eq!(channel.duty_cycle(), 30);
+    assert_eq!(channel.calibrated_duty(), Some(3_000));
+    assert_eq!(channel.validate().on_ticks, 300);
+
+    let expected = [(0, 200), (10, 200), (51, 412), (75, 700), (100, 1000)];
+
+    for (value, on_ticks) in expected {
+        channel.update_duty_cycle(value).unwrap();
+
+        assert_eq!(channel.validate().on_ticks, on_ticks);
+    }
+
+    assert!(matches!(
+        Spwm::<1>::new(1_000_000)
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .calibration(&UNSORTED)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build(),
+        Err(SpwmError::InvalidDutyCycle)
+    ));
+}
+
+#[test]
+fn burst_dimming_skips_periods_below_threshold() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(5)
+        .burst_dimming(20)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(id).unwrap();
+
+    assert!(channel.is_burst_dimming());
+
+    channel.enable().unwrap();
+
+    let mut on_ticks = [0; 9];
+
+    for tick in 0..900 {
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks[tick / 100] += 1;
+        }
+
+        spwm.irq_handler();
+    }
+
+    // the threshold pulse every fourth period, counting the one latched at enable
+    assert_eq!(on_ticks[1..], [0, 0, 0, 20, 0, 0, 0, 20]);
+
+    channel.update_duty_cycle(40).unwrap();
+
+    assert!(!channel.is_burst_dimming());
+    assert_eq!(channel.validate().on_ticks, 40);
+    assert_eq!(
+        channel.set_burst_dimming(101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn flicker_safe_channel_rejects_low_frequencies() {
+    assert_eq!(assert_flicker_safe(FLICKER_SAFE_MIN_HZ), Ok(()));
+    assert_eq!(assert_flicker_safe(100), Err(SpwmError::InvalidFrequency));
+
+    let builder = || {
+        Spwm::<1>::new(1_000_000)
+            .create_channel()
+            .freq_hz(200)
+            .duty_cycle(50)
+            .flicker_safe()
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+    };
+
+    assert!(matches!(
+        builder().build(),
+        Err(SpwmError::InvalidFrequency)
+    ));
+
+    let channel = Spwm::<1>::new(1_000_000)
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .flicker_safe()
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+
+    assert!(channel.is_flicker_safe());
+    assert_eq!(
+        channel.update_frequency(250, 1_000_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        channel.set_frequency_target(250, 10, 1_000_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(channel.validate().period_ticks, 1000);
+    assert_eq!(channel.update_frequency(400, 1_000_000), Ok(()));
+
+    // unconstrained channels accept any valid frequency
+    let channel = test_create_pwm_channel(1_000_000, 1000, 50);
+
+    assert!(!channel.is_flicker_safe());
+    assert_eq!(channel.update_frequency(100, 1_000_000), Ok(()));
+}
+
+#[test]
+fn quantize_reports_produced_duty_cycle() {
+    let channel = test_create_pwm_channel(2_000_000, 1000, 50);
+
+    assert_eq!(channel.effective_resolution(), 100);
+    assert_eq!(channel.quantize(37), Ok(3_700));
+
+    // 150 ticks per period, one or two ticks per percent
+    channel.update_frequency(1000, 150_000).unwrap();
+
+    assert_eq!(channel.effective_resolution(), 134);
+    assert_eq!(channel.quantize(3), Ok(266));
+    assert_eq!(channel.quantize(100), Ok(10_000));
+    assert_eq!(channel.quantize(101), Err(SpwmError::InvalidDutyCycle));
+}
+
+/// Runs one 100 tick period and returns its on-time ticks.
+fn run_period(spwm: &Spwm<1>) -> u32 {
+    let mut on_ticks = 0;
+
+    for _ in 0..100 {
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks += 1;
+        }
+
+        spwm.irq_handler();
+    }
+
+    on_ticks
+}
+
+#[test]
+fn update_policy_controls_when_updates_latch() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .update_policy(UpdatePolicy::OnEnable)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(id).unwrap();
+
+    assert_eq!(channel.validate().on_ticks, 50);
+
+    channel.update_duty_cycle(20).unwrap();
+
+    assert!(channel.has_staged_update());
+    assert_eq!(channel.validate().on_ticks, 50);
+
+    channel.enable().unwrap();
+
+    assert_eq!(run_period(&spwm), 20);
+
+    // staged while running, kept until the channel is enabled again
+    channel.update_duty_cycle(70).unwrap();
+
+    assert_eq!([run_period(&spwm), run_period(&spwm)], [20, 20]);
+
+    channel.disable().unwrap();
+    channel.enable().unwrap();
+
+    assert!(!channel.has_staged_update());
+    assert_eq!(run_period(&spwm), 70);
+
+    // applied to the period in progress
+    channel.set_update_policy(UpdatePolicy::Immediate);
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    channel.update_duty_cycle(40).unwrap();
+
+    // ticks 10..40 of this period and the first 10 ticks of the next one
+    assert_eq!(run_period(&spwm), 30 + 10);
+    assert_eq!(channel.update_policy(), UpdatePolicy::Immediate);
+}
+
+#[test]
+fn cancel_pending_restores_values_in_effect() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(id).unwrap();
+
+    channel.enable().unwrap();
+
+    assert!(channel.pending_update().is_empty());
+
+    channel.update_duty_cycle(30).unwrap();
+    channel.update_duty_cycle(20).unwrap();
+    channel.set_pulse_offset(10).unwrap();
+    channel.set_frequency_target(500, 100, 100_000).unwrap();
+
+    let pending = PendingUpdate {
+        duty_cycle: Some(20),
+        on_ticks: Some(20),
+        freq_hz: Some(500),
+        pulse_offset: Some(10),
+    };
+
+    assert_eq!(channel.pending_update(), pending);
+    assert_eq!(channel.cancel_pending(), pending);
+    assert!(channel.pending_update().is_empty());
+    assert_eq!(channel.duty_cycle(), 50);
+    assert_eq!(channel.pulse_offset(), 0);
+    assert_eq!(run_period(&spwm), 50);
+    assert_eq!(channel.validate().period_ticks, 100);
+}
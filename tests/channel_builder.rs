@@ -66,3 +66,87 @@ fn builder_with_invalid_duty_cycle() {
     assert!(r.is_err());
     assert_eq!(r.err().unwrap(), SpwmError::InvalidDutyCycle);
 }
+
+#[test]
+fn builder_validates_each_step() {
+    let init_fn =
+        |hardware_freq_hz: u32, freq_hz: u32, duty_cycle: u8| -> Result<SpwmChannel, SpwmError> {
+            SpwmChannelBuilder::new(hardware_freq_hz)
+                .on_off_callback(|_: &SpwmState| {})
+                .period_callback(|| {})
+                .try_freq_hz(freq_hz)?
+                .try_duty_cycle(duty_cycle)?
+                .build()
+        };
+
+    assert!(init_fn(100_000, 100, 50).is_ok());
+    assert_eq!(
+        init_fn(0, 100, 50).err(),
+        Some(SpwmError::InvalidHardwareFrequency)
+    );
+    assert_eq!(
+        init_fn(100_000, 2000, 50).err(),
+        Some(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        init_fn(100_000, 100, 101).err(),
+        Some(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn builder_presets() {
+    let led = SpwmChannelBuilder::led(1_000_000, 1000)
+        .on_off_callback(|_: &SpwmState| {})
+        .build()
+        .unwrap();
+
+    assert!(led.is_flicker_safe());
+    assert_eq!(led.validate().on_ticks, 0);
+    assert_eq!(
+        SpwmChannelBuilder::led(1_000_000, 100)
+            .on_off_callback(|_: &SpwmState| {})
+            .build()
+            .err(),
+        Some(SpwmError::InvalidFrequency)
+    );
+
+    let servo = SpwmChannelBuilder::servo(1_000_000)
+        .on_off_callback(|_: &SpwmState| {})
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        (servo.validate().period_ticks, servo.validate().on_ticks),
+        (20_000, 1_500)
+    );
+
+    let heater = SpwmChannelBuilder::heater(1000, 10)
+        .on_off_callback(|_: &SpwmState| {})
+        .build()
+        .unwrap();
+
+    heater.update_duty_cycle(25).unwrap();
+
+    assert_eq!(
+        (heater.validate().period_ticks, heater.validate().on_ticks),
+        (10_000, 2_500)
+    );
+    assert_eq!(
+        SpwmChannelBuilder::heater(u32::MAX, 1)
+            .on_off_callback(|_: &SpwmState| {})
+            .build()
+            .err(),
+        Some(SpwmError::InvalidHardwareFrequency)
+    );
+
+    let buzzer = SpwmChannelBuilder::buzzer(4_000_000)
+        .on_off_callback(|_: &SpwmState| {})
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        (buzzer.validate().period_ticks, buzzer.validate().on_ticks),
+        (1000, 500)
+    );
+}
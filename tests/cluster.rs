@@ -0,0 +1,70 @@
+use spwm::{SpwmCluster, SpwmError};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static FAST_PERIODS: AtomicU32 = AtomicU32::new(0);
+static SLOW_PERIODS: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn cluster_ticks_members_with_their_prescalers() {
+    let mut cluster = SpwmCluster::<1, 2>::new(100_000, [1, 10]).unwrap();
+    let period_callbacks: [fn(); 2] = [
+        || {
+            FAST_PERIODS.fetch_add(1, Ordering::Relaxed);
+        },
+        || {
+            SLOW_PERIODS.fetch_add(1, Ordering::Relaxed);
+        },
+    ];
+
+    for (index, (freq_hz, callback)) in [1000, 100].into_iter().zip(period_callbacks).enumerate() {
+        let spwm = cluster.instance_mut(index).unwrap();
+        let channel = spwm
+            .create_channel()
+            .freq_hz(freq_hz)
+            .duty_cycle(50)
+            .on_off_callback(|_| {})
+            .period_callback(callback)
+            .build()
+            .unwrap();
+        let channel_id = spwm.register_channel(channel).unwrap();
+
+        spwm.get_channel(channel_id).unwrap().enable().unwrap();
+    }
+
+    assert_eq!(cluster.prescaler(1), Some(10));
+    assert!(cluster.instance(2).is_none());
+
+    // Both channels have 100-tick periods, the slow one in ticks of 10 hardware ticks
+    for _ in 0..1000 {
+        cluster.irq_handler();
+    }
+
+    assert_eq!(FAST_PERIODS.load(Ordering::Relaxed), 10);
+    assert_eq!(SLOW_PERIODS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn cluster_rejects_invalid_prescalers() {
+    assert!(matches!(
+        SpwmCluster::<1, 2>::new(100_000, [1, 0]),
+        Err(SpwmError::InvalidTickDivider)
+    ));
+    assert!(matches!(
+        SpwmCluster::<1, 2>::new(100_000, [1, 3]),
+        Err(SpwmError::InvalidTickDivider)
+    ));
+}
+
+#[test]
+fn cluster_rejects_invalid_hardware_frequencies() {
+    assert!(matches!(
+        SpwmCluster::<1, 1>::new(0, [1]),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+    assert!(matches!(
+        SpwmCluster::<1, 1>::new(u32::MAX, [1]),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+    // the manager frequency counts, not the one of the shared hardware timer
+    assert!(SpwmCluster::<1, 1>::new(u32::MAX, [3]).is_ok());
+}
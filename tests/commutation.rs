@@ -0,0 +1,144 @@
+use spwm::{Commutator, Spwm, SpwmError, decode_hall_120};
+
+fn create_spwm() -> Spwm<6> {
+    let mut spwm = Spwm::<6>::new(1_000_000);
+
+    for _ in 0..6 {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(10_000)
+            .duty_cycle(40)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+fn enabled(spwm: &Spwm<6>) -> Vec<usize> {
+    (0..6)
+        .filter(|&id| spwm.get_channel(id).unwrap().is_enabled())
+        .collect()
+}
+
+#[test]
+fn hall_sequence_commutates_six_steps() {
+    let spwm = create_spwm();
+    let mut commutator = Commutator::new(&spwm, [0, 1, 2], [3, 4, 5], decode_hall_120).unwrap();
+
+    assert_eq!(commutator.step(), None);
+    assert!(enabled(&spwm).is_empty());
+    assert_eq!(spwm.get_channel(3).unwrap().duty_cycle(), 100);
+
+    let steps: Vec<_> = [0b001, 0b011, 0b010, 0b110, 0b100, 0b101]
+        .into_iter()
+        .map(|hall_state| {
+            let step = commutator.commutate(&spwm, hall_state).unwrap();
+
+            for _ in 0..50 {
+                spwm.irq_handler();
+            }
+
+            (step, enabled(&spwm))
+        })
+        .collect();
+
+    // A+B-, A+C-, B+C-, B+A-, C+A-, C+B-
+    assert_eq!(
+        steps,
+        [
+            (Some(0), vec![0, 4]),
+            (Some(1), vec![0, 5]),
+            (Some(2), vec![1, 5]),
+            (Some(3), vec![1, 3]),
+            (Some(4), vec![2, 3]),
+            (Some(5), vec![2, 4]),
+        ]
+    );
+
+    assert_eq!(commutator.commutate(&spwm, 0b111).unwrap(), None);
+    assert!(enabled(&spwm).is_empty());
+}
+
+#[test]
+fn duty_cycle_applies_to_high_side() {
+    let spwm = create_spwm();
+    let commutator = Commutator::new(&spwm, [0, 1, 2], [3, 4, 5], decode_hall_120).unwrap();
+
+    commutator.set_duty_cycle(&spwm, 70).unwrap();
+
+    let duty_cycles: Vec<_> = (0..6)
+        .map(|id| spwm.get_channel(id).unwrap().duty_cycle())
+        .collect();
+
+    assert_eq!(duty_cycles, [70, 70, 70, 100, 100, 100]);
+    assert_eq!(
+        commutator.set_duty_cycle(&spwm, 101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn commutator_rejects_invalid_channels() {
+    let spwm = create_spwm();
+
+    assert!(matches!(
+        Commutator::new(&spwm, [0, 1, 2], [3, 4, 2], decode_hall_120),
+        Err(SpwmError::InvalidChannel)
+    ));
+    assert!(matches!(
+        Commutator::new(&spwm, [0, 1, 2], [3, 4, 6], decode_hall_120),
+        Err(SpwmError::InvalidChannel)
+    ));
+}
+
+#[test]
+fn hall_edges_measure_period_and_advance_commutation() {
+    let spwm = create_spwm();
+    let mut commutator = Commutator::new(&spwm, [0, 1, 2], [3, 4, 5], decode_hall_120).unwrap();
+
+    assert_eq!(
+        commutator.set_phase_advance(60),
+        Err(SpwmError::InvalidConfiguration)
+    );
+
+    commutator.set_phase_advance(30).unwrap();
+
+    assert_eq!(commutator.on_hall_change(&spwm, 0b001, 0).unwrap(), Some(0));
+    assert_eq!(commutator.electrical_period_ticks(), None);
+    assert_eq!(commutator.poll(&spwm, 1_000).unwrap(), None);
+    assert_eq!(
+        commutator.on_hall_change(&spwm, 0b011, 600).unwrap(),
+        Some(1)
+    );
+    assert_eq!(commutator.electrical_period_ticks(), Some(3_600));
+
+    // the next step is applied 30° (300 ticks) ahead of its hall edge
+    assert_eq!(commutator.poll(&spwm, 899).unwrap(), None);
+    assert_eq!(commutator.poll(&spwm, 900).unwrap(), Some(2));
+    assert_eq!(commutator.step(), Some(2));
+    assert_eq!(enabled(&spwm), [1, 5]);
+    assert_eq!(
+        commutator.on_hall_change(&spwm, 0b010, 1_200).unwrap(),
+        Some(2)
+    );
+
+    // reversing schedules the preceding step
+    assert_eq!(
+        commutator.on_hall_change(&spwm, 0b011, 1_300).unwrap(),
+        Some(1)
+    );
+    assert_eq!(commutator.electrical_period_ticks(), Some(600));
+    assert_eq!(commutator.poll(&spwm, 1_350).unwrap(), Some(0));
+
+    // an invalid hall state coasts and restarts the measurement
+    assert_eq!(
+        commutator.on_hall_change(&spwm, 0b000, 1_400).unwrap(),
+        None
+    );
+    assert_eq!(commutator.electrical_period_ticks(), None);
+    assert!(enabled(&spwm).is_empty());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "serde")]
+
+use spwm::{ChannelConfig, Spwm, SpwmConfig, SpwmError};
+
+#[test]
+fn config_round_trip_and_apply() {
+    let mut spwm = Spwm::<3>::new(100_000);
+    let mut ids = [0; 2];
+
+    for id in &mut ids {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(0)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    let mut config = SpwmConfig::<3>::default();
+
+    config.channels[ids[0]] = Some(ChannelConfig {
+        freq_hz: 500,
+        duty_cycle: 30,
+        priority: 2,
+        enabled: true,
+    });
+    config.channels[ids[1]] = Some(ChannelConfig {
+        freq_hz: 250,
+        duty_cycle: 75,
+        priority: 0,
+        enabled: false,
+    });
+
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: SpwmConfig<3> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, config);
+    assert!(serde_json::from_str::<SpwmConfig<4>>(&json).is_err());
+
+    assert!(spwm.apply_config(&loaded).is_ok());
+
+    let channel = spwm.get_channel(ids[0]).unwrap();
+
+    assert!(channel.is_enabled());
+    assert_eq!(channel.priority(), 2);
+    assert_eq!(channel.duty_cycle(), 30);
+    assert_eq!(channel.validate().period_ticks, 200);
+    assert_eq!(channel.validate().on_ticks, 60);
+    assert!(!spwm.get_channel(ids[1]).unwrap().is_enabled());
+
+    // invalid entries leave all channels untouched
+    let mut invalid = loaded;
+
+    invalid.channels[ids[0]] = Some(ChannelConfig {
+        duty_cycle: 10,
+        ..loaded.channels[ids[0]].unwrap()
+    });
+    invalid.channels[ids[1]] = Some(ChannelConfig {
+        freq_hz: 2000,
+        ..loaded.channels[ids[1]].unwrap()
+    });
+    assert_eq!(
+        spwm.apply_config(&invalid),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(spwm.get_channel(ids[0]).unwrap().duty_cycle(), 30);
+
+    invalid.channels[ids[1]] = None;
+    invalid.channels[2] = loaded.channels[ids[0]];
+    assert_eq!(spwm.apply_config(&invalid), Err(SpwmError::InvalidChannel));
+}
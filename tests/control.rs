@@ -0,0 +1,65 @@
+use spwm::{Spwm, SpwmControl, SpwmError};
+use std::thread;
+
+static CONTROL: SpwmControl<2> = SpwmControl::new();
+
+fn create_spwm() -> Spwm<2> {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(10)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+#[test]
+fn requests_from_another_thread_are_applied_by_irq_thread() {
+    let spwm = create_spwm();
+    let control = thread::spawn(|| {
+        for duty_cycle in 0..=100 {
+            CONTROL.request_duty_cycle(0, duty_cycle).unwrap();
+        }
+
+        CONTROL.request_enable(0).unwrap();
+    });
+
+    while !control.is_finished() || CONTROL.is_pending(0) {
+        spwm.apply_control(&CONTROL);
+        spwm.irq_handler();
+    }
+
+    control.join().unwrap();
+
+    let channel = spwm.get_channel(0).unwrap();
+
+    assert!(channel.is_enabled());
+    assert_eq!(channel.duty_cycle(), 100);
+
+    CONTROL.request_enable(0).unwrap();
+    CONTROL.request_disable(0).unwrap();
+    // Channel 1 is not registered, so its request is dropped
+    CONTROL.request_enable(1).unwrap();
+
+    assert_eq!(spwm.apply_control(&CONTROL), 1);
+    assert!(!channel.is_enabled());
+    assert!(!CONTROL.is_pending(1));
+}
+
+#[test]
+fn invalid_requests_are_rejected() {
+    let control = SpwmControl::<1>::new();
+
+    assert_eq!(control.request_enable(1), Err(SpwmError::InvalidChannel));
+    assert_eq!(
+        control.request_duty_cycle(0, 101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert!(!control.is_pending(0));
+}
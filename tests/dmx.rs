@@ -0,0 +1,75 @@
+#![cfg(feature = "dmx")]
+
+use spwm::{DmxAdapter, DmxCurve, DmxPatch, Spwm, SpwmError};
+
+fn invert(level: u8) -> u8 {
+    u8::MAX - level
+}
+
+fn create_spwm() -> Spwm<4> {
+    let mut spwm = Spwm::<4>::new(100_000);
+
+    for _ in 0..4 {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(200)
+            .duty_cycle(0)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+#[test]
+fn dmx_levels_map_through_patches() {
+    let spwm = create_spwm();
+    let adapter = DmxAdapter::new([
+        DmxPatch::new(10, 0),
+        DmxPatch::new(11, 1).curve(DmxCurve::Square).max_duty(50),
+        DmxPatch::new(12, 2).curve(DmxCurve::Cube),
+        DmxPatch::new(13, 3).curve(DmxCurve::Custom(invert)),
+    ]);
+    let mut levels = [0u8; 512];
+
+    levels[9..13].copy_from_slice(&[64, 128, 128, 51]);
+
+    assert_eq!(adapter.apply(&spwm, &levels), Ok(4));
+
+    let duty_cycles: Vec<u8> = (0..4)
+        .map(|id| spwm.get_channel(id).unwrap().duty_cycle())
+        .collect();
+
+    assert_eq!(duty_cycles, [25, 13, 13, 80]);
+    assert_eq!(adapter.apply(&spwm, &levels), Ok(0));
+
+    // A short frame leaves the channels beyond its end unchanged
+    assert_eq!(adapter.apply(&spwm, &[255; 11]), Ok(2));
+    assert_eq!(spwm.get_channel(1).unwrap().duty_cycle(), 50);
+    assert_eq!(spwm.get_channel(2).unwrap().duty_cycle(), 13);
+}
+
+#[test]
+fn dmx_patches_are_validated() {
+    let spwm = create_spwm();
+
+    for patch in [
+        DmxPatch::new(0, 0),
+        DmxPatch::new(513, 0),
+        DmxPatch::new(1, 4),
+    ] {
+        assert_eq!(
+            DmxAdapter::new([DmxPatch::new(2, 1), patch]).apply(&spwm, &[255; 2]),
+            Err(SpwmError::InvalidChannel)
+        );
+    }
+
+    assert_eq!(
+        DmxAdapter::new([DmxPatch::new(1, 0).max_duty(101)]).apply(&spwm, &[255]),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(spwm.get_channel(1).unwrap().duty_cycle(), 0);
+}
@@ -0,0 +1,128 @@
+use spwm::{DoublePulse, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static OUTPUT_ON: AtomicBool = AtomicBool::new(false);
+static COMPLETED: AtomicU32 = AtomicU32::new(0);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn create_spwm() -> Spwm<1> {
+    let mut spwm = Spwm::<1>::new(1_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .double_pulse_complete_callback(|| {
+            COMPLETED.fetch_add(1, Ordering::Relaxed);
+        })
+        .on_off_callback(|state| {
+            OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+        })
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+#[test]
+fn double_pulse_runs_once_and_stops() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+    COMPLETED.store(0, Ordering::Relaxed);
+
+    let spwm = create_spwm();
+    let channel = spwm.get_channel(0).unwrap();
+    // 10 µs delay, 50 µs first pulse, 5 µs gap, 3 µs second pulse at 1 MHz
+    let pattern = DoublePulse::from_micros(10, 50, 5, 3, 1_000_000).unwrap();
+
+    assert_eq!(
+        pattern,
+        DoublePulse {
+            delay_ticks: 10,
+            first_ticks: 50,
+            gap_ticks: 5,
+            second_ticks: 3,
+        }
+    );
+
+    channel.double_pulse(&pattern).unwrap();
+
+    assert!(channel.is_double_pulse_active());
+    assert_eq!(
+        channel.double_pulse(&pattern),
+        Err(SpwmError::AlreadyEnabled)
+    );
+
+    let mut on_ticks = Vec::new();
+
+    for tick in 0..1_000 {
+        if OUTPUT_ON.load(Ordering::Relaxed) {
+            on_ticks.push(tick);
+        }
+
+        spwm.irq_handler();
+    }
+
+    let expected: Vec<u32> = (10..60).chain(65..68).collect();
+
+    assert_eq!(on_ticks, expected);
+    assert_eq!(COMPLETED.load(Ordering::Relaxed), 1);
+    assert!(!channel.is_double_pulse_active());
+    assert!(!channel.is_enabled());
+    assert_eq!(channel.duty_cycle(), 50);
+}
+
+#[test]
+fn double_pulse_rejects_empty_pulses() {
+    let spwm = create_spwm();
+    let channel = spwm.get_channel(0).unwrap();
+
+    assert_eq!(
+        channel.double_pulse(&DoublePulse {
+            delay_ticks: 0,
+            first_ticks: 10,
+            gap_ticks: 0,
+            second_ticks: 5,
+        }),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+    assert_eq!(
+        DoublePulse::from_micros(0, 10, 5, 0, 1_000_000),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+}
+
+#[test]
+fn disable_aborts_double_pulse() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+    COMPLETED.store(0, Ordering::Relaxed);
+
+    let spwm = create_spwm();
+    let channel = spwm.get_channel(0).unwrap();
+
+    channel
+        .double_pulse(&DoublePulse {
+            delay_ticks: 0,
+            first_ticks: 10,
+            gap_ticks: 5,
+            second_ticks: 5,
+        })
+        .unwrap();
+
+    assert!(OUTPUT_ON.load(Ordering::Relaxed));
+
+    channel.disable().unwrap();
+
+    assert!(!OUTPUT_ON.load(Ordering::Relaxed));
+    assert!(!channel.is_double_pulse_active());
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(COMPLETED.load(Ordering::Relaxed), 0);
+}
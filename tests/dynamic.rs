@@ -0,0 +1,170 @@
+#![cfg(feature = "alloc")]
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use spwm::{IRQ_ERROR_UNCONFIGURED, SpwmChannel, SpwmDyn, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static TEST_ON_TICKS: [AtomicU32; 3] = [const { AtomicU32::new(0) }; 3];
+static TEST_ON: Mutex<[bool; 3]> = Mutex::new([false; 3]);
+
+fn on_off_test_callback<const I: usize>(state: &SpwmState) {
+    if let Some(on) = TEST_ON.lock().unwrap().get_mut(I) {
+        *on = matches!(state, SpwmState::On);
+    }
+}
+
+fn run(spwm: &SpwmDyn, ticks: u32) {
+    for _ in 0..ticks {
+        let on = *TEST_ON.lock().unwrap();
+
+        for (ticks, on) in TEST_ON_TICKS.iter().zip(on) {
+            if on {
+                ticks.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        spwm.irq_handler();
+    }
+}
+
+#[test]
+fn dynamic_manager_grows_and_reuses_slots() {
+    let mut spwm = SpwmDyn::new(100_000);
+    let callbacks = [
+        on_off_test_callback::<0>,
+        on_off_test_callback::<1>,
+        on_off_test_callback::<2>,
+    ];
+
+    for (duty_cycle, callback) in [10, 50, 90].into_iter().zip(callbacks) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        let id = spwm.register_channel(channel);
+
+        spwm.get_channel(id).unwrap().enable().unwrap();
+    }
+
+    assert_eq!(spwm.channel_count(), 3);
+
+    run(&spwm, 1000);
+
+    let on_ticks: Vec<_> = TEST_ON_TICKS
+        .iter()
+        .map(|ticks| ticks.load(Ordering::Relaxed))
+        .collect();
+
+    assert_eq!(on_ticks, [100, 500, 900]);
+    assert_eq!(spwm.ticks(), 1000);
+
+    let removed = spwm.remove_channel(1).unwrap();
+
+    assert_eq!(removed.duty_cycle(), 50);
+    assert_eq!(spwm.channel_count(), 2);
+    assert!(matches!(
+        spwm.remove_channel(1),
+        Err(SpwmError::InvalidChannel)
+    ));
+    assert_eq!(spwm.register_channel(removed), 1);
+    assert_eq!(spwm.set_channel_priority(1, 3), Ok(()));
+    assert_eq!(
+        spwm.set_channel_priority(3, 3),
+        Err(SpwmError::InvalidChannel)
+    );
+}
+
+static REMOVED_OUTPUT_ON: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn removed_channel_is_disabled_and_switched_off() {
+    let mut spwm = SpwmDyn::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(100)
+        .on_off_callback(|state| {
+            REMOVED_OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+        })
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel);
+
+    spwm.get_channel(id).unwrap().enable().unwrap();
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    assert!(REMOVED_OUTPUT_ON.load(Ordering::Relaxed));
+
+    let removed = spwm.remove_channel(id).unwrap();
+
+    assert!(!removed.is_enabled());
+    assert!(!REMOVED_OUTPUT_ON.load(Ordering::Relaxed));
+}
+
+static REUSED_PERIODS: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn dynamic_manager_processes_reused_slot_once_and_reports_errors() {
+    let mut spwm = SpwmDyn::new(100_000);
+
+    for _ in 0..2 {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(50)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel);
+    }
+
+    spwm.remove_channel(0).unwrap();
+
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {
+            REUSED_PERIODS.fetch_add(1, Ordering::Relaxed);
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(spwm.register_channel(channel), 0);
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    let unconfigured = spwm.register_channel(SpwmChannel::default());
+
+    spwm.get_channel(unconfigured).unwrap().enable().unwrap();
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(REUSED_PERIODS.load(Ordering::Relaxed), 10);
+    assert_eq!(spwm.take_errors(), IRQ_ERROR_UNCONFIGURED);
+    assert_eq!(spwm.take_errors(), 0);
+}
+
+#[test]
+fn try_new_rejects_unusable_hardware_frequencies() {
+    assert!(SpwmDyn::try_new(1_000_000).is_ok());
+    assert!(matches!(
+        SpwmDyn::try_new(0),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+    assert!(matches!(
+        SpwmDyn::try_with_capacity(u32::MAX, 4),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+}
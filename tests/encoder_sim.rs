@@ -0,0 +1,85 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use spwm::{EncoderDirection, EncoderSim, OnOffCallback, Spwm, SpwmState};
+use std::sync::Mutex;
+
+static EDGES: Mutex<Vec<(u8, u32)>> = Mutex::new(Vec::new());
+static TICK: AtomicU32 = AtomicU32::new(0);
+
+fn record_on_edge(channel: u8, state: &SpwmState) {
+    if matches!(state, SpwmState::On) {
+        EDGES
+            .lock()
+            .unwrap()
+            .push((channel, TICK.load(Ordering::Relaxed)));
+    }
+}
+
+fn a_callback(state: &SpwmState) {
+    record_on_edge(0, state);
+}
+
+fn b_callback(state: &SpwmState) {
+    record_on_edge(1, state);
+}
+
+fn index_callback(state: &SpwmState) {
+    record_on_edge(2, state);
+}
+
+fn run<const N: usize>(spwm: &Spwm<N>, ticks: u32) -> Vec<(u8, u32)> {
+    EDGES.lock().unwrap().clear();
+
+    for _ in 0..ticks {
+        spwm.irq_handler();
+        TICK.fetch_add(1, Ordering::Relaxed);
+    }
+
+    EDGES.lock().unwrap().clone()
+}
+
+fn b_phase(edges: &[(u8, u32)], period: u32) -> u32 {
+    let tick = |channel| edges.iter().find(|edge| edge.0 == channel).unwrap().1;
+
+    (tick(1) + period - tick(0)) % period
+}
+
+#[test]
+fn encoder_sim_direction_and_index() {
+    let mut spwm = Spwm::<3>::new(100_000);
+    let callbacks: [OnOffCallback; 3] = [a_callback, b_callback, index_callback];
+    let mut ids = [0; 3];
+
+    for (id, callback) in ids.iter_mut().zip(callbacks) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(100)
+            .duty_cycle(50)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    let mut encoder = EncoderSim::new(&mut spwm, ids[0], ids[1], Some((ids[2], 4))).unwrap();
+
+    assert!(run(&spwm, 1000).is_empty());
+
+    assert!(encoder.set_velocity(&mut spwm, 1000).is_ok());
+    assert_eq!(encoder.direction(), EncoderDirection::Forward);
+    run(&spwm, 100);
+    let edges = run(&spwm, 800);
+
+    assert_eq!(b_phase(&edges, 100), 25);
+    assert_eq!(edges.iter().filter(|edge| edge.0 == 2).count(), 2);
+
+    assert!(encoder.set_velocity(&mut spwm, -1000).is_ok());
+    assert_eq!(encoder.direction(), EncoderDirection::Reverse);
+    run(&spwm, 200);
+
+    assert_eq!(b_phase(&run(&spwm, 100), 100), 75);
+
+    assert!(encoder.set_velocity(&mut spwm, 0).is_ok());
+    run(&spwm, 1);
+    assert!(run(&spwm, 1000).is_empty());
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "inputs")]
+
+use spwm::{Input, Inputs, Spwm};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static BUTTON: AtomicBool = AtomicBool::new(false);
+static CHANGES: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+static INPUTS: Inputs<1> = Inputs::new([Input::new(read_button, button_changed)], 3);
+
+fn read_button() -> bool {
+    BUTTON.load(Ordering::Relaxed)
+}
+
+fn button_changed(level: bool) {
+    CHANGES.lock().unwrap().push(level);
+}
+
+#[test]
+fn inputs_debounce_bouncing_contact() {
+    let mut spwm = Spwm::<1, 1>::new(100_000);
+
+    spwm.set_periodic_alarm(10, || INPUTS.sample()).unwrap();
+
+    // One raw level per sample: a bouncing press, a held press and a bouncing release
+    let raw = [
+        true, false, true, true, false, true, true, true, true, false, true, false, false, false,
+        false,
+    ];
+
+    for level in raw {
+        BUTTON.store(level, Ordering::Relaxed);
+
+        for _ in 0..10 {
+            spwm.irq_handler();
+        }
+    }
+
+    assert_eq!(*CHANGES.lock().unwrap(), [true, false]);
+    assert_eq!(INPUTS.level(0), Some(false));
+    assert_eq!(INPUTS.level(1), None);
+}
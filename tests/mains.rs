@@ -0,0 +1,169 @@
+use spwm::{ChainMode, DimmerEdge, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OUTPUT_ON: AtomicBool = AtomicBool::new(false);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn create_spwm() -> Spwm<2> {
+    let mut spwm = Spwm::<2>::new(10_000);
+
+    for _ in 0..2 {
+        // 100 Hz: one period per 50 Hz mains half-cycle
+        let channel = spwm
+            .create_channel()
+            .freq_hz(100)
+            .duty_cycle(30)
+            .on_off_callback(|state| {
+                OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+            })
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+/// Runs two half-cycles and returns the ticks the output was on in the second one.
+fn on_ticks_of_half_cycle(spwm: &Spwm<2>) -> Vec<u32> {
+    let mut on_ticks = Vec::new();
+
+    for tick in 0..200 {
+        if tick % 100 == 0 {
+            spwm.sync_to_zero_cross(0).unwrap();
+        }
+
+        spwm.irq_handler();
+
+        if tick >= 100 && OUTPUT_ON.load(Ordering::Relaxed) {
+            on_ticks.push(tick - 100);
+        }
+    }
+
+    on_ticks
+}
+
+#[test]
+fn phase_angle_edges_place_conduction_in_half_cycle() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    for (edge, expected) in [
+        (DimmerEdge::Trailing, (0..30).collect::<Vec<_>>()),
+        (
+            DimmerEdge::Leading {
+                gate_pulse_ticks: 0,
+            },
+            (70..100).collect(),
+        ),
+        (
+            DimmerEdge::Leading {
+                gate_pulse_ticks: 5,
+            },
+            (70..75).collect(),
+        ),
+    ] {
+        let spwm = create_spwm();
+
+        OUTPUT_ON.store(false, Ordering::Relaxed);
+        spwm.set_phase_angle(0, edge).unwrap();
+        spwm.get_channel(0).unwrap().enable().unwrap();
+
+        // Waits for the first zero cross
+        assert!(!OUTPUT_ON.load(Ordering::Relaxed));
+        assert_eq!(on_ticks_of_half_cycle(&spwm), expected);
+    }
+}
+
+#[test]
+fn missing_zero_cross_switches_output_off() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let spwm = create_spwm();
+
+    spwm.set_phase_angle(
+        0,
+        DimmerEdge::Leading {
+            gate_pulse_ticks: 0,
+        },
+    )
+    .unwrap();
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.sync_to_zero_cross(0).unwrap();
+
+    for _ in 0..300 {
+        spwm.irq_handler();
+    }
+
+    assert!(!OUTPUT_ON.load(Ordering::Relaxed));
+    assert_eq!(spwm.sync_to_zero_cross(1), Err(SpwmError::InvalidMode));
+
+    spwm.clear_mains_sync(0).unwrap();
+
+    assert_eq!(spwm.sync_to_zero_cross(0), Err(SpwmError::InvalidMode));
+}
+
+#[test]
+fn burst_fire_passes_spread_half_cycles() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let mut spwm = Spwm::<1>::new(10_000);
+    // Slightly below 100 Hz, so the 100-tick half-cycles restart before the 101-tick period ends
+    let channel = spwm
+        .create_channel()
+        .freq_hz(99)
+        .duty_cycle(0)
+        .on_off_callback(|state| {
+            OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+        })
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    assert_eq!(
+        spwm.set_burst_fire(channel_id, 3, 2),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+    spwm.set_burst_fire(channel_id, 2, 6).unwrap();
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    let run_half_cycle = || {
+        spwm.sync_to_zero_cross(channel_id).unwrap();
+
+        (0..100)
+            .filter(|_| {
+                spwm.irq_handler();
+                OUTPUT_ON.load(Ordering::Relaxed)
+            })
+            .count()
+    };
+    let pattern: Vec<_> = (0..12).map(|_| run_half_cycle()).collect();
+
+    assert_eq!(pattern, [0, 0, 100, 0, 0, 100, 0, 0, 100, 0, 0, 100]);
+
+    // Full power keeps the output on across zero crosses
+    spwm.set_burst_fire(channel_id, 1, 1).unwrap();
+
+    assert_eq!(run_half_cycle(), 100);
+    assert_eq!(run_half_cycle(), 100);
+}
+
+#[test]
+fn chained_channel_cannot_become_mains_synchronized() {
+    let mut spwm = create_spwm();
+
+    spwm.chain(0, 1, ChainMode::EveryNthPeriod(1)).unwrap();
+
+    assert_eq!(
+        spwm.set_phase_angle(
+            1,
+            DimmerEdge::Leading {
+                gate_pulse_ticks: 0,
+            },
+        ),
+        Err(SpwmError::InvalidMode)
+    );
+}
@@ -0,0 +1,112 @@
+use spwm::{Spwm, SpwmError, SpwmState};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static PRIMARY_ON: AtomicBool = AtomicBool::new(false);
+static SECONDARY_ON: AtomicBool = AtomicBool::new(false);
+static DIVERGENCES: AtomicU32 = AtomicU32::new(0);
+
+fn create_spwm() -> Spwm<3> {
+    let mut spwm = Spwm::<3>::new(100_000);
+    let callbacks: [fn(&SpwmState); 3] = [
+        |state| PRIMARY_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed),
+        |state| SECONDARY_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed),
+        |_| {},
+    ];
+
+    for (callback, duty_cycle) in callbacks.into_iter().zip([30, 80, 50]) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+#[test]
+fn mirrored_channel_follows_primary_and_reports_divergence() {
+    let mut spwm = create_spwm();
+
+    spwm.mirror(
+        0,
+        1,
+        true,
+        Some(|primary, secondary| {
+            assert_eq!((primary, secondary), (0, 1));
+            DIVERGENCES.fetch_add(1, Ordering::Relaxed);
+        }),
+    )
+    .unwrap();
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    let mut on_ticks = 0;
+
+    for _ in 0..300 {
+        spwm.irq_handler();
+
+        assert_ne!(
+            PRIMARY_ON.load(Ordering::Relaxed),
+            SECONDARY_ON.load(Ordering::Relaxed)
+        );
+
+        if SECONDARY_ON.load(Ordering::Relaxed) {
+            on_ticks += 1;
+        }
+    }
+
+    // The secondary reproduces the inverted 30% primary instead of its own 80%
+    assert_eq!(on_ticks, 210);
+    assert_eq!(DIVERGENCES.load(Ordering::Relaxed), 0);
+
+    let secondary = spwm.get_channel(1).unwrap();
+
+    secondary.pause().unwrap();
+
+    for _ in 0..50 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(DIVERGENCES.load(Ordering::Relaxed), 1);
+
+    secondary.resume().unwrap();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    secondary.disable().unwrap();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(DIVERGENCES.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn mirror_configuration_is_validated() {
+    let mut spwm = create_spwm();
+
+    assert_eq!(
+        spwm.mirror(0, 0, false, None),
+        Err(SpwmError::InvalidChannel)
+    );
+    assert_eq!(
+        spwm.mirror(0, 3, false, None),
+        Err(SpwmError::InvalidChannel)
+    );
+
+    spwm.mirror(0, 1, false, None).unwrap();
+
+    assert_eq!(spwm.mirror(1, 2, false, None), Err(SpwmError::InvalidMode));
+    assert_eq!(spwm.mirror(2, 0, false, None), Err(SpwmError::InvalidMode));
+
+    spwm.unmirror(1).unwrap();
+    spwm.mirror(1, 2, false, None).unwrap();
+}
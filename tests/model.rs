@@ -0,0 +1,48 @@
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use spwm::test_util::strategies::{boundary_params, valid_params};
+use spwm::test_util::{ModelMismatch, PwmParams, ReferenceModel, check_against_model};
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn output_matches_reference_model(params in valid_params()) {
+        prop_assert_eq!(check_against_model(&params, 3), Ok(()));
+    }
+
+    #[test]
+    fn boundary_output_matches_reference_model(params in boundary_params()) {
+        prop_assert_eq!(check_against_model(&params, 3), Ok(()));
+    }
+}
+
+#[test]
+fn reference_model_quantizes_like_the_engine() {
+    let model = ReferenceModel::new(&PwmParams {
+        hardware_freq_hz: 100_000,
+        freq_hz: 333,
+        duty_cycle: 50,
+    });
+
+    assert_eq!(model.period_ticks, 300);
+    assert_eq!(model.on_ticks, 150);
+    assert!(model.level(149));
+    assert!(!model.level(150));
+    assert!(model.level(300));
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+    let params = PwmParams {
+        hardware_freq_hz: 1_000,
+        freq_hz: 11,
+        duty_cycle: 50,
+    };
+
+    assert!(matches!(
+        check_against_model(&params, 1),
+        Err(ModelMismatch::Rejected(_))
+    ));
+}
@@ -0,0 +1,75 @@
+use spwm::{ChainMode, MAX_HARDWARE_FREQ_HZ, Spwm, SpwmChannelBuilder, SpwmSingle};
+
+const TICKS_FOR_TEST: u32 = 10_000;
+
+#[test]
+fn extreme_configurations_do_not_panic() {
+    assert!(Spwm::<4>::try_new(u32::MAX).is_err());
+    assert!(
+        SpwmChannelBuilder::new(u32::MAX)
+            .freq_hz(1)
+            .duty_cycle(50)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .is_err()
+    );
+
+    let hardware_freq_hz = MAX_HARDWARE_FREQ_HZ;
+    let mut spwm = Spwm::<4>::new(hardware_freq_hz);
+    let mut ids = [0; 4];
+
+    for (id, (freq_hz, duty_cycle)) in ids.iter_mut().zip([
+        (hardware_freq_hz / 100, 100),
+        (1, 0),
+        (1, 99),
+        (hardware_freq_hz / 100, 1),
+    ]) {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(freq_hz)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    // a slave period that does not fit into `u32` ticks is rejected instead of overflowing
+    assert!(spwm.lock_ratio(ids[1], ids[2], u32::MAX).is_err());
+    spwm.lock_ratio(ids[0], ids[2], u32::MAX / 200).unwrap();
+    spwm.chain(ids[0], ids[3], ChainMode::EveryNthPeriod(u32::MAX))
+        .unwrap();
+
+    for &id in &ids {
+        spwm.get_channel(id).unwrap().enable().unwrap();
+    }
+
+    for _ in 0..TICKS_FOR_TEST {
+        spwm.irq_handler();
+    }
+
+    for &id in &ids {
+        let channel = spwm.get_channel(id).unwrap();
+
+        channel.increase_duty(u8::MAX).unwrap();
+        channel.decrease_duty(u8::MAX).unwrap();
+        channel.disable().unwrap();
+    }
+
+    let channel = SpwmChannelBuilder::new(100)
+        .freq_hz(1)
+        .duty_cycle(100)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let single = SpwmSingle::new(channel);
+
+    single.channel().enable().unwrap();
+
+    for _ in 0..TICKS_FOR_TEST {
+        single.irq_handler();
+    }
+}
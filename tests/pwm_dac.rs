@@ -0,0 +1,66 @@
+use spwm::{PwmDac, Spwm, SpwmError};
+
+fn create_spwm() -> Spwm<1> {
+    let mut spwm = Spwm::<1>::new(10_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+#[test]
+fn frequency_meets_ripple_target_with_tick_resolution() {
+    let spwm = create_spwm();
+    // 1 ms RC, 10 mV ripple at 3.3 V: f >= 3300 / (4 * 0.001 * 10) = 82.5 kHz
+    let mut dac = PwmDac::new(&spwm, 0, 1_000, 10, 3_300).unwrap();
+    let channel = spwm.get_channel(0).unwrap();
+
+    assert_eq!(dac.frequency_hz(), 82_500);
+    assert_eq!(channel.validate().period_ticks, 121);
+    assert_eq!(channel.validate().on_ticks, 0);
+    assert_eq!(dac.resolution_uv(3_300), 27_272);
+
+    // 1.65 V is 60.5 ticks, rounded to 61
+    let settling_periods = dac.set_voltage_mv(&spwm, 1_650, 3_300).unwrap();
+
+    assert_eq!(channel.validate().on_ticks, 61);
+    assert_eq!(channel.duty_cycle(), 50);
+    // RC * ln(122) * f = 0.001 * 4.8 * 82500 (4.79 with the logarithm approximation)
+    assert_eq!(settling_periods, 395);
+
+    // a lower supply needs a longer on-time for the same voltage
+    dac.set_voltage_mv(&spwm, 1_650, 3_000).unwrap();
+
+    assert_eq!(channel.validate().on_ticks, 67);
+    assert_eq!(dac.set_voltage_mv(&spwm, 1_650, 3_000), Ok(0));
+    assert_eq!(
+        dac.set_voltage_mv(&spwm, 3_500, 3_300),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn unreachable_ripple_is_rejected() {
+    let spwm = create_spwm();
+
+    // would need 825 kHz, above 1/100 of the 10 MHz timer
+    assert!(matches!(
+        PwmDac::new(&spwm, 0, 1_000, 1, 3_300),
+        Err(SpwmError::InvalidFrequency)
+    ));
+    assert!(matches!(
+        PwmDac::new(&spwm, 0, 0, 10, 3_300),
+        Err(SpwmError::InvalidFrequency)
+    ));
+    assert!(matches!(
+        PwmDac::new(&spwm, 1, 1_000, 10, 3_300),
+        Err(SpwmError::InvalidChannel)
+    ));
+}
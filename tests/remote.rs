@@ -0,0 +1,84 @@
+#![cfg(feature = "remote")]
+
+use spwm::{RemoteAction, RemoteCommand, RemoteResponse, RemoteStatus, Spwm};
+
+fn execute<const N: usize>(
+    spwm: &mut Spwm<N>,
+    action: RemoteAction,
+    channel: u8,
+    value: u32,
+) -> RemoteResponse {
+    let frame = RemoteCommand {
+        action,
+        channel,
+        value,
+    }
+    .encode();
+
+    assert_eq!(RemoteCommand::decode(&frame).unwrap().value, value);
+
+    spwm.remote_execute(&frame)
+}
+
+#[test]
+fn remote_commands_map_to_channel_operations() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(10)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    spwm.register_channel(channel).unwrap();
+
+    let ok = |value| RemoteResponse {
+        status: RemoteStatus::Ok,
+        value,
+    };
+
+    assert_eq!(execute(&mut spwm, RemoteAction::SetDuty, 0, 40), ok(0));
+    assert_eq!(execute(&mut spwm, RemoteAction::GetDuty, 0, 0), ok(40));
+    assert_eq!(
+        execute(&mut spwm, RemoteAction::SetFrequency, 0, 250),
+        ok(0)
+    );
+    assert_eq!(
+        execute(&mut spwm, RemoteAction::GetFrequency, 0, 0),
+        ok(250)
+    );
+    assert_eq!(execute(&mut spwm, RemoteAction::Enable, 0, 0), ok(0));
+    assert_eq!(execute(&mut spwm, RemoteAction::GetEnabled, 0, 0), ok(1));
+
+    let status = |response: RemoteResponse| response.status;
+
+    assert_eq!(
+        status(execute(&mut spwm, RemoteAction::Enable, 0, 0)),
+        RemoteStatus::AlreadyEnabled
+    );
+    assert_eq!(
+        status(execute(&mut spwm, RemoteAction::SetDuty, 0, 101)),
+        RemoteStatus::ValueOutOfRange
+    );
+    assert_eq!(
+        status(execute(&mut spwm, RemoteAction::SetDuty, 0, 300)),
+        RemoteStatus::ValueOutOfRange
+    );
+    assert_eq!(
+        status(execute(&mut spwm, RemoteAction::SetFrequency, 0, 2000)),
+        RemoteStatus::InvalidFrequency
+    );
+    assert_eq!(
+        status(execute(&mut spwm, RemoteAction::Disable, 1, 0)),
+        RemoteStatus::InvalidChannel
+    );
+    assert_eq!(
+        spwm.remote_execute(&[9, 0, 0, 0, 0, 0]).encode(),
+        [RemoteStatus::UnknownAction as u8, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        spwm.remote_execute(&[1, 0, 0]).status,
+        RemoteStatus::MalformedFrame
+    );
+}
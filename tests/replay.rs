@@ -0,0 +1,76 @@
+#![cfg(feature = "replay")]
+
+use spwm::{ControlOp, ReplayLog, ReplayRecord, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static TRACE: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+
+fn create_spwm() -> Spwm<1> {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(|state| {
+            TRACE.lock().unwrap().push(matches!(state, SpwmState::On));
+        })
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+#[test]
+fn replay_reproduces_recorded_operations() {
+    let mut buffer = [ReplayRecord::default(); 3];
+    let mut log = ReplayLog::new(&mut buffer);
+    let spwm = create_spwm();
+    let ops = [
+        (0, ControlOp::Enable),
+        (130, ControlOp::DutyCycle(20)),
+        (250, ControlOp::Frequency(500)),
+        (420, ControlOp::Disable),
+    ];
+
+    for tick in 0..500 {
+        for &(_, op) in ops.iter().filter(|(at, _)| *at == tick) {
+            spwm.execute(&mut log, 0, op).unwrap();
+        }
+
+        spwm.irq_handler();
+    }
+
+    assert_eq!(
+        spwm.execute(&mut log, 0, ControlOp::Disable),
+        Err(SpwmError::AlreadyDisabled)
+    );
+    assert_eq!(log.dropped(), 1);
+    assert_eq!(
+        log.records()[1],
+        ReplayRecord {
+            tick: 130,
+            channel: 0,
+            op: ControlOp::DutyCycle(20),
+        }
+    );
+
+    let recorded = std::mem::take(&mut *TRACE.lock().unwrap());
+    let records: Vec<_> = log
+        .records()
+        .iter()
+        .copied()
+        .chain([ReplayRecord {
+            tick: 420,
+            channel: 0,
+            op: ControlOp::Disable,
+        }])
+        .collect();
+
+    create_spwm().replay(&records, 500).unwrap();
+
+    assert_eq!(*TRACE.lock().unwrap(), recorded);
+    assert!(recorded.len() > 4);
+}
@@ -0,0 +1,93 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use spwm::{ResonanceTracker, Spwm, SpwmError};
+use std::sync::Mutex;
+
+static TEST_FREQ_HZ: AtomicU32 = AtomicU32::new(0);
+static TEST_RESONANCE_HZ: AtomicU32 = AtomicU32::new(0);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn response() -> u32 {
+    1_000
+        - TEST_FREQ_HZ
+            .load(Ordering::Relaxed)
+            .abs_diff(TEST_RESONANCE_HZ.load(Ordering::Relaxed))
+}
+
+fn create_spwm() -> Spwm<1> {
+    let mut spwm = Spwm::<1>::new(1_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+#[test]
+fn sweep_finds_and_tracks_resonance() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_RESONANCE_HZ.store(1_030, Ordering::Relaxed);
+
+    let spwm = create_spwm();
+    let mut tracker = ResonanceTracker::new(&spwm, 0, 900, 1_200, 50, response).unwrap();
+    let mut sweep = vec![tracker.frequency()];
+
+    TEST_FREQ_HZ.store(tracker.frequency(), Ordering::Relaxed);
+
+    while tracker.resonance_hz().is_none() {
+        let freq_hz = tracker.poll(&spwm).unwrap();
+
+        TEST_FREQ_HZ.store(freq_hz, Ordering::Relaxed);
+        sweep.push(freq_hz);
+    }
+
+    // the sweep ends on the best sampled frequency
+    assert_eq!(sweep, [900, 950, 1_000, 1_050, 1_100, 1_150, 1_200, 1_050]);
+    assert_eq!(tracker.resonance_hz(), Some(1_050));
+
+    for _ in 0..20 {
+        TEST_FREQ_HZ.store(tracker.poll(&spwm).unwrap(), Ordering::Relaxed);
+    }
+
+    assert_eq!(tracker.resonance_hz(), Some(1_026));
+
+    // the resonance drifts up and the tracker follows in fine steps
+    TEST_RESONANCE_HZ.store(1_070, Ordering::Relaxed);
+
+    for _ in 0..40 {
+        TEST_FREQ_HZ.store(tracker.poll(&spwm).unwrap(), Ordering::Relaxed);
+    }
+
+    assert_eq!(tracker.resonance_hz(), Some(1_074));
+    assert_eq!(
+        spwm.get_channel(0).unwrap().validate().period_ticks,
+        1_000_000 / tracker.frequency()
+    );
+}
+
+#[test]
+fn invalid_band_is_rejected() {
+    let spwm = create_spwm();
+
+    assert!(matches!(
+        ResonanceTracker::new(&spwm, 0, 1_200, 900, 50, response),
+        Err(SpwmError::InvalidFrequency)
+    ));
+    assert!(matches!(
+        ResonanceTracker::new(&spwm, 0, 900, 1_200, 0, response),
+        Err(SpwmError::InvalidFrequency)
+    ));
+    assert!(matches!(
+        ResonanceTracker::new(&spwm, 0, 900, 20_000, 50, response),
+        Err(SpwmError::InvalidFrequency)
+    ));
+    assert!(matches!(
+        ResonanceTracker::new(&spwm, 1, 900, 1_200, 50, response),
+        Err(SpwmError::InvalidChannel)
+    ));
+}
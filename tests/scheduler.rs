@@ -0,0 +1,84 @@
+use spwm::{SpwmError, TickScheduler};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static ONE_SHOT: AtomicU32 = AtomicU32::new(0);
+static PERIODIC: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn compare_events_fire_at_their_tick() {
+    let mut scheduler = TickScheduler::<2>::new();
+    // compare values wrap around the end of the counter
+    let one_shot = scheduler
+        .add_compare(u32::MAX - 4, 0, || {
+            ONE_SHOT.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+    let periodic = scheduler
+        .add_compare(u32::MAX - 9, 10, || {
+            PERIODIC.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+    assert_eq!(
+        scheduler.add_compare(0, 0, || {}),
+        Err(SpwmError::NoAlarmSlotAvailable)
+    );
+
+    let mut fired = Vec::new();
+    let mut tick = u32::MAX - 20;
+
+    for _ in 0..40 {
+        let before = (
+            ONE_SHOT.load(Ordering::Relaxed),
+            PERIODIC.load(Ordering::Relaxed),
+        );
+
+        scheduler.process(tick);
+
+        if ONE_SHOT.load(Ordering::Relaxed) != before.0 {
+            fired.push(("one-shot", tick));
+        }
+
+        if PERIODIC.load(Ordering::Relaxed) != before.1 {
+            fired.push(("periodic", tick));
+        }
+
+        tick = tick.wrapping_add(1);
+    }
+
+    assert_eq!(
+        fired,
+        [
+            ("periodic", u32::MAX - 9),
+            ("one-shot", u32::MAX - 4),
+            ("periodic", 0),
+            ("periodic", 10),
+        ]
+    );
+    assert!(!scheduler.is_pending(one_shot));
+    assert!(scheduler.is_pending(periodic));
+
+    scheduler.remove_compare(periodic).unwrap();
+
+    assert_eq!(
+        scheduler.remove_compare(periodic),
+        Err(SpwmError::InvalidAlarm)
+    );
+    assert_eq!(scheduler.process(tick), u32::MAX);
+    assert_eq!(
+        scheduler.add_compare(0, 1 << 31, || {}),
+        Err(SpwmError::InvalidAlarm)
+    );
+}
+
+#[test]
+fn process_reports_ticks_until_next_event() {
+    let mut scheduler = TickScheduler::<2>::default();
+
+    scheduler.add_compare(50, 0, || {}).unwrap();
+    scheduler.add_compare(20, 100, || {}).unwrap();
+
+    assert_eq!(scheduler.process(10), 9);
+    assert_eq!(scheduler.process(20), 29);
+    assert_eq!(scheduler.process(50), 69);
+}
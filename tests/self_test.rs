@@ -0,0 +1,81 @@
+use spwm::{ChainMode, SelfTestReport, Spwm, SpwmError, SpwmState};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PIN: AtomicBool = AtomicBool::new(false);
+static STUCK_PIN: AtomicBool = AtomicBool::new(false);
+
+fn create_spwm() -> Spwm<2> {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let callbacks: [fn(&SpwmState); 2] = [
+        |state| PIN.store(matches!(state, SpwmState::On), Ordering::Relaxed),
+        |_| {},
+    ];
+
+    for callback in callbacks {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(30)
+            .on_off_callback(callback)
+            .period_callback(|| {})
+            .start_delay_ticks(15)
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+#[test]
+fn self_test_passes_with_wired_readback() {
+    let mut spwm = create_spwm();
+    let report = spwm
+        .self_test(0, 3, || PIN.load(Ordering::Relaxed))
+        .unwrap();
+
+    assert_eq!(
+        report,
+        SelfTestReport {
+            periods: 3,
+            period_ticks: 100,
+            expected_on_ticks: 30,
+            observed_on_ticks: 90,
+            mismatched_ticks: 0,
+        }
+    );
+    assert!(report.passed());
+    assert!(!spwm.get_channel(0).unwrap().is_enabled());
+}
+
+#[test]
+fn self_test_detects_stuck_output() {
+    let mut spwm = create_spwm();
+    let report = spwm
+        .self_test(1, 2, || STUCK_PIN.load(Ordering::Relaxed))
+        .unwrap();
+
+    assert_eq!(report.periods, 2);
+    assert_eq!(report.mismatched_ticks, 60);
+    assert!(!report.passed());
+
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    assert_eq!(
+        spwm.self_test(1, 2, || false),
+        Err(SpwmError::AlreadyEnabled)
+    );
+    assert_eq!(
+        spwm.self_test(2, 2, || false),
+        Err(SpwmError::InvalidChannel)
+    );
+}
+
+#[test]
+fn self_test_rejects_chained_channel() {
+    let mut spwm = create_spwm();
+
+    spwm.chain(0, 1, ChainMode::EveryNthPeriod(1)).unwrap();
+
+    assert_eq!(spwm.self_test(1, 2, || false), Err(SpwmError::InvalidMode));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "shell")]
+
+use spwm::Spwm;
+
+fn run<const N: usize>(spwm: &mut Spwm<N>, command: &str) -> String {
+    let mut response = [0; 128];
+    let len = spwm.shell_command(command, &mut response);
+
+    String::from_utf8(response[..len].to_vec()).unwrap()
+}
+
+#[test]
+fn shell_commands_control_channels() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(10)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    assert_eq!(
+        run(&mut spwm, "pwm set 0 33"),
+        "ok: channel 0 duty 33% (was 10%)\n"
+    );
+    assert_eq!(
+        run(&mut spwm, "  pwm   freq 0 500 "),
+        "ok: channel 0 frequency 500 Hz\n"
+    );
+    assert_eq!(run(&mut spwm, "pwm on 0"), "ok: channel 0 enabled\n");
+    assert!(spwm.get_channel(id).unwrap().is_enabled());
+    assert_eq!(spwm.get_channel(id).unwrap().validate().on_ticks, 66);
+    assert_eq!(run(&mut spwm, "pwm status"), "ok\n0: on 500 Hz duty 33%\n");
+    assert_eq!(run(&mut spwm, "pwm on 0"), "error: already enabled\n");
+    assert_eq!(run(&mut spwm, "pwm set 1 50"), "error: invalid channel\n");
+    assert_eq!(
+        run(&mut spwm, "pwm set 0 101"),
+        "error: invalid duty cycle\n"
+    );
+    assert!(run(&mut spwm, "pwm set 0").starts_with("error: usage"));
+    assert!(run(&mut spwm, "led on").starts_with("error: usage"));
+
+    // responses are truncated to the buffer size
+    let mut response = [0; 4];
+    assert_eq!(spwm.shell_command("pwm off 0", &mut response), 4);
+    assert_eq!(&response, b"ok: ");
+    assert!(!spwm.get_channel(id).unwrap().is_enabled());
+}
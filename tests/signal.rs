@@ -0,0 +1,174 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use spwm::{SignalWaveform, Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+
+static TEST_ON_OFF: AtomicBool = AtomicBool::new(false);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn on_off_test_callback(state: &SpwmState) {
+    TEST_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+/// Creates a 10 kHz carrier generating a 100 Hz waveform, 100 carrier periods per signal period.
+fn create_spwm(waveform: SignalWaveform) -> Spwm<1> {
+    let mut spwm = Spwm::<1>::new(1_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(10_000)
+        .signal_generator(waveform, 100_000)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    spwm.register_channel(channel).unwrap();
+
+    spwm
+}
+
+/// Runs `periods` carrier periods and returns the on-time ticks of each.
+fn run_periods(spwm: &Spwm<1>, periods: usize) -> Vec<u32> {
+    let mut on_ticks = vec![0; periods];
+
+    for tick in 0..periods * 100 {
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks[tick / 100] += 1;
+        }
+
+        spwm.irq_handler();
+    }
+
+    on_ticks
+}
+
+#[test]
+fn sine_modulates_duty_cycle_around_offset() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let spwm = create_spwm(SignalWaveform::Sine);
+    let channel = spwm.get_channel(0).unwrap();
+
+    assert_eq!(channel.signal_waveform(), Some(SignalWaveform::Sine));
+
+    channel.enable().unwrap();
+
+    let on_ticks = run_periods(&spwm, 100);
+
+    assert_eq!(
+        [on_ticks[0], on_ticks[25], on_ticks[50], on_ticks[75]],
+        [50, 100, 50, 0]
+    );
+    assert!(on_ticks[..25].windows(2).all(|w| w[0] <= w[1]));
+    assert!(on_ticks[25..75].windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn amplitude_and_offset_shape_triangle_and_square() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let spwm = create_spwm(SignalWaveform::Triangle);
+    let channel = spwm.get_channel(0).unwrap();
+
+    channel.set_signal_amplitude(20).unwrap();
+    channel.set_signal_offset(30).unwrap();
+    channel.enable().unwrap();
+
+    // the first period was latched with the default amplitude and offset
+    let on_ticks = run_periods(&spwm, 100);
+
+    assert_eq!([on_ticks[25], on_ticks[50], on_ticks[75]], [50, 30, 10]);
+
+    channel.set_signal_waveform(SignalWaveform::Square).unwrap();
+
+    let on_ticks = run_periods(&spwm, 100);
+
+    assert!(on_ticks[1..50].iter().all(|&ticks| ticks == 50));
+    assert!(on_ticks[51..].iter().all(|&ticks| ticks == 10));
+}
+
+#[test]
+fn signal_generator_rejects_invalid_settings() {
+    let spwm = create_spwm(SignalWaveform::Sine);
+    let channel = spwm.get_channel(0).unwrap();
+
+    assert_eq!(
+        channel.update_duty_cycle(50),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(
+        channel.modify_duty(|_| 50),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(channel.decrease_duty(10), Err(SpwmError::InvalidDutyCycle));
+    assert_eq!(
+        channel.set_signal_amplitude(101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(
+        channel.set_signal_offset(101),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    // above half the carrier frequency
+    assert_eq!(
+        channel.set_signal_frequency(6_000_000, 1_000_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        channel.set_signal_frequency(0, 1_000_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+
+    let mut spwm = Spwm::<1>::new(1_000_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(10_000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(id).unwrap();
+
+    assert_eq!(channel.signal_waveform(), None);
+    assert_eq!(
+        channel.set_signal_amplitude(10),
+        Err(SpwmError::InvalidMode)
+    );
+}
+
+#[test]
+fn noise_stays_within_bounds_and_holds_level_per_sample() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    for waveform in [SignalWaveform::WhiteNoise, SignalWaveform::PinkNoise] {
+        TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+        let spwm = create_spwm(waveform);
+        let channel = spwm.get_channel(0).unwrap();
+
+        // 20%..60%, new level every 10 carrier periods (1 kHz)
+        channel.set_signal_offset(40).unwrap();
+        channel.set_signal_amplitude(20).unwrap();
+        channel.set_signal_frequency(1_000_000, 1_000_000).unwrap();
+        channel.enable().unwrap();
+
+        let on_ticks = run_periods(&spwm, 1_000);
+        let changes = on_ticks[1..].windows(2).filter(|w| w[0] != w[1]).count();
+
+        assert!(
+            on_ticks[1..]
+                .iter()
+                .all(|&ticks| (20..=60).contains(&ticks))
+        );
+        assert!(changes <= 100);
+
+        let mut levels = on_ticks[1..].to_vec();
+
+        levels.sort_unstable();
+        levels.dedup();
+
+        assert!(levels.len() > 10);
+    }
+}
@@ -0,0 +1,44 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use spwm::{SpwmChannelBuilder, SpwmSingle, SpwmState};
+
+static TEST_ON_OFF: AtomicBool = AtomicBool::new(false);
+static TEST_PERIOD: AtomicU32 = AtomicU32::new(0);
+
+fn on_off_test_callback(state: &SpwmState) {
+    TEST_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+fn period_test_callback() {
+    TEST_PERIOD.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn single_channel_generates_periods() {
+    let channel = SpwmChannelBuilder::new(100_000)
+        .freq_hz(1000)
+        .duty_cycle(40)
+        .on_off_callback(on_off_test_callback)
+        .period_callback(period_test_callback)
+        .build()
+        .unwrap();
+    let spwm = SpwmSingle::new(channel);
+
+    spwm.channel().enable().unwrap();
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+
+    let mut on_ticks = 0;
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks += 1;
+        }
+    }
+
+    assert_eq!(TEST_PERIOD.load(Ordering::Relaxed), 10);
+    assert!((400..=410).contains(&on_ticks));
+
+    let channel = spwm.into_channel();
+    assert_eq!(channel.duty_cycle(), 40);
+}
@@ -0,0 +1,63 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use spwm::{SoftSerial, Spwm, SpwmState};
+
+static SERIAL: SoftSerial<4> = SoftSerial::new();
+static TX_LEVEL: AtomicBool = AtomicBool::new(false);
+
+fn serial_level() -> Option<bool> {
+    SERIAL.next_level()
+}
+
+fn tx_callback(state: &SpwmState) {
+    TX_LEVEL.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+#[test]
+fn soft_serial_transmits_8n1_frames() {
+    let mut spwm = Spwm::<1>::new(96_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(960)
+        .duty_cycle(100)
+        .on_off_callback(tx_callback)
+        .period_callback(|| {})
+        .level_source(serial_level)
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(id).unwrap().enable().unwrap();
+    assert!(SERIAL.is_idle());
+    assert_eq!(SERIAL.write(b"A\x55\x0f\xff"), 3);
+    assert!(!SERIAL.is_idle());
+
+    // sample the line in the middle of every bit
+    let mut levels = Vec::new();
+
+    for tick in 0..40 * 100 {
+        spwm.irq_handler();
+
+        if tick % 100 == 49 {
+            levels.push(TX_LEVEL.load(Ordering::Relaxed));
+        }
+    }
+
+    let mut received = Vec::new();
+    let mut bit = 0;
+
+    while bit + 10 <= levels.len() {
+        if levels[bit] {
+            bit += 1;
+            continue;
+        }
+
+        let byte = (0..8).fold(0u8, |byte, i| byte | (u8::from(levels[bit + 1 + i]) << i));
+
+        assert!(levels[bit + 9], "missing stop bit");
+        received.push(byte);
+        bit += 10;
+    }
+
+    assert_eq!(received, b"A\x55\x0f");
+    assert!(SERIAL.is_idle());
+}
@@ -1,5 +1,9 @@
 use core::sync::atomic::{AtomicBool, AtomicU32};
-use spwm::{ChannelId, OnOffCallback, PeriodCallback, Spwm, SpwmChannel, SpwmError, SpwmState};
+use spwm::{
+    ChannelId, IRQ_ERROR_CURRENT_LIMIT, IRQ_ERROR_OVERRUN, IRQ_ERROR_PROTECTION,
+    IRQ_ERROR_UNCONFIGURED, MAX_HARDWARE_FREQ_HZ, OnOffCallback, PeriodCallback, ProtectionProfile,
+    SignalWaveform, Spwm, SpwmChannel, SpwmError, SpwmState,
+};
 use std::sync::Mutex;
 use std::sync::atomic::Ordering;
 use std::vec::Vec;
@@ -366,3 +370,1036 @@ fn on_off_callback_for_single_channel_disabled_50_duty_cycle() {
     assert_eq!(TEST_PERIOD.load(Ordering::Relaxed), expected_period);
     assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
 }
+
+static TEST_ORDER: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+fn on_off_order_callback_0(_: &SpwmState) {
+    TEST_ORDER.lock().unwrap().push(0);
+}
+
+fn on_off_order_callback_1(_: &SpwmState) {
+    TEST_ORDER.lock().unwrap().push(1);
+}
+
+#[test]
+fn channel_priority_controls_callback_order() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel0 =
+        test_create_pwm_channel_with_callbacks(&spwm, 1000, 50, on_off_order_callback_0, || {})
+            .unwrap();
+    let channel1 = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(50)
+        .on_off_callback(on_off_order_callback_1)
+        .period_callback(|| {})
+        .priority(1)
+        .build()
+        .unwrap();
+    let channel0_id = spwm.register_channel(channel0).unwrap();
+    let channel1_id = spwm.register_channel(channel1).unwrap();
+
+    spwm.get_channel(channel0_id).unwrap().enable().unwrap();
+    spwm.get_channel(channel1_id).unwrap().enable().unwrap();
+    TEST_ORDER.lock().unwrap().clear();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(*TEST_ORDER.lock().unwrap(), [1, 0, 1, 0]);
+
+    assert!(spwm.set_channel_priority(channel0_id, 2).is_ok());
+    assert_eq!(
+        spwm.set_channel_priority(2, 2),
+        Err(SpwmError::InvalidChannel)
+    );
+    TEST_ORDER.lock().unwrap().clear();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(*TEST_ORDER.lock().unwrap(), [0, 1, 0, 1]);
+}
+
+static TEST_SKIP_ON_OFF: AtomicBool = AtomicBool::new(false);
+
+fn on_off_skip_callback(state: &SpwmState) {
+    TEST_SKIP_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+fn ticks_until_off<const N: usize>(spwm: &Spwm<N>) -> u32 {
+    let mut ticks = 0;
+
+    while TEST_SKIP_ON_OFF.load(Ordering::Relaxed) {
+        spwm.irq_handler();
+        ticks += 1;
+    }
+
+    ticks
+}
+
+#[test]
+fn channel_enabled_between_events_keeps_timing() {
+    let mut reference = Spwm::<1>::new(100_000);
+    let channel =
+        test_create_pwm_channel_with_callbacks(&reference, 1000, 30, on_off_skip_callback, || {})
+            .unwrap();
+    let reference_id = reference.register_channel(channel).unwrap();
+
+    reference
+        .get_channel(reference_id)
+        .unwrap()
+        .enable()
+        .unwrap();
+    let expected_ticks = ticks_until_off(&reference);
+
+    let mut spwm = Spwm::<2>::new(100_000);
+    let slow = test_create_pwm_channel(&spwm, 10, 50).unwrap();
+    let fast = test_create_pwm_channel_with_callbacks(&spwm, 1000, 30, on_off_skip_callback, || {})
+        .unwrap();
+    let slow_id = spwm.register_channel(slow).unwrap();
+    let fast_id = spwm.register_channel(fast).unwrap();
+
+    spwm.get_channel(slow_id).unwrap().enable().unwrap();
+
+    for _ in 0..1234 {
+        spwm.irq_handler();
+    }
+
+    spwm.get_channel(fast_id).unwrap().enable().unwrap();
+
+    assert_eq!(ticks_until_off(&spwm), expected_ticks);
+}
+
+static TEST_REGISTERED_ON: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn channel_enabled_before_registration_switches_off_in_time() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let slow = test_create_pwm_channel(&spwm, 10, 50).unwrap();
+    let slow_id = spwm.register_channel(slow).unwrap();
+    let channel = test_create_pwm_channel_with_callbacks(
+        &spwm,
+        1000,
+        30,
+        |state| TEST_REGISTERED_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed),
+        || {},
+    )
+    .unwrap();
+
+    spwm.get_channel(slow_id).unwrap().enable().unwrap();
+    channel.enable().unwrap();
+
+    // the slow channel leaves the manager idle until its next edge
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    spwm.register_channel(channel).unwrap();
+
+    let mut ticks = 0;
+
+    while TEST_REGISTERED_ON.load(Ordering::Relaxed) && ticks < 1000 {
+        spwm.irq_handler();
+        ticks += 1;
+    }
+
+    assert_eq!(ticks, 30);
+}
+
+static TEST_FIRST_MANAGER_ON: AtomicBool = AtomicBool::new(false);
+static TEST_LAST_MANAGER_ON: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn immediate_update_reaches_idle_manager_among_many() {
+    // more managers than reschedule generations of their own
+    let mut managers: Vec<Spwm<1>> = (0..40).map(|_| Spwm::new(100_000)).collect();
+    let last = managers.len() - 1;
+
+    for (index, spwm) in managers.iter_mut().enumerate() {
+        let on_off_callback: OnOffCallback = match index {
+            0 => |state| {
+                TEST_FIRST_MANAGER_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+            },
+            i if i == last => |state| {
+                TEST_LAST_MANAGER_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+            },
+            _ => |_| {},
+        };
+        let channel =
+            test_create_pwm_channel_with_callbacks(spwm, 10, 50, on_off_callback, || {}).unwrap();
+        let id = spwm.register_channel(channel).unwrap();
+
+        spwm.get_channel(id).unwrap().enable().unwrap();
+    }
+
+    // every manager is idle until the off edge after 5000 ticks
+    for spwm in &managers {
+        for _ in 0..10 {
+            spwm.irq_handler();
+        }
+    }
+
+    for spwm in [&managers[0], &managers[last]] {
+        spwm.get_channel(0)
+            .unwrap()
+            .update_duty_cycle_immediate(0)
+            .unwrap();
+    }
+
+    for spwm in &managers {
+        spwm.irq_handler();
+    }
+
+    assert!(!TEST_FIRST_MANAGER_ON.load(Ordering::Relaxed));
+    assert!(!TEST_LAST_MANAGER_ON.load(Ordering::Relaxed));
+}
+
+static TEST_CALLBACKS: AtomicU32 = AtomicU32::new(0);
+
+fn on_off_count_callback(_: &SpwmState) {
+    TEST_CALLBACKS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn constant_output_invokes_callback_on_state_changes_only() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    for (duty_cycle, expected_callbacks) in [(100, 1), (0, 0)] {
+        TEST_CALLBACKS.store(0, Ordering::Relaxed);
+
+        let mut spwm = Spwm::<1>::new(100_000);
+        let channel = test_create_pwm_channel_with_callbacks(
+            &spwm,
+            1000,
+            duty_cycle,
+            on_off_count_callback,
+            || {},
+        )
+        .unwrap();
+        let channel_id = spwm.register_channel(channel).unwrap();
+        let channel = spwm.get_channel(channel_id).unwrap();
+
+        channel.enable().unwrap();
+
+        for _ in 0..PERIODS_FOR_TEST * 100 {
+            spwm.irq_handler();
+        }
+
+        assert_eq!(TEST_CALLBACKS.load(Ordering::Relaxed), expected_callbacks);
+
+        channel.disable().unwrap();
+
+        // disabling a constant-on channel switches it off, a constant-off one is already off
+        assert_eq!(
+            TEST_CALLBACKS.load(Ordering::Relaxed),
+            expected_callbacks * 2
+        );
+    }
+}
+
+static TEST_RESCALE_ON_OFF: AtomicBool = AtomicBool::new(false);
+
+fn on_off_rescale_callback(state: &SpwmState) {
+    TEST_RESCALE_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+#[test]
+fn hardware_frequency_update_keeps_output_frequency() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel =
+        test_create_pwm_channel_with_callbacks(&spwm, 500, 30, on_off_rescale_callback, || {})
+            .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = test_create_pwm_channel(&spwm, 1000, 30).unwrap();
+    let fast_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    for _ in 0..250 {
+        spwm.irq_handler();
+    }
+
+    // the 1 kHz channel cannot run from a 50 kHz timer
+    assert_eq!(
+        spwm.update_hardware_frequency(50_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        spwm.update_hardware_frequency(0),
+        Err(SpwmError::InvalidHardwareFrequency)
+    );
+    assert_eq!(
+        spwm.update_hardware_frequency(MAX_HARDWARE_FREQ_HZ + 1),
+        Err(SpwmError::InvalidHardwareFrequency)
+    );
+    assert_eq!(
+        spwm.get_channel(channel_id)
+            .unwrap()
+            .validate()
+            .period_ticks,
+        200
+    );
+
+    spwm.get_channel(fast_id)
+        .unwrap()
+        .update_frequency(250, 100_000)
+        .unwrap();
+    assert_eq!(spwm.update_hardware_frequency(50_000), Ok(()));
+
+    let validation = spwm.get_channel(channel_id).unwrap().validate();
+
+    assert_eq!(validation.period_ticks, 100);
+    assert_eq!(validation.on_ticks, 30);
+    assert_eq!(
+        spwm.get_channel(fast_id).unwrap().validate().period_ticks,
+        200
+    );
+    assert_eq!(
+        test_create_pwm_channel(&spwm, 1000, 30).err(),
+        Some(SpwmError::InvalidFrequency)
+    );
+
+    // 50 of 200 ticks elapsed with a 60 tick on-time, i.e. 25 of 100 ticks with 30 on-ticks
+    let mut ticks = 0;
+
+    while TEST_RESCALE_ON_OFF.load(Ordering::Relaxed) {
+        spwm.irq_handler();
+        ticks += 1;
+    }
+
+    assert_eq!(ticks, 5);
+
+    while !TEST_RESCALE_ON_OFF.load(Ordering::Relaxed) {
+        spwm.irq_handler();
+        ticks += 1;
+    }
+
+    assert_eq!(ticks, 75);
+}
+
+static TEST_DIVIDER_ON_OFF: AtomicBool = AtomicBool::new(false);
+
+fn on_off_divider_callback(state: &SpwmState) {
+    TEST_DIVIDER_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+#[test]
+fn tick_divider_keeps_slow_channel_timing() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let led = test_create_pwm_channel_with_callbacks(&spwm, 1, 50, on_off_divider_callback, || {})
+        .unwrap();
+    let led_id = spwm.register_channel(led).unwrap();
+    let motor = test_create_pwm_channel(&spwm, 1000, 30).unwrap();
+    let motor_id = spwm.register_channel(motor).unwrap();
+
+    assert_eq!(spwm.max_tick_divider(), u32::MAX);
+
+    spwm.get_channel(led_id).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.max_tick_divider(), 50_000);
+    assert_eq!(spwm.set_tick_divider(0), Err(SpwmError::InvalidTickDivider));
+    assert_eq!(spwm.set_tick_divider(3), Err(SpwmError::InvalidTickDivider));
+    assert_eq!(spwm.set_tick_divider(1000), Ok(()));
+    assert_eq!(spwm.tick_divider(), 1000);
+
+    for _ in 0..49 {
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_DIVIDER_ON_OFF.load(Ordering::Relaxed));
+    spwm.irq_handler();
+    assert!(!TEST_DIVIDER_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 0..50 {
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_DIVIDER_ON_OFF.load(Ordering::Relaxed));
+
+    spwm.set_tick_divider(1).unwrap();
+    spwm.get_channel(motor_id).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.max_tick_divider(), 10);
+
+    for _ in 0..49_999 {
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_DIVIDER_ON_OFF.load(Ordering::Relaxed));
+    spwm.irq_handler();
+    assert!(!TEST_DIVIDER_ON_OFF.load(Ordering::Relaxed));
+}
+
+static TEST_DIVIDER_OFFSET_ON_EDGES: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn tick_divider_keeps_pulse_offset_edges() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(100)
+        .duty_cycle(50)
+        .on_off_callback(|state| {
+            if matches!(state, SpwmState::On) {
+                TEST_DIVIDER_OFFSET_ON_EDGES.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .period_callback(|| {})
+        .pulse_offset_ticks(250)
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(id).unwrap().enable().unwrap();
+
+    // the On edge 250 ticks into the 1000 tick period limits the divider
+    assert_eq!(spwm.max_tick_divider(), 250);
+    assert_eq!(
+        spwm.set_tick_divider(500),
+        Err(SpwmError::InvalidTickDivider)
+    );
+    assert_eq!(spwm.set_tick_divider(250), Ok(()));
+
+    for _ in 0..40 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(TEST_DIVIDER_OFFSET_ON_EDGES.load(Ordering::Relaxed), 10);
+
+    // an offset changed behind the divider's back is stepped over, but still switches on
+    spwm.get_channel(id).unwrap().set_pulse_offset(100).unwrap();
+
+    for _ in 0..40 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(TEST_DIVIDER_OFFSET_ON_EDGES.load(Ordering::Relaxed), 20);
+}
+
+static TEST_DIVIDER_PREPARED: AtomicU32 = AtomicU32::new(0);
+
+fn divider_prepare_callback() {
+    TEST_DIVIDER_PREPARED.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn tick_divider_covers_prepare_and_blanking_ticks() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .prepare_callback(divider_prepare_callback, 300)
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(id).unwrap();
+
+    channel.enable().unwrap();
+
+    // the prepare callback 300 ticks before the period end limits the divider
+    assert_eq!(spwm.max_tick_divider(), 100);
+
+    channel.set_blanking_ticks(30);
+
+    assert_eq!(spwm.max_tick_divider(), 10);
+    assert_eq!(
+        spwm.set_tick_divider(100),
+        Err(SpwmError::InvalidTickDivider)
+    );
+    assert_eq!(spwm.set_tick_divider(10), Ok(()));
+
+    for _ in 0..10_000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(TEST_DIVIDER_PREPARED.load(Ordering::Relaxed), 1);
+}
+
+static TEST_SUSPEND_ON_OFF: AtomicBool = AtomicBool::new(false);
+
+fn on_off_suspend_callback(state: &SpwmState) {
+    TEST_SUSPEND_ON_OFF.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+}
+
+#[test]
+fn suspend_holds_outputs_and_resume_keeps_phase() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel =
+        test_create_pwm_channel_with_callbacks(&spwm, 1000, 30, on_off_suspend_callback, || {})
+            .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let idle = test_create_pwm_channel(&spwm, 1000, 30).unwrap();
+    let idle_id = spwm.register_channel(idle).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    for _ in 0..10 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.resume(), Err(SpwmError::NotSuspended));
+    assert_eq!(spwm.suspend(), Ok(()));
+    assert_eq!(spwm.suspend(), Err(SpwmError::AlreadySuspended));
+    assert!(spwm.is_suspended());
+    assert!(!TEST_SUSPEND_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert!(!TEST_SUSPEND_ON_OFF.load(Ordering::Relaxed));
+    assert_eq!(spwm.resume(), Ok(()));
+    assert!(TEST_SUSPEND_ON_OFF.load(Ordering::Relaxed));
+    assert!(spwm.get_channel(channel_id).unwrap().is_enabled());
+    assert!(!spwm.get_channel(idle_id).unwrap().is_enabled());
+
+    // 10 of 30 on-ticks elapsed before the suspension
+    let mut ticks = 0;
+
+    while TEST_SUSPEND_ON_OFF.load(Ordering::Relaxed) {
+        spwm.irq_handler();
+        ticks += 1;
+    }
+
+    assert_eq!(ticks, 20);
+}
+
+#[test]
+fn manager_counts_absolute_ticks() {
+    use core::time::Duration;
+    use spwm::{duration_to_ticks, ticks_to_duration};
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = test_create_pwm_channel(&spwm, 1, 50).unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    for _ in 0..150_000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.ticks(), 150_000);
+    assert_eq!(spwm.elapsed(), Some(Duration::from_millis(1500)));
+
+    spwm.set_tick_divider(50_000).unwrap();
+    spwm.irq_handler();
+    spwm.suspend().unwrap();
+    spwm.irq_handler();
+
+    assert_eq!(spwm.ticks(), 200_000);
+
+    // any divider is valid without enabled channels, which crosses the 32-bit boundary quickly
+    let mut spwm = Spwm::<1>::new(100_000);
+
+    spwm.set_tick_divider(u32::MAX).unwrap();
+
+    for _ in 0..3 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.ticks(), u64::from(u32::MAX) * 3);
+
+    assert_eq!(
+        ticks_to_duration(u64::from(u32::MAX) * 3, 1_000_000),
+        Some(Duration::new(12_884, 901_885_000))
+    );
+    assert_eq!(ticks_to_duration(1, 0), None);
+    assert_eq!(
+        duration_to_ticks(Duration::from_micros(2_500), 100_000),
+        250
+    );
+    assert_eq!(duration_to_ticks(Duration::MAX, 100_000), u64::MAX);
+}
+
+#[test]
+fn duty_buffer_updates_registered_channels() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<3>::new(100_000);
+
+    for _ in 0..2 {
+        let channel = test_create_pwm_channel_with_callbacks(
+            &spwm,
+            1000,
+            50,
+            on_off_test_callback,
+            period_test_callback,
+        )
+        .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    assert_eq!(
+        spwm.apply_duty_buffer(&[20, 101, 40]),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(spwm.get_channel(0).unwrap().duty_cycle(), 50);
+
+    assert_eq!(spwm.apply_duty_buffer(&[20, 50, 70]), Ok(1));
+    assert_eq!(spwm.get_channel(0).unwrap().duty_cycle(), 20);
+    assert_eq!(spwm.get_channel(1).unwrap().duty_cycle(), 50);
+
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.apply_duty_buffer(&[60, 10, 0]), Ok(2));
+
+    let mut on_ticks = 0;
+
+    for _ in 0..200 {
+        spwm.irq_handler();
+
+        if TEST_ON_OFF.load(Ordering::Relaxed) {
+            on_ticks += 1;
+        }
+    }
+
+    // The first period still runs with the on-time configured at enable
+    assert_eq!(on_ticks, 20 + 60);
+}
+
+#[test]
+fn duty_buffer_skips_trigger_outputs_and_signal_generators() {
+    let mut spwm = Spwm::<3>::new(1_000_000);
+    let channels = [
+        spwm.create_channel().freq_hz(10_000).duty_cycle(50),
+        spwm.create_channel().trigger_output(30_000, 100),
+        spwm.create_channel()
+            .freq_hz(10_000)
+            .signal_generator(SignalWaveform::Sine, 100_000),
+    ];
+
+    for builder in channels {
+        let channel = builder
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    let duty_cycles: Vec<_> = (0..3)
+        .map(|id| spwm.get_channel(id).unwrap().duty_cycle())
+        .collect();
+
+    assert_eq!(spwm.apply_duty_buffer(&[20, 40, 60]), Ok(1));
+    assert_eq!(spwm.get_channel(0).unwrap().duty_cycle(), 20);
+    assert_eq!(spwm.get_channel(1).unwrap().duty_cycle(), duty_cycles[1]);
+    assert_eq!(spwm.get_channel(2).unwrap().duty_cycle(), duty_cycles[2]);
+}
+
+#[test]
+fn derate_all_caps_on_time_until_restored() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    TEST_ON_OFF.store(false, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<2>::new(100_000);
+    let channel = test_create_pwm_channel_with_callbacks(
+        &spwm,
+        1000,
+        60,
+        on_off_test_callback,
+        period_test_callback,
+    )
+    .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    let on_ticks_per_period = |spwm: &Spwm<2>| {
+        (0..100)
+            .filter(|_| {
+                spwm.irq_handler();
+                TEST_ON_OFF.load(Ordering::Relaxed)
+            })
+            .count()
+    };
+
+    assert_eq!(spwm.derate_all(101), Err(SpwmError::InvalidDutyCycle));
+    assert_eq!(on_ticks_per_period(&spwm), 60);
+
+    // The cap takes effect with the period following the next boundary
+    spwm.derate_all(50).unwrap();
+
+    assert_eq!(spwm.derate_percent(), 50);
+    assert_eq!(on_ticks_per_period(&spwm), 60);
+    assert_eq!(on_ticks_per_period(&spwm), 30);
+    assert_eq!(spwm.get_channel(channel_id).unwrap().duty_cycle(), 60);
+
+    spwm.restore_all();
+
+    assert_eq!(spwm.derate_percent(), 100);
+    assert_eq!(on_ticks_per_period(&spwm), 30);
+    assert_eq!(on_ticks_per_period(&spwm), 60);
+}
+
+#[test]
+fn shed_load_sheds_lowest_priority_channels_first() {
+    let mut spwm = Spwm::<3>::new(100_000);
+
+    for (duty_cycle, shed_priority) in [(50, 2), (40, 0), (30, 1)] {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .shed_priority(shed_priority)
+            .build()
+            .unwrap();
+        let channel_id = spwm.register_channel(channel).unwrap();
+
+        spwm.get_channel(channel_id).unwrap().enable().unwrap();
+    }
+
+    assert_eq!(spwm.total_duty(), 120);
+    assert_eq!(spwm.shed_load(200), Ok(120));
+
+    // Channel 1 is disabled, channel 2 covers the remaining excess of 20%
+    assert_eq!(spwm.shed_load(60), Ok(60));
+    assert!(spwm.get_channel(0).unwrap().is_enabled());
+    assert!(!spwm.get_channel(1).unwrap().is_enabled());
+    assert_eq!(spwm.get_channel(2).unwrap().duty_cycle(), 10);
+    assert_eq!(spwm.get_channel(2).unwrap().shed_priority(), 1);
+
+    assert_eq!(spwm.shed_load(0), Ok(0));
+    assert!((0..3).all(|id| !spwm.get_channel(id).unwrap().is_enabled()));
+}
+
+#[test]
+fn shed_load_counts_and_disables_trigger_outputs() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let heater = spwm
+        .create_channel()
+        .freq_hz(100)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .shed_priority(1)
+        .build()
+        .unwrap();
+    // 250 ticks of every 1000 tick frame
+    let trigger = spwm
+        .create_channel()
+        .trigger_output(100_000, 2500)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let heater_id = spwm.register_channel(heater).unwrap();
+    let trigger_id = spwm.register_channel(trigger).unwrap();
+
+    spwm.get_channel(heater_id).unwrap().enable().unwrap();
+    spwm.get_channel(trigger_id).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.total_duty(), 75);
+
+    // The trigger output has no duty cycle to lower, so it is disabled for an excess of 15%
+    assert_eq!(spwm.shed_load(60), Ok(50));
+    assert!(!spwm.get_channel(trigger_id).unwrap().is_enabled());
+    assert!(spwm.get_channel(heater_id).unwrap().is_enabled());
+    assert_eq!(spwm.get_channel(heater_id).unwrap().duty_cycle(), 50);
+}
+
+#[test]
+fn total_weighted_duty_sums_enabled_channels() {
+    let mut spwm = Spwm::<3>::new(100_000);
+
+    for duty_cycle in [50, 25, 100] {
+        let channel = test_create_pwm_channel(&spwm, 1000, duty_cycle).unwrap();
+
+        spwm.register_channel(channel).unwrap();
+    }
+
+    let weights = [1000, 400, 2000];
+
+    assert_eq!(spwm.total_weighted_duty(&weights), 0);
+
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.total_weighted_duty(&weights), 600);
+
+    spwm.get_channel(2).unwrap().enable().unwrap();
+
+    assert_eq!(spwm.total_weighted_duty(&weights), 2600);
+    assert_eq!(spwm.total_weighted_duty(&[u16::MAX; 3]), 114_685);
+}
+
+thread_local! {
+    static REENTRANT_SPWM: std::cell::OnceCell<Spwm<1>> = const { std::cell::OnceCell::new() };
+}
+
+#[test]
+fn nested_irq_handler_call_is_dropped_and_counted() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = test_create_pwm_channel_with_callbacks(
+        &spwm,
+        1000,
+        50,
+        // Simulates the tick interrupt preempting itself on every output edge
+        |_| REENTRANT_SPWM.with(|spwm| spwm.get().map(Spwm::irq_handler).unwrap_or_default()),
+        || {},
+    )
+    .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+    REENTRANT_SPWM.with(|cell| assert!(cell.set(spwm).is_ok()));
+
+    REENTRANT_SPWM.with(|spwm| {
+        let spwm = spwm.get().unwrap();
+
+        for _ in 0..200 {
+            spwm.irq_handler();
+        }
+
+        assert_eq!(spwm.overrun_count(), 4);
+        assert_eq!(spwm.take_errors(), IRQ_ERROR_OVERRUN);
+        assert_eq!(spwm.take_errors(), 0);
+    });
+}
+
+#[test]
+fn set_phase_ticks_shifts_running_channel() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = test_create_pwm_channel_with_callbacks(
+        &spwm,
+        1000,
+        50,
+        on_off_test_callback,
+        period_test_callback,
+    )
+    .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+
+    assert_eq!(
+        spwm.set_phase_ticks(channel_id, 10),
+        Err(SpwmError::AlreadyDisabled)
+    );
+
+    spwm.get_channel(channel_id).unwrap().enable().unwrap();
+
+    for _ in 0..30 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.phase_ticks(channel_id), Ok(30));
+
+    spwm.set_phase_ticks(channel_id, 50).unwrap();
+
+    for _ in 30..100 {
+        spwm.irq_handler();
+    }
+
+    // The period after the boundary is delayed by 80 ticks with the output off
+    for _ in 100..180 {
+        assert!(!TEST_ON_OFF.load(Ordering::Relaxed));
+        spwm.irq_handler();
+    }
+
+    assert!(TEST_ON_OFF.load(Ordering::Relaxed));
+
+    for _ in 180..250 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.phase_ticks(channel_id), Ok(70));
+    assert_eq!(spwm.phase_ticks(1), Err(SpwmError::InvalidChannel));
+}
+
+#[test]
+fn set_phase_ticks_rejects_locked_channel() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let mut ids = [0; 2];
+
+    for id in &mut ids {
+        let channel = test_create_pwm_channel(&spwm, 1000, 50).unwrap();
+        *id = spwm.register_channel(channel).unwrap();
+    }
+
+    spwm.lock_ratio(ids[0], ids[1], 2).unwrap();
+    spwm.get_channel(ids[1]).unwrap().enable().unwrap();
+
+    assert_eq!(
+        spwm.set_phase_ticks(ids[1], 10),
+        Err(SpwmError::InvalidMode)
+    );
+}
+
+#[test]
+fn validate_budget_counts_coinciding_callbacks() {
+    let mut spwm = Spwm::<2, 1>::new(100_000);
+
+    for duty_cycle in [25, 50] {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    // on edge, off edge and period callback of both channels
+    assert_eq!(spwm.validate_budget(0), Ok(6));
+    assert_eq!(
+        spwm.validate_budget(1),
+        Err(SpwmError::InvalidConfiguration)
+    );
+
+    spwm.set_alarm(50, || {}).unwrap();
+    spwm.set_tick_divider(10).unwrap();
+
+    assert_eq!(spwm.validate_budget(1), Ok(7));
+    assert_eq!(
+        spwm.validate_budget(2),
+        Err(SpwmError::InvalidConfiguration)
+    );
+}
+
+#[test]
+fn take_errors_collects_channel_failures() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let profile = ProtectionProfile {
+        max_duty: 50,
+        ..ProtectionProfile::default()
+    };
+    let limited = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(20)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .protection(profile, |_| {})
+        .build()
+        .unwrap();
+    let limited = spwm.register_channel(limited).unwrap();
+    let tripped = test_create_pwm_channel_with_callbacks(&spwm, 1000, 50, |_| {}, || {}).unwrap();
+    let tripped = spwm.register_channel(tripped).unwrap();
+
+    assert_eq!(spwm.take_errors(), 0);
+
+    spwm.get_channel(limited)
+        .unwrap()
+        .update_duty_cycle(80)
+        .unwrap();
+    spwm.get_channel(tripped).unwrap().enable().unwrap();
+    spwm.get_channel(tripped).unwrap().trip_current_limit();
+
+    assert_eq!(
+        spwm.take_errors(),
+        IRQ_ERROR_PROTECTION | IRQ_ERROR_CURRENT_LIMIT
+    );
+    assert_eq!(spwm.take_errors(), 0);
+}
+
+#[test]
+fn try_new_rejects_unusable_hardware_frequencies() {
+    assert!(Spwm::<1>::try_new(1_000_000).is_ok());
+    assert!(matches!(
+        Spwm::<1>::try_new(0),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+    assert!(matches!(
+        Spwm::<1>::try_new(MAX_HARDWARE_FREQ_HZ + 1),
+        Err(SpwmError::InvalidHardwareFrequency)
+    ));
+}
+
+static UNCONFIGURED_NEIGHBOUR_PERIODS: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn unconfigured_channel_is_skipped_and_reported() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    let unconfigured = SpwmChannel::default();
+
+    assert!(!unconfigured.is_configured());
+
+    let unconfigured_id = spwm.register_channel(unconfigured).unwrap();
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {
+            UNCONFIGURED_NEIGHBOUR_PERIODS.fetch_add(1, Ordering::Relaxed);
+        })
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    assert!(spwm.get_channel(id).unwrap().is_configured());
+    spwm.get_channel(unconfigured_id).unwrap().enable().unwrap();
+    spwm.get_channel(id).unwrap().enable().unwrap();
+
+    for _ in 0..1_000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(UNCONFIGURED_NEIGHBOUR_PERIODS.load(Ordering::Relaxed), 10);
+    assert_eq!(spwm.take_errors(), IRQ_ERROR_UNCONFIGURED);
+
+    spwm.get_channel(unconfigured_id)
+        .unwrap()
+        .disable()
+        .unwrap();
+
+    for _ in 0..1_000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.take_errors(), 0);
+}
+
+static LONG_RUN_ON_EDGES: AtomicU32 = AtomicU32::new(0);
+static LONG_RUN_PERIODS: AtomicU32 = AtomicU32::new(0);
+static LONG_RUN_ALARMS: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn long_run_crosses_u32_tick_boundary() {
+    let mut spwm = Spwm::<1, 1>::new(102_400_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .on_off_callback(|state| {
+            if matches!(state, SpwmState::On) {
+                LONG_RUN_ON_EDGES.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .period_callback(|| {
+            LONG_RUN_PERIODS.fetch_add(1, Ordering::Relaxed);
+        })
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    spwm.set_periodic_alarm(1 << 30, || {
+        LONG_RUN_ALARMS.fetch_add(1, Ordering::Relaxed);
+    })
+    .unwrap();
+    spwm.get_channel(id).unwrap().enable().unwrap();
+    spwm.set_tick_divider(spwm.max_tick_divider()).unwrap();
+    assert_eq!(spwm.tick_divider(), 51_200);
+
+    for _ in 0..83_888 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(spwm.ticks(), 83_888 * 51_200);
+    assert!(spwm.ticks() > u64::from(u32::MAX));
+    assert_eq!(LONG_RUN_PERIODS.load(Ordering::Relaxed), 41_944);
+    assert_eq!(LONG_RUN_ON_EDGES.load(Ordering::Relaxed), 41_945);
+    assert_eq!(LONG_RUN_ALARMS.load(Ordering::Relaxed), 4);
+}
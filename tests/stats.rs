@@ -0,0 +1,50 @@
+#![cfg(feature = "stats")]
+
+use spwm::Spwm;
+
+#[test]
+fn stats_accumulate_on_time_across_skipped_ticks() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1000)
+        .duty_cycle(30)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let channel_id = spwm.register_channel(channel).unwrap();
+    let channel = spwm.get_channel(channel_id).unwrap();
+
+    assert_eq!(channel.on_time_ratio(), None);
+
+    channel.enable().unwrap();
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(channel.total_ticks(), 1000);
+    assert_eq!(channel.total_on_ticks(), 300);
+    assert!((channel.on_time_ratio().unwrap() - 0.3).abs() < 1e-4);
+
+    // Paused output delivers no on-time, disabled channels accumulate nothing
+    channel.pause().unwrap();
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    channel.disable().unwrap();
+
+    for _ in 0..1000 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(channel.total_ticks(), 2000);
+    assert_eq!(channel.total_on_ticks(), 300);
+
+    channel.reset_stats();
+
+    assert_eq!(channel.on_time_ratio(), None);
+}
@@ -0,0 +1,184 @@
+use spwm::{Spwm, SpwmError, StepDirection, Stepper, StepperOutputs};
+
+fn create_spwm() -> Spwm<4> {
+    let mut spwm = Spwm::<4>::new(1_000_000);
+
+    for _ in 0..4 {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(10_000)
+            .duty_cycle(0)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm
+}
+
+fn duty_cycles(spwm: &Spwm<4>) -> Vec<u8> {
+    (0..4)
+        .map(|id| spwm.get_channel(id).unwrap().duty_cycle())
+        .collect()
+}
+
+#[test]
+fn full_steps_walk_through_bridge_quadrants() {
+    let spwm = create_spwm();
+    let outputs = StepperOutputs::Bridge {
+        a_pos: 0,
+        a_neg: 1,
+        b_pos: 2,
+        b_neg: 3,
+    };
+    let mut stepper = Stepper::new(&spwm, outputs, 1).unwrap();
+
+    assert!((0..4).all(|id| spwm.get_channel(id).unwrap().is_enabled()));
+    assert_eq!(duty_cycles(&spwm), [0, 0, 100, 0]);
+
+    let duties: Vec<_> = (0..4)
+        .map(|_| {
+            stepper.step(&spwm, StepDirection::Forward).unwrap();
+            duty_cycles(&spwm)
+        })
+        .collect();
+
+    assert_eq!(
+        duties,
+        [
+            vec![100, 0, 0, 0],
+            vec![0, 0, 0, 100],
+            vec![0, 100, 0, 0],
+            vec![0, 0, 100, 0],
+        ]
+    );
+    assert_eq!(stepper.position(), 4);
+
+    stepper.step(&spwm, StepDirection::Reverse).unwrap();
+
+    assert_eq!(duty_cycles(&spwm), [0, 100, 0, 0]);
+    assert_eq!(stepper.position(), 3);
+}
+
+#[test]
+fn microsteps_follow_sine_and_cosine() {
+    let spwm = create_spwm();
+    let outputs = StepperOutputs::Amplitude { a: 0, b: 1 };
+    let mut stepper = Stepper::new(&spwm, outputs, 4).unwrap();
+
+    let levels: Vec<_> = (0..8)
+        .map(|_| {
+            stepper.step(&spwm, StepDirection::Forward).unwrap();
+            (duty_cycles(&spwm)[..2].to_vec(), stepper.polarity())
+        })
+        .collect();
+
+    assert_eq!(
+        levels,
+        [
+            (vec![38, 92], (true, true)),
+            (vec![71, 71], (true, true)),
+            (vec![92, 38], (true, true)),
+            (vec![100, 0], (true, true)),
+            (vec![92, 38], (true, false)),
+            (vec![71, 71], (true, false)),
+            (vec![38, 92], (true, false)),
+            (vec![0, 100], (true, false)),
+        ]
+    );
+}
+
+#[test]
+fn rate_steps_on_poll_and_catches_up() {
+    let spwm = create_spwm();
+    let outputs = StepperOutputs::Amplitude { a: 0, b: 1 };
+    let mut stepper = Stepper::new(&spwm, outputs, 16).unwrap();
+
+    // 1000 microsteps/s at 1 MHz: one microstep every 1000 ticks
+    stepper.set_rate(&spwm, 1_000, 0).unwrap();
+
+    assert_eq!(stepper.poll(&spwm, 999), Ok(0));
+    assert_eq!(stepper.poll(&spwm, 1_000), Ok(1));
+    assert_eq!(stepper.poll(&spwm, 4_500), Ok(3));
+    assert_eq!(stepper.position(), 4);
+
+    stepper.set_rate(&spwm, -500, 4_500).unwrap();
+
+    assert_eq!(stepper.poll(&spwm, 8_500), Ok(2));
+    assert_eq!(stepper.position(), 2);
+
+    stepper.set_rate(&spwm, 0, 8_500).unwrap();
+
+    assert_eq!(stepper.poll(&spwm, 100_000), Ok(0));
+    assert_eq!(stepper.position(), 2);
+}
+
+#[test]
+fn invalid_configuration_is_rejected() {
+    let spwm = create_spwm();
+
+    assert!(matches!(
+        Stepper::new(&spwm, StepperOutputs::Amplitude { a: 0, b: 1 }, 3),
+        Err(SpwmError::InvalidConfiguration)
+    ));
+    assert!(matches!(
+        Stepper::new(&spwm, StepperOutputs::Amplitude { a: 0, b: 0 }, 4),
+        Err(SpwmError::InvalidChannel)
+    ));
+
+    let mut stepper = Stepper::new(&spwm, StepperOutputs::Amplitude { a: 0, b: 1 }, 4).unwrap();
+
+    assert_eq!(
+        stepper.set_rate(&spwm, 2_000_000, 0),
+        Err(SpwmError::InvalidFrequency)
+    );
+}
+
+/// Returns the signed current levels of both coils of an amplitude driven stepper.
+fn signed_levels(spwm: &Spwm<4>, stepper: &Stepper) -> (i16, i16) {
+    let duties = duty_cycles(spwm);
+    let (a_positive, b_positive) = stepper.polarity();
+    let signed = |duty: u8, positive: bool| {
+        if positive {
+            i16::from(duty)
+        } else {
+            -i16::from(duty)
+        }
+    };
+
+    (signed(duties[0], a_positive), signed(duties[1], b_positive))
+}
+
+#[test]
+fn forward_steps_let_coil_b_lead_coil_a() {
+    // Forward, coil A follows coil B to the positive peak one full step later; in reverse it
+    // reaches the negative peak instead
+    for (direction, expected) in [
+        (
+            StepDirection::Forward,
+            [(100, 0), (0, -100), (-100, 0), (0, 100)],
+        ),
+        (
+            StepDirection::Reverse,
+            [(-100, 0), (0, -100), (100, 0), (0, 100)],
+        ),
+    ] {
+        let spwm = create_spwm();
+        let outputs = StepperOutputs::Amplitude { a: 0, b: 1 };
+        let mut stepper = Stepper::new(&spwm, outputs, 1).unwrap();
+
+        // coil B starts at its positive peak
+        assert_eq!(signed_levels(&spwm, &stepper), (0, 100));
+
+        let levels: Vec<_> = (0..4)
+            .map(|_| {
+                stepper.step(&spwm, direction).unwrap();
+                signed_levels(&spwm, &stepper)
+            })
+            .collect();
+
+        assert_eq!(levels, expected);
+    }
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "telemetry")]
+
+use spwm::{Spwm, TelemetryEvent, TelemetryRecord};
+use std::sync::Mutex;
+
+static RECORDS: Mutex<Vec<TelemetryRecord>> = Mutex::new(Vec::new());
+
+fn record(record: &TelemetryRecord) {
+    RECORDS.lock().unwrap().push(*record);
+}
+
+#[test]
+fn telemetry_mirrors_edges_and_period_ends() {
+    let mut spwm = Spwm::<2>::new(100_000);
+
+    for duty_cycle in [30, 0] {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm.set_telemetry(Some(record));
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    for _ in 0..250 {
+        spwm.irq_handler();
+    }
+
+    let expected = [
+        (30, TelemetryEvent::Off),
+        (100, TelemetryEvent::PeriodEnd),
+        (100, TelemetryEvent::On),
+        (130, TelemetryEvent::Off),
+        (200, TelemetryEvent::PeriodEnd),
+        (200, TelemetryEvent::On),
+        (230, TelemetryEvent::Off),
+    ]
+    .map(|(tick, event)| TelemetryRecord {
+        channel: 0,
+        tick,
+        event,
+    });
+
+    assert_eq!(*RECORDS.lock().unwrap(), expected);
+
+    spwm.set_telemetry(None);
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    assert_eq!(RECORDS.lock().unwrap().len(), expected.len());
+}
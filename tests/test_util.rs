@@ -0,0 +1,107 @@
+#![cfg(feature = "test-util")]
+
+use spwm::Spwm;
+use spwm::test_util::{
+    TickFaults, Trace, assert_duty_within, assert_period_duty_within, assert_phase_offset,
+};
+
+fn register_channels<const N: usize>(spwm: &mut Spwm<N>, duty_cycle: u8) {
+    for _ in 0..N {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+}
+
+#[test]
+fn trace_measures_duty_cycle() {
+    let mut spwm = Spwm::<3>::new(100_000);
+    register_channels(&mut spwm, 30);
+    spwm.get_channel(1).unwrap().update_duty_cycle(100).unwrap();
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    let trace = Trace::capture(&spwm, 550);
+
+    assert_duty_within(&trace, 0, 29.9..=30.1);
+    assert_duty_within(&trace, 1, 100.0..=100.0);
+    assert_duty_within(&trace, 2, 0.0..=0.0);
+    assert_eq!(trace.duty_percent(3), None);
+    assert_eq!(trace.levels(0).map(<[bool]>::len), Some(550));
+}
+
+#[test]
+fn trace_measures_phase_offset() {
+    let mut spwm = Spwm::<2>::new(100_000);
+    register_channels(&mut spwm, 50);
+    spwm.quadrature(0, 1).unwrap();
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    let trace = Trace::capture(&spwm, 500);
+
+    assert_phase_offset(&trace, 0, 1, 88.0..=92.0);
+    assert_phase_offset(&trace, 1, 0, 268.0..=272.0);
+}
+
+#[test]
+#[should_panic(expected = "channel 0 duty cycle Some(30.0)% is not within 49.0..=51.0%")]
+fn duty_assertion_reports_measurement() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    register_channels(&mut spwm, 30);
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    assert_duty_within(&Trace::capture(&spwm, 300), 0, 49.0..=51.0);
+}
+
+#[test]
+fn trace_with_faults_bounds_duty_error() {
+    let capture = |faults: &TickFaults| {
+        let mut spwm = Spwm::<1>::new(100_000);
+        register_channels(&mut spwm, 30);
+        spwm.get_channel(0).unwrap().enable().unwrap();
+
+        Trace::capture_with_faults(&spwm, 20_000, faults)
+    };
+
+    let ideal = capture(&TickFaults::default());
+
+    assert_eq!(
+        ideal,
+        capture(&TickFaults {
+            seed: 7,
+            ..TickFaults::default()
+        })
+    );
+    assert_eq!(ideal.dropped_ticks(), 0);
+    assert_period_duty_within(&ideal, 0, 30.0..=30.0);
+
+    let jitter = capture(&TickFaults {
+        max_jitter: 0.5,
+        seed: 1,
+        ..TickFaults::default()
+    });
+
+    assert_period_duty_within(&jitter, 0, 29.5..=30.5);
+    assert_duty_within(&jitter, 0, 29.9..=30.1);
+
+    let faults = TickFaults {
+        max_jitter: 0.5,
+        drop_probability: 0.002,
+        seed: 2,
+    };
+    let dropped = capture(&faults);
+
+    assert_eq!(dropped, capture(&faults));
+    assert!(dropped.dropped_ticks() > 10);
+    assert!(dropped.period_duty_percent(0).unwrap().len() < 200);
+    // each missed tick stretches one phase by a tick; this seed misses up to three per period
+    assert_period_duty_within(&dropped, 0, 28.5..=32.5);
+    assert_duty_within(&dropped, 0, 29.5..=30.5);
+}
@@ -0,0 +1,71 @@
+use spwm::{Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static OUTPUT_ON: AtomicBool = AtomicBool::new(false);
+static BURSTS: AtomicU32 = AtomicU32::new(0);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn burst_is_followed_by_quiet_window() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+    BURSTS.store(0, Ordering::Relaxed);
+
+    let mut spwm = Spwm::<1>::new(100_000);
+    // 1 kHz carrier: 3 periods of 100 ticks, then 200 quiet ticks
+    let channel = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .burst(3, 200)
+        .burst_complete_callback(|| {
+            BURSTS.fetch_add(1, Ordering::Relaxed);
+        })
+        .on_off_callback(|state| {
+            OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+        })
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    spwm.get_channel(id).unwrap().enable().unwrap();
+
+    let mut rising_edges = Vec::new();
+    let mut was_on = OUTPUT_ON.load(Ordering::Relaxed);
+
+    if was_on {
+        rising_edges.push(0);
+    }
+
+    for tick in 1..1_000 {
+        spwm.irq_handler();
+
+        let on = OUTPUT_ON.load(Ordering::Relaxed);
+
+        if on && !was_on {
+            rising_edges.push(tick);
+        }
+
+        was_on = on;
+    }
+
+    assert_eq!(rising_edges, [0, 100, 200, 500, 600, 700]);
+    assert_eq!(BURSTS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn burst_without_carrier_periods_is_rejected() {
+    let spwm = Spwm::<1>::new(100_000);
+    let result = spwm
+        .create_channel()
+        .freq_hz(1_000)
+        .duty_cycle(50)
+        .burst(0, 200)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build();
+
+    assert_eq!(result.err(), Some(SpwmError::InvalidPulseWidth));
+}
@@ -0,0 +1,129 @@
+use spwm::{Spwm, SpwmError, SpwmState};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OUTPUT_ON: AtomicBool = AtomicBool::new(false);
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn create_spwm(frame_rate_mhz: u32, pulse_width_us: u32) -> Result<Spwm<1>, SpwmError> {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .trigger_output(frame_rate_mhz, pulse_width_us)
+        .on_off_callback(|state| {
+            OUTPUT_ON.store(matches!(state, SpwmState::On), Ordering::Relaxed);
+        })
+        .period_callback(|| {})
+        .build()?;
+
+    spwm.register_channel(channel)?;
+
+    Ok(spwm)
+}
+
+/// Runs `ticks` ticks and returns the start tick and length of every pulse.
+fn pulses(spwm: &Spwm<1>, ticks: u32, mut on_tick: impl FnMut(u32)) -> Vec<(u32, u32)> {
+    let mut pulses: Vec<(u32, u32)> = Vec::new();
+
+    for tick in 0..ticks {
+        on_tick(tick);
+
+        if OUTPUT_ON.load(Ordering::Relaxed) {
+            match pulses.last_mut() {
+                Some((start, width)) if *start + *width == tick => *width += 1,
+                _ => pulses.push((tick, 1)),
+            }
+        }
+
+        spwm.irq_handler();
+    }
+
+    pulses
+}
+
+#[test]
+fn trigger_output_pulses_once_per_frame() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+
+    // 30 fps with 100 µs pulses: 3333 tick period, 10 tick pulse
+    let spwm = create_spwm(30_000, 100).unwrap();
+
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    assert_eq!(
+        pulses(&spwm, 10_000, |_| {}),
+        [(0, 10), (3333, 10), (6666, 10), (9999, 1)]
+    );
+    assert_eq!(
+        spwm.get_channel(0).unwrap().update_duty_cycle(50),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(
+        spwm.get_channel(0).unwrap().modify_duty(|_| 50),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+    assert_eq!(
+        spwm.get_channel(0).unwrap().increase_duty(10),
+        Err(SpwmError::InvalidDutyCycle)
+    );
+}
+
+#[test]
+fn trigger_output_keeps_pulse_width_across_frame_rate_changes() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    OUTPUT_ON.store(false, Ordering::Relaxed);
+
+    let spwm = create_spwm(30_000, 100).unwrap();
+
+    spwm.get_channel(0).unwrap().enable().unwrap();
+
+    // switch to 60 fps (1667 ticks) in the middle of the first pulse and after it
+    let result = pulses(&spwm, 5_000, |tick| {
+        if tick == 5 {
+            spwm.set_frame_rate(0, 60_000).unwrap();
+        } else if tick == 2_000 {
+            spwm.set_pulse_width(0, 200).unwrap();
+        }
+    });
+
+    assert_eq!(result, [(0, 10), (1667, 10), (3334, 20)]);
+    assert_eq!(
+        spwm.set_frame_rate(0, 100_000_000),
+        Err(SpwmError::InvalidFrequency)
+    );
+    assert_eq!(
+        spwm.set_frame_rate(0, 10_000_000),
+        Err(SpwmError::InvalidPulseWidth)
+    );
+}
+
+#[test]
+fn trigger_output_rejects_unachievable_pulse_width() {
+    // 4 µs is below half a tick at 100 kHz
+    assert_eq!(
+        create_spwm(30_000, 4).err(),
+        Some(SpwmError::InvalidPulseWidth)
+    );
+    assert_eq!(
+        create_spwm(30_000, 40_000).err(),
+        Some(SpwmError::InvalidPulseWidth)
+    );
+    assert_eq!(create_spwm(0, 100).err(), Some(SpwmError::InvalidFrequency));
+}
+
+#[test]
+fn frame_rate_of_duty_cycle_channel_is_rejected() {
+    let mut spwm = Spwm::<1>::new(100_000);
+    let channel = spwm
+        .create_channel()
+        .freq_hz(100)
+        .duty_cycle(50)
+        .on_off_callback(|_| {})
+        .period_callback(|| {})
+        .build()
+        .unwrap();
+    let id = spwm.register_channel(channel).unwrap();
+
+    assert_eq!(spwm.set_frame_rate(id, 30_000), Err(SpwmError::InvalidMode));
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "watch")]
+
+use spwm::Spwm;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static EDGES: [AtomicU32; 4] = [const { AtomicU32::new(0) }; 4];
+
+#[test]
+fn watch_window_records_next_edges_and_disarms() {
+    let mut spwm = Spwm::<2>::new(100_000);
+
+    for duty_cycle in [30, 50] {
+        let channel = spwm
+            .create_channel()
+            .freq_hz(1000)
+            .duty_cycle(duty_cycle)
+            .on_off_callback(|_| {})
+            .period_callback(|| {})
+            .build()
+            .unwrap();
+        spwm.register_channel(channel).unwrap();
+    }
+
+    spwm.get_channel(0).unwrap().enable().unwrap();
+    spwm.get_channel(1).unwrap().enable().unwrap();
+
+    for _ in 0..50 {
+        spwm.irq_handler();
+    }
+
+    spwm.arm_watch(1, &EDGES).unwrap();
+    assert!(spwm.is_watch_armed());
+
+    for _ in 0..400 {
+        spwm.irq_handler();
+    }
+
+    let edges: Vec<u32> = EDGES
+        .iter()
+        .map(|edge| edge.load(Ordering::Relaxed))
+        .collect();
+
+    assert_eq!(edges, [100, 150, 200, 250]);
+    assert_eq!(spwm.watch_count(), 4);
+    assert!(!spwm.is_watch_armed());
+
+    spwm.arm_watch(0, &EDGES).unwrap();
+
+    for _ in 0..100 {
+        spwm.irq_handler();
+    }
+
+    spwm.disarm_watch();
+    assert_eq!(spwm.watch_count(), 2);
+    assert!(!spwm.is_watch_armed());
+    assert_eq!(EDGES[0].load(Ordering::Relaxed), 500);
+    assert_eq!(EDGES[1].load(Ordering::Relaxed), 530);
+    assert_eq!(
+        spwm.arm_watch(2, &EDGES),
+        Err(spwm::SpwmError::InvalidChannel)
+    );
+}